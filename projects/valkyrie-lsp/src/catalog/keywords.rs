@@ -0,0 +1,252 @@
+use super::KeywordDoc;
+use crate::SyntaxKind;
+
+/// All keywords, in the order of [`SyntaxKind::KEYWORDS`].
+pub const KEYWORD_DOCS: &[KeywordDoc] = &[
+    KeywordDoc {
+        kind: SyntaxKind::NamespaceKw,
+        summary: "Declares the namespace of the following items, for the rest of the file or inside the braces.",
+        syntax: "namespace path;\nnamespace path { items }",
+        example: "namespace geometry::shapes;",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::UsingKw,
+        summary: "Brings items of another namespace into scope, optionally renamed.",
+        syntax: "using path;\nusing path::{name, name as alias};\nusing path::*;",
+        example: "using std::collections::{List, Map as Dict};",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::AsKw,
+        summary: "Renames an imported item, or converts a value to another type.",
+        syntax: "using path as alias;\nexpression as Type",
+        example: "let ratio = count as f64 / total as f64;",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::ClassKw,
+        summary: "Declares a class, a nominal type with fields and methods passed by reference.",
+        syntax: "class Name<T>: Super, Trait { fields methods }",
+        example: "class Point {\n    x: f64\n    y: f64\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::StructureKw,
+        summary: "Declares a structure, a class whose values are copied instead of shared.",
+        syntax: "structure Name<T>: Trait { fields methods }",
+        example: "structure Color {\n    r: u8\n    g: u8\n    b: u8\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::TraitKw,
+        summary: "Declares a trait, a set of methods a type can implement.",
+        syntax: "trait Name<T>: Super { methods }",
+        example: "trait Shape {\n    area(self) -> f64\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::UnionKw,
+        summary: "Declares a tagged union, a value that is exactly one of its variants.",
+        syntax: "union Name<T> { Variant, Variant(T) }",
+        example: "union Option<T> {\n    Some(T),\n    None,\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::ExtendsKw,
+        summary: "Adds methods to an existing type, optionally implementing a trait for it.",
+        syntax: "extends Type { methods }\nextends Type: Trait { methods }",
+        example: "extends String {\n    shout(self) -> String { self.upper() }\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::ImplyKw,
+        summary: "Implements a trait for a type.",
+        syntax: "imply Type: Trait { methods }",
+        example: "imply Circle: Shape {\n    area(self) -> f64 { PI * self.r ** 2 }\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::MicroKw,
+        summary: "Declares a function.",
+        syntax: "micro name<T>(parameters) -> Type { body }",
+        example: "micro square(x: i32) -> i32 {\n    x * x\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::LetKw,
+        summary: "Binds the value to a pattern, the type is inferred when omitted.",
+        syntax: "let pattern: Type = value;",
+        example: "let (x, mut y) = (1, 2);",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::TypeKw,
+        summary: "Declares another name for a type.",
+        syntax: "type Name<T> = Type;",
+        example: "type Pair<T> = (T, T);",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::NewKw,
+        summary: "Creates an instance of a class by calling its constructor.",
+        syntax: "new Type(arguments)",
+        example: "let origin = new Point(0.0, 0.0);",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::IfKw,
+        summary: "Evaluates a branch when the condition holds, `if` is an expression and has a value.",
+        syntax: "if condition { value } else { value }",
+        example: "let sign = if x < 0 { -1 } else { 1 };",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::ElseKw,
+        summary: "The branch of an `if` taken when the condition does not hold.",
+        syntax: "if condition { value } else if condition { value } else { value }",
+        example: "if a { 1 } else if b { 2 } else { 3 }",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::WhileKw,
+        summary: "Repeats the body as long as the condition holds.",
+        syntax: "while condition { body }",
+        example: "while i < 10 {\n    i += 1\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::LoopKw,
+        summary: "Repeats the body until a `break`, the value of the loop is the value given to `break`.",
+        syntax: "loop { body }",
+        example: "let line = loop {\n    if let Some(s) = read() { break s }\n};",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::ForKw,
+        summary: "Runs the body for each element of an iterable, bound to the pattern.",
+        syntax: "for pattern in iterable { body }",
+        example: "for i in 0..<10 {\n    print(i)\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::InKw,
+        summary: "Separates the pattern of a `for` loop from the iterated value.",
+        syntax: "for pattern in iterable { body }",
+        example: "for (key, value) in map { }",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::MatchKw,
+        summary: "Compares a value against patterns, the first matching arm is evaluated.",
+        syntax: "match value {\n    case pattern if guard => value\n}",
+        example: "match shape {\n    case Circle(r) => PI * r ** 2\n    case _ => 0.0\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::CaseKw,
+        summary: "Starts an arm of a `match`, with a pattern and an optional guard.",
+        syntax: "case pattern if guard => value",
+        example: "case Some(x) if x > 0 => x",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::ReturnKw,
+        summary: "Leaves the current function with a value, or with `()` if omitted.",
+        syntax: "return value",
+        example: "if list.empty() { return None }",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::BreakKw,
+        summary: "Leaves the innermost loop, a `loop` takes the value given to it.",
+        syntax: "break\nbreak value",
+        example: "loop {\n    if done() { break }\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::ContinueKw,
+        summary: "Skips the rest of the body and starts the next iteration of the innermost loop.",
+        syntax: "continue",
+        example: "for x in items {\n    if x < 0 { continue }\n    sum += x\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::YieldKw,
+        summary: "Produces the next value of a generator and suspends it until the next one is requested.",
+        syntax: "yield value",
+        example: "micro naturals() {\n    let mut n = 0;\n    loop { yield n; n += 1 }\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::RaiseKw,
+        summary: "Throws an error, unwinding until a `catch` handles it.",
+        syntax: "raise error",
+        example: "if x < 0 { raise new ValueError(\"negative\") }",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::TryKw,
+        summary: "Evaluates a block, handling the errors raised inside with the `catch` clauses.",
+        syntax: "try { body } catch pattern { handler }",
+        example: "try {\n    parse(text)\n} catch e {\n    default()\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::CatchKw,
+        summary: "Handles the errors raised in a `try` block that match the pattern.",
+        syntax: "catch pattern { handler }",
+        example: "catch e { log(e) }",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::IsKw,
+        summary: "Checks if a value has a type, the result is a boolean.",
+        syntax: "expression is Type",
+        example: "if shape is Circle { }",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::NotKw,
+        summary: "Negates a boolean, the same as `!`.",
+        syntax: "not expression",
+        example: "while not done { }",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::TrueKw,
+        summary: "The boolean value true.",
+        syntax: "true",
+        example: "let mut running = true;",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::FalseKw,
+        summary: "The boolean value false.",
+        syntax: "false",
+        example: "let mut done = false;",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::NullKw,
+        summary: "The absent value of a nullable type.",
+        syntax: "null",
+        example: "let name = user?.name ?? null;",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::SelfKw,
+        summary: "The receiver of a method.",
+        syntax: "self\nself.member",
+        example: "length(self) -> f64 {\n    (self.x ** 2 + self.y ** 2).sqrt()\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::SelfTypeKw,
+        summary: "The type being declared or implemented.",
+        syntax: "Self",
+        example: "trait Clone {\n    clone(self) -> Self\n}",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::PublicKw,
+        summary: "Makes a declaration visible everywhere.",
+        syntax: "public declaration",
+        example: "public micro area(self) -> f64 { }",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::PrivateKw,
+        summary: "Makes a declaration visible only inside its enclosing type or namespace.",
+        syntax: "private declaration",
+        example: "private cache: Map<String, i32>",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::ProtectedKw,
+        summary: "Makes a declaration visible inside its enclosing type and the types extending it.",
+        syntax: "protected declaration",
+        example: "protected micro validate(self) { }",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::StaticKw,
+        summary: "Makes a member belong to the type instead of its instances.",
+        syntax: "static declaration",
+        example: "static micro origin() -> Point { new Point(0.0, 0.0) }",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::FinalKw,
+        summary: "Prevents a class from being extended or a method from being overridden.",
+        syntax: "final declaration",
+        example: "final class Token { }",
+    },
+    KeywordDoc {
+        kind: SyntaxKind::MutKw,
+        summary: "Allows a binding or a field to be assigned again.",
+        syntax: "let mut name = value;",
+        example: "let mut count = 0;\ncount += 1;",
+    },
+];
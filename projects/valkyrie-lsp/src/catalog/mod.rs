@@ -0,0 +1,149 @@
+pub use self::{keywords::KEYWORD_DOCS, operators::OPERATOR_DOCS};
+use crate::{DocumentRenderer, SyntaxKind};
+
+mod keywords;
+mod operators;
+
+/// The documentation of a keyword.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct KeywordDoc {
+    /// The keyword token.
+    pub kind: SyntaxKind,
+    /// One sentence on what the keyword does.
+    pub summary: &'static str,
+    /// The grammar of the construct the keyword introduces.
+    pub syntax: &'static str,
+    /// A short use in Valkyrie code.
+    pub example: &'static str,
+}
+
+/// The documentation of an operator in one position.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct OperatorDoc {
+    /// The spelling, such as `+` or `>>=`.
+    pub symbol: &'static str,
+    /// A short name, such as `addition`.
+    pub name: &'static str,
+    /// Where the operator stands relative to its operands.
+    pub fixity: Fixity,
+    /// The precedence level, higher binds tighter, assignments are 1 and postfix operators 17.
+    pub precedence: u8,
+    /// How a chain of operators of the same level groups.
+    pub associativity: Associativity,
+    /// The trait method the operator is rewritten to, none for built-in control flow.
+    pub desugar: Option<&'static str>,
+    /// One sentence on what the operator does.
+    pub summary: &'static str,
+    /// A short use in Valkyrie code.
+    pub example: &'static str,
+}
+
+/// Where an operator stands relative to its operands.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Fixity {
+    /// Before the operand, `-a`.
+    Prefix,
+    /// Between the operands, `a - b`.
+    Infix,
+    /// After the operand, `a?`.
+    Postfix,
+}
+
+/// How a chain of operators of the same precedence groups.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum Associativity {
+    /// `a - b - c` is `(a - b) - c`.
+    Left,
+    /// `a ** b ** c` is `a ** (b ** c)`.
+    Right,
+    /// Takes a single operand.
+    None,
+}
+
+impl KeywordDoc {
+    /// Find the documentation of a keyword token.
+    pub fn find(kind: SyntaxKind) -> Option<&'static KeywordDoc> {
+        KEYWORD_DOCS.iter().find(|doc| doc.kind == kind)
+    }
+    /// The spelling of the keyword.
+    pub fn keyword(&self) -> &'static str {
+        self.kind.as_str().unwrap_or_default()
+    }
+    /// Write the documentation shown on hover.
+    pub fn render(&self, out: &mut dyn DocumentRenderer) {
+        out.write_keyword("keyword");
+        out.write_text(" ");
+        out.write_attribute(self.keyword());
+        paragraph(out);
+        out.write_markdown(self.summary);
+        paragraph(out);
+        out.write_code(self.syntax);
+        example(out, self.example);
+    }
+}
+
+impl OperatorDoc {
+    /// Find the documentation of an operator in a position.
+    pub fn find(symbol: &str, fixity: Fixity) -> Option<&'static OperatorDoc> {
+        OPERATOR_DOCS.iter().find(|doc| doc.symbol == symbol && doc.fixity == fixity)
+    }
+    /// Write the documentation shown on hover.
+    pub fn render(&self, out: &mut dyn DocumentRenderer) {
+        out.write_keyword("operator");
+        out.write_text(" ");
+        out.write_operator(self.symbol);
+        out.write_text(" ");
+        out.write_text(self.name);
+        paragraph(out);
+        out.write_markdown(self.summary);
+        paragraph(out);
+        out.write_text(&format!("- fixity: {}", self.fixity.as_str()));
+        out.write_newline();
+        out.write_text(&format!("- precedence: {}", self.precedence));
+        out.write_newline();
+        out.write_text(&format!("- associativity: {}", self.associativity.as_str()));
+        out.write_newline();
+        match self.desugar {
+            Some(path) => {
+                out.write_text("- desugars to: ");
+                out.write_function(path);
+            }
+            None => out.write_text("- built-in, can not be overloaded"),
+        }
+        example(out, self.example);
+    }
+}
+
+impl Fixity {
+    /// The lowercase name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Fixity::Prefix => "prefix",
+            Fixity::Infix => "infix",
+            Fixity::Postfix => "postfix",
+        }
+    }
+}
+
+impl Associativity {
+    /// The lowercase name.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Associativity::Left => "left",
+            Associativity::Right => "right",
+            Associativity::None => "none",
+        }
+    }
+}
+
+fn paragraph(out: &mut dyn DocumentRenderer) {
+    out.write_newline();
+    out.write_newline();
+}
+
+fn example(out: &mut dyn DocumentRenderer, code: &str) {
+    paragraph(out);
+    out.write_keyword("example");
+    paragraph(out);
+    out.write_code(code);
+}
@@ -0,0 +1,439 @@
+use super::{
+    Associativity::{Left, None as Unary, Right},
+    Fixity::{Infix, Postfix, Prefix},
+    OperatorDoc,
+};
+
+/// All operators, by precedence from the loosest, the levels mirror the binding powers of the parser.
+pub const OPERATOR_DOCS: &[OperatorDoc] = &[
+    OperatorDoc {
+        symbol: "=",
+        name: "assignment",
+        fixity: Infix,
+        precedence: 1,
+        associativity: Right,
+        desugar: None,
+        summary: "Stores the value on the right in the place on the left, the place must be mutable.",
+        example: "x = y = 0",
+    },
+    OperatorDoc {
+        symbol: "+=",
+        name: "addition assignment",
+        fixity: Infix,
+        precedence: 1,
+        associativity: Right,
+        desugar: Some("std::ops::AddAssign::add_assign"),
+        summary: "Adds the value on the right to the place on the left.",
+        example: "total += price",
+    },
+    OperatorDoc {
+        symbol: "-=",
+        name: "subtraction assignment",
+        fixity: Infix,
+        precedence: 1,
+        associativity: Right,
+        desugar: Some("std::ops::SubAssign::sub_assign"),
+        summary: "Subtracts the value on the right from the place on the left.",
+        example: "lives -= 1",
+    },
+    OperatorDoc {
+        symbol: "*=",
+        name: "multiplication assignment",
+        fixity: Infix,
+        precedence: 1,
+        associativity: Right,
+        desugar: Some("std::ops::MulAssign::mul_assign"),
+        summary: "Multiplies the place on the left by the value on the right.",
+        example: "scale *= 2",
+    },
+    OperatorDoc {
+        symbol: "/=",
+        name: "division assignment",
+        fixity: Infix,
+        precedence: 1,
+        associativity: Right,
+        desugar: Some("std::ops::DivAssign::div_assign"),
+        summary: "Divides the place on the left by the value on the right.",
+        example: "width /= 2",
+    },
+    OperatorDoc {
+        symbol: "%=",
+        name: "remainder assignment",
+        fixity: Infix,
+        precedence: 1,
+        associativity: Right,
+        desugar: Some("std::ops::RemAssign::rem_assign"),
+        summary: "Replaces the place on the left by its remainder after division by the value on the right.",
+        example: "angle %= 360",
+    },
+    OperatorDoc {
+        symbol: "^=",
+        name: "bitwise xor assignment",
+        fixity: Infix,
+        precedence: 1,
+        associativity: Right,
+        desugar: Some("std::ops::BitXorAssign::bitxor_assign"),
+        summary: "Flips the bits of the place on the left that are set on the right.",
+        example: "flags ^= MASK",
+    },
+    OperatorDoc {
+        symbol: "&=",
+        name: "bitwise and assignment",
+        fixity: Infix,
+        precedence: 1,
+        associativity: Right,
+        desugar: Some("std::ops::BitAndAssign::bitand_assign"),
+        summary: "Clears the bits of the place on the left that are not set on the right.",
+        example: "flags &= MASK",
+    },
+    OperatorDoc {
+        symbol: "|=",
+        name: "bitwise or assignment",
+        fixity: Infix,
+        precedence: 1,
+        associativity: Right,
+        desugar: Some("std::ops::BitOrAssign::bitor_assign"),
+        summary: "Sets the bits of the place on the left that are set on the right.",
+        example: "flags |= MASK",
+    },
+    OperatorDoc {
+        symbol: "<<=",
+        name: "left shift assignment",
+        fixity: Infix,
+        precedence: 1,
+        associativity: Right,
+        desugar: Some("std::ops::ShlAssign::shl_assign"),
+        summary: "Shifts the bits of the place on the left to the left by the value on the right.",
+        example: "mask <<= 1",
+    },
+    OperatorDoc {
+        symbol: ">>=",
+        name: "right shift assignment",
+        fixity: Infix,
+        precedence: 1,
+        associativity: Right,
+        desugar: Some("std::ops::ShrAssign::shr_assign"),
+        summary: "Shifts the bits of the place on the left to the right by the value on the right.",
+        example: "mask >>= 1",
+    },
+    OperatorDoc {
+        symbol: "|>",
+        name: "pipe",
+        fixity: Infix,
+        precedence: 2,
+        associativity: Left,
+        desugar: None,
+        summary: "Calls the function on the right with the value on the left as its first argument.",
+        example: "text |> trim |> parse",
+    },
+    OperatorDoc {
+        symbol: "..",
+        name: "inclusive range",
+        fixity: Infix,
+        precedence: 3,
+        associativity: Left,
+        desugar: Some("std::ops::Range::inclusive"),
+        summary: "The values from the left bound up to and including the right bound.",
+        example: "for i in 1..10 { }",
+    },
+    OperatorDoc {
+        symbol: "..<",
+        name: "exclusive range",
+        fixity: Infix,
+        precedence: 3,
+        associativity: Left,
+        desugar: Some("std::ops::Range::exclusive"),
+        summary: "The values from the left bound up to but excluding the right bound.",
+        example: "for i in 0..<list.length { }",
+    },
+    OperatorDoc {
+        symbol: "??",
+        name: "null coalescing",
+        fixity: Infix,
+        precedence: 4,
+        associativity: Right,
+        desugar: None,
+        summary: "The value on the left unless it is null, then the value on the right, which is only evaluated if needed.",
+        example: "let name = user?.name ?? \"anonymous\";",
+    },
+    OperatorDoc {
+        symbol: "||",
+        name: "logical or",
+        fixity: Infix,
+        precedence: 5,
+        associativity: Left,
+        desugar: None,
+        summary: "True if either side is true, the right side is only evaluated if the left side is false.",
+        example: "if empty || done { }",
+    },
+    OperatorDoc {
+        symbol: "&&",
+        name: "logical and",
+        fixity: Infix,
+        precedence: 6,
+        associativity: Left,
+        desugar: None,
+        summary: "True if both sides are true, the right side is only evaluated if the left side is true.",
+        example: "if x > 0 && x < 10 { }",
+    },
+    OperatorDoc {
+        symbol: "==",
+        name: "equality",
+        fixity: Infix,
+        precedence: 7,
+        associativity: Left,
+        desugar: Some("std::ops::Equal::equal"),
+        summary: "True if both sides are equal.",
+        example: "if name == \"main\" { }",
+    },
+    OperatorDoc {
+        symbol: "!=",
+        name: "inequality",
+        fixity: Infix,
+        precedence: 7,
+        associativity: Left,
+        desugar: Some("std::ops::Equal::not_equal"),
+        summary: "True if the sides are not equal.",
+        example: "while line != null { }",
+    },
+    OperatorDoc {
+        symbol: "<",
+        name: "less than",
+        fixity: Infix,
+        precedence: 7,
+        associativity: Left,
+        desugar: Some("std::ops::Compare::less"),
+        summary: "True if the left side orders before the right side.",
+        example: "if i < length { }",
+    },
+    OperatorDoc {
+        symbol: ">",
+        name: "greater than",
+        fixity: Infix,
+        precedence: 7,
+        associativity: Left,
+        desugar: Some("std::ops::Compare::greater"),
+        summary: "True if the left side orders after the right side.",
+        example: "if score > best { }",
+    },
+    OperatorDoc {
+        symbol: "<=",
+        name: "less or equal",
+        fixity: Infix,
+        precedence: 7,
+        associativity: Left,
+        desugar: Some("std::ops::Compare::less_equal"),
+        summary: "True if the left side orders before or equal to the right side.",
+        example: "if age <= 12 { }",
+    },
+    OperatorDoc {
+        symbol: ">=",
+        name: "greater or equal",
+        fixity: Infix,
+        precedence: 7,
+        associativity: Left,
+        desugar: Some("std::ops::Compare::greater_equal"),
+        summary: "True if the left side orders after or equal to the right side.",
+        example: "if age >= 18 { }",
+    },
+    OperatorDoc {
+        symbol: "is",
+        name: "type test",
+        fixity: Infix,
+        precedence: 7,
+        associativity: Left,
+        desugar: None,
+        summary: "True if the value on the left has the type on the right.",
+        example: "if shape is Circle { }",
+    },
+    OperatorDoc {
+        symbol: "|",
+        name: "bitwise or",
+        fixity: Infix,
+        precedence: 8,
+        associativity: Left,
+        desugar: Some("std::ops::BitOr::bitor"),
+        summary: "The bits set on either side.",
+        example: "let flags = READ | WRITE;",
+    },
+    OperatorDoc {
+        symbol: "^",
+        name: "bitwise xor",
+        fixity: Infix,
+        precedence: 9,
+        associativity: Left,
+        desugar: Some("std::ops::BitXor::bitxor"),
+        summary: "The bits set on exactly one side.",
+        example: "let changed = old ^ new;",
+    },
+    OperatorDoc {
+        symbol: "&",
+        name: "bitwise and",
+        fixity: Infix,
+        precedence: 10,
+        associativity: Left,
+        desugar: Some("std::ops::BitAnd::bitand"),
+        summary: "The bits set on both sides.",
+        example: "let low = value & 0xFF;",
+    },
+    OperatorDoc {
+        symbol: "<<",
+        name: "left shift",
+        fixity: Infix,
+        precedence: 11,
+        associativity: Left,
+        desugar: Some("std::ops::Shl::shl"),
+        summary: "Shifts the bits of the left side to the left by the right side.",
+        example: "let bit = 1 << index;",
+    },
+    OperatorDoc {
+        symbol: ">>",
+        name: "right shift",
+        fixity: Infix,
+        precedence: 11,
+        associativity: Left,
+        desugar: Some("std::ops::Shr::shr"),
+        summary: "Shifts the bits of the left side to the right by the right side.",
+        example: "let high = value >> 8;",
+    },
+    OperatorDoc {
+        symbol: "+",
+        name: "addition",
+        fixity: Infix,
+        precedence: 12,
+        associativity: Left,
+        desugar: Some("std::ops::Add::add"),
+        summary: "Adds numbers or concatenates sequences.",
+        example: "let total = price + tax;",
+    },
+    OperatorDoc {
+        symbol: "-",
+        name: "subtraction",
+        fixity: Infix,
+        precedence: 12,
+        associativity: Left,
+        desugar: Some("std::ops::Sub::sub"),
+        summary: "Subtracts the right side from the left side.",
+        example: "let rest = total - paid;",
+    },
+    OperatorDoc {
+        symbol: "*",
+        name: "multiplication",
+        fixity: Infix,
+        precedence: 13,
+        associativity: Left,
+        desugar: Some("std::ops::Mul::mul"),
+        summary: "Multiplies both sides.",
+        example: "let area = width * height;",
+    },
+    OperatorDoc {
+        symbol: "/",
+        name: "division",
+        fixity: Infix,
+        precedence: 13,
+        associativity: Left,
+        desugar: Some("std::ops::Div::div"),
+        summary: "Divides the left side by the right side, integers round towards zero.",
+        example: "let mean = sum / count;",
+    },
+    OperatorDoc {
+        symbol: "%",
+        name: "remainder",
+        fixity: Infix,
+        precedence: 13,
+        associativity: Left,
+        desugar: Some("std::ops::Rem::rem"),
+        summary: "The remainder of dividing the left side by the right side, with the sign of the left side.",
+        example: "let even = n % 2 == 0;",
+    },
+    OperatorDoc {
+        symbol: "-",
+        name: "negation",
+        fixity: Prefix,
+        precedence: 14,
+        associativity: Unary,
+        desugar: Some("std::ops::Neg::neg"),
+        summary: "Flips the sign of a number.",
+        example: "let down = -speed;",
+    },
+    OperatorDoc {
+        symbol: "!",
+        name: "logical not",
+        fixity: Prefix,
+        precedence: 14,
+        associativity: Unary,
+        desugar: Some("std::ops::Not::not"),
+        summary: "Negates a boolean.",
+        example: "if !list.empty() { }",
+    },
+    OperatorDoc {
+        symbol: "not",
+        name: "logical not",
+        fixity: Prefix,
+        precedence: 14,
+        associativity: Unary,
+        desugar: Some("std::ops::Not::not"),
+        summary: "Negates a boolean, the same as `!`.",
+        example: "while not done { }",
+    },
+    OperatorDoc {
+        symbol: "~",
+        name: "bitwise not",
+        fixity: Prefix,
+        precedence: 14,
+        associativity: Unary,
+        desugar: Some("std::ops::BitNot::bitnot"),
+        summary: "Flips all bits of an integer.",
+        example: "let cleared = flags & ~MASK;",
+    },
+    OperatorDoc {
+        symbol: "**",
+        name: "power",
+        fixity: Infix,
+        precedence: 15,
+        associativity: Right,
+        desugar: Some("std::ops::Pow::pow"),
+        summary: "Raises the left side to the power of the right side, binds tighter than a prefix minus on its left.",
+        example: "let area = PI * r ** 2;",
+    },
+    OperatorDoc {
+        symbol: "as",
+        name: "conversion",
+        fixity: Infix,
+        precedence: 16,
+        associativity: Left,
+        desugar: None,
+        summary: "Converts the value on the left to the type on the right.",
+        example: "let ratio = count as f64 / total as f64;",
+    },
+    OperatorDoc {
+        symbol: ".",
+        name: "member access",
+        fixity: Postfix,
+        precedence: 17,
+        associativity: Left,
+        desugar: None,
+        summary: "Reads a field or calls a method of the value on the left.",
+        example: "let length = self.items.length;",
+    },
+    OperatorDoc {
+        symbol: "?.",
+        name: "optional member access",
+        fixity: Postfix,
+        precedence: 17,
+        associativity: Left,
+        desugar: None,
+        summary: "Reads a field or calls a method unless the value on the left is null, the result is then null.",
+        example: "let city = user?.address?.city;",
+    },
+    OperatorDoc {
+        symbol: "?",
+        name: "error propagation",
+        fixity: Postfix,
+        precedence: 17,
+        associativity: Left,
+        desugar: Some("std::ops::Try::branch"),
+        summary: "Unwraps a success value, or returns the failure from the current function.",
+        example: "let config = read(path)?;",
+    },
+];
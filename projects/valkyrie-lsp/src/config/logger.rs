@@ -0,0 +1,32 @@
+use std::{
+    fmt::Display,
+    sync::{Arc, RwLock},
+};
+
+use tower_lsp::{Client, lsp_types::MessageType};
+
+use super::LogLevel;
+
+/// Writes to the client log, dropping the messages below the configured `valkyrie.log.level`.
+#[derive(Clone, Debug)]
+pub(crate) struct Logger {
+    client: Client,
+    level: Arc<RwLock<LogLevel>>,
+}
+
+impl Logger {
+    pub fn new(client: Client) -> Self {
+        Self { client, level: Arc::default() }
+    }
+    /// Change the least severe messages written.
+    pub fn set_level(&self, level: LogLevel) {
+        *self.level.write().unwrap() = level;
+    }
+    /// Write a message unless its type is below the level.
+    pub async fn log(&self, kind: MessageType, message: impl Display) {
+        let allowed = self.level.read().unwrap().allows(kind);
+        if allowed {
+            self.client.log_message(kind, message).await;
+        }
+    }
+}
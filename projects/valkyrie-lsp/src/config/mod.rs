@@ -0,0 +1,302 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tower_lsp::lsp_types::{MessageType, Url};
+
+pub(crate) use self::logger::Logger;
+use crate::{Color, ColorSchema, DiagnosticCode, ValkyrieResult};
+
+mod logger;
+
+/// The section of the client settings read by the server.
+pub const CONFIGURATION_SECTION: &str = "valkyrie";
+
+/// The settings of the whole client and of each workspace folder.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Configuration {
+    /// The settings outside of all folders.
+    pub global: Settings,
+    /// The settings of each workspace folder by its root.
+    pub folders: BTreeMap<PathBuf, Settings>,
+}
+
+/// The `valkyrie.*` settings, missing keys keep their defaults.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct Settings {
+    /// `valkyrie.inlayHints`
+    pub inlay_hints: InlayHintSettings,
+    /// `valkyrie.lints`, the level of diagnostics by code or name, such as `"duplicate-type": "allow"`.
+    pub lints: BTreeMap<String, LintLevel>,
+    /// `valkyrie.formatter`
+    pub formatter: FormatterSettings,
+    /// `valkyrie.diagnostics`
+    pub diagnostics: DiagnosticSettings,
+    /// `valkyrie.log`
+    pub log: LogSettings,
+    /// `valkyrie.theme`, the colors of HTML documentation rendered through the API, the server does not read it.
+    pub theme: ThemeSettings,
+}
+
+/// Which inlay hints are shown.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct InlayHintSettings {
+    /// Show any inlay hints.
+    pub enable: bool,
+    /// Show the inferred types of bindings.
+    pub type_hints: bool,
+    /// Show the parameter names of arguments.
+    pub parameter_hints: bool,
+    /// Truncate hints longer than this many chars, no limit if null.
+    pub max_length: Option<u32>,
+}
+
+/// The level a diagnostic is reported at, overriding its default severity.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LintLevel {
+    /// Not reported.
+    Allow,
+    /// Reported as a warning.
+    Warn,
+    /// Reported as an error.
+    Deny,
+}
+
+/// The layout of formatted code.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct FormatterSettings {
+    /// The width of one indentation level.
+    pub indent_width: u32,
+    /// Indent with tabs instead of spaces.
+    pub use_tabs: bool,
+    /// The line width to wrap at.
+    pub max_width: u32,
+}
+
+/// When diagnostics are computed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct DiagnosticSettings {
+    /// How long to wait after the last change before publishing, in milliseconds.
+    pub debounce: u64,
+}
+
+/// What the server writes to the client log.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct LogSettings {
+    /// The least severe messages written.
+    pub level: LogLevel,
+}
+
+/// The severity of log messages, from the most severe.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Ord, PartialOrd, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LogLevel {
+    /// Only errors.
+    Error,
+    /// Errors and warnings.
+    Warning,
+    /// Errors, warnings and information.
+    #[default]
+    Info,
+    /// Everything.
+    Log,
+}
+
+/// The colors of rendered documentation.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(default, rename_all = "camelCase")]
+pub struct ThemeSettings {
+    /// Whether the editor theme is dark or light, picks the preset the other settings start from.
+    pub kind: ThemeKind,
+    /// A VS Code color theme or a JetBrains color scheme, overrides the kind, relative to the workspace folders.
+    pub path: Option<PathBuf>,
+    /// The colors of single categories, such as `"keyword": "#C679DD"`.
+    pub colors: BTreeMap<String, Color>,
+}
+
+/// The brightness of the editor theme.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ThemeKind {
+    /// Light text on a dark background.
+    #[default]
+    Dark,
+    /// Dark text on a light background.
+    Light,
+}
+
+impl Default for InlayHintSettings {
+    fn default() -> Self {
+        Self { enable: true, type_hints: true, parameter_hints: true, max_length: Some(25) }
+    }
+}
+
+impl Default for FormatterSettings {
+    fn default() -> Self {
+        Self { indent_width: 4, use_tabs: false, max_width: 120 }
+    }
+}
+
+impl Default for DiagnosticSettings {
+    fn default() -> Self {
+        Self { debounce: 200 }
+    }
+}
+
+impl Configuration {
+    /// The settings of the file, those of the innermost folder containing it.
+    pub fn settings(&self, uri: &Url) -> &Settings {
+        let path = match uri.to_file_path() {
+            Ok(s) => s,
+            Err(_) => return &self.global,
+        };
+        let folder = self.folders.iter().filter(|(root, _)| path.starts_with(root)).max_by_key(|(root, _)| depth(root));
+        match folder {
+            Some((_, settings)) => settings,
+            None => &self.global,
+        }
+    }
+}
+
+impl Settings {
+    /// Override these settings with a `valkyrie` section, returns the problems found.
+    ///
+    /// Unknown keys and invalid values are reported and leave the old value in place.
+    pub fn merge(&self, section: &Value) -> (Settings, Vec<String>) {
+        let mut problems = vec![];
+        let mut current = serde_json::to_value(self).unwrap_or_default();
+        let mut leaves = vec![];
+        collect_leaves(&current, section, &mut vec![], &mut leaves, &mut problems);
+        for (path, value) in leaves {
+            let mut candidate = current.clone();
+            let mut slot = &mut candidate;
+            for key in &path {
+                slot = &mut slot[key.as_str()];
+            }
+            *slot = value;
+            match serde_json::from_value::<Settings>(candidate.clone()) {
+                Ok(_) => current = candidate,
+                Err(e) => problems.push(format!("invalid value for `{}`: {}", dotted(&path), e)),
+            }
+        }
+        (serde_json::from_value(current).unwrap_or_else(|_| self.clone()), problems)
+    }
+    /// The level configured for a lint, by its code or its name, none for other diagnostics.
+    pub fn lint_level(&self, code: &DiagnosticCode) -> Option<LintLevel> {
+        if !code.lint {
+            return None;
+        }
+        self.lints.iter().find(|(key, _)| DiagnosticCode::find(key) == Some(code)).map(|(_, level)| *level)
+    }
+}
+
+impl ThemeSettings {
+    /// The color schema, from the preset of the kind or the theme file, then the single colors.
+    ///
+    /// A relative theme file is searched in the workspace folders.
+    pub fn schema(&self, roots: &[PathBuf]) -> ValkyrieResult<ColorSchema> {
+        let mut schema = match (&self.path, self.kind) {
+            (Some(path), _) => ColorSchema::load_theme(&theme_path(path, roots))?,
+            (None, ThemeKind::Dark) => ColorSchema::DARK,
+            (None, ThemeKind::Light) => ColorSchema::LIGHT,
+        };
+        for (category, color) in &self.colors {
+            if let Some(slot) = schema.category_mut(category) {
+                *slot = *color;
+            }
+        }
+        Ok(schema)
+    }
+}
+
+/// A relative path in the first workspace folder containing it, in the first folder if none does.
+fn theme_path(path: &Path, roots: &[PathBuf]) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+    let candidates: Vec<PathBuf> = roots.iter().map(|root| root.join(path)).collect();
+    match candidates.iter().find(|candidate| candidate.exists()).or(candidates.first()) {
+        Some(s) => s.clone(),
+        None => path.to_path_buf(),
+    }
+}
+
+impl LogLevel {
+    /// Check if messages of the type are written at this level.
+    pub fn allows(&self, kind: MessageType) -> bool {
+        let level = match kind {
+            MessageType::ERROR => LogLevel::Error,
+            MessageType::WARNING => LogLevel::Warning,
+            MessageType::INFO => LogLevel::Info,
+            _ => LogLevel::Log,
+        };
+        level <= *self
+    }
+}
+
+/// The `valkyrie` section of client settings, either nested under the key or given directly.
+pub fn configuration_section(settings: &Value) -> &Value {
+    settings.get(CONFIGURATION_SECTION).unwrap_or(settings)
+}
+
+/// Pair each known key of the section with its new value.
+///
+/// The keys of the lints are the diagnostic codes, the keys of the theme colors are the color categories.
+fn collect_leaves(
+    known: &Value,
+    section: &Value,
+    path: &mut Vec<String>,
+    out: &mut Vec<(Vec<String>, Value)>,
+    problems: &mut Vec<String>,
+) {
+    let entries = match section {
+        Value::Object(s) => s,
+        Value::Null => return,
+        _ => {
+            problems.push(format!("invalid value for `{}`: expected an object", dotted(path)));
+            return;
+        }
+    };
+    for (key, value) in entries {
+        path.push(key.clone());
+        let lint = path.len() == 2 && path[0] == "lints";
+        let color = path.len() == 3 && path[0] == "theme" && path[1] == "colors";
+        match known.get(key) {
+            _ if lint => match DiagnosticCode::find(key) {
+                Some(code) if code.lint => out.push((path.clone(), value.clone())),
+                Some(code) => problems.push(format!("`{}` is not a lint, its level can not be changed", code.code)),
+                None => problems.push(format!("unknown lint `{}`", key)),
+            },
+            _ if color && !ColorSchema::CATEGORIES.contains(&key.as_str()) => {
+                problems.push(format!("unknown color category `{}`", key))
+            }
+            _ if color => out.push((path.clone(), value.clone())),
+            Some(inner @ Value::Object(_)) => collect_leaves(inner, value, path, out, problems),
+            Some(_) => out.push((path.clone(), value.clone())),
+            None => problems.push(format!("unknown setting `{}`", dotted(path))),
+        }
+        path.pop();
+    }
+}
+
+fn dotted(path: &[String]) -> String {
+    let mut out = CONFIGURATION_SECTION.to_string();
+    for key in path {
+        out.push('.');
+        out.push_str(key);
+    }
+    out
+}
+
+fn depth(path: &Path) -> usize {
+    path.components().count()
+}
@@ -0,0 +1,480 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    sync::{Arc, Mutex, RwLock},
+};
+
+use tower_lsp::lsp_types::Url;
+
+use crate::{
+    Configuration, FileDiagnostic, PackageGraph, PackageId, Parse, ParsedManifest,
+    diagnostics::file_diagnostics,
+    semantic::{DefMap, FileScope, InferenceResult, ItemSourceMap, ItemTree, infer_file},
+};
+
+/// A file known to the [Database], interned from its uri.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct FileId(u32);
+
+/// The inputs of the server and all results derived from them, memoized by revision.
+///
+/// Inputs are only changed through `&mut self`, every change starts a new revision. Derived results are recomputed
+/// lazily when one of the inputs they read changed, a result equal to the previous one does not invalidate its
+/// dependents.
+#[derive(Debug, Default)]
+pub struct Database {
+    state: Arc<State>,
+}
+
+/// An immutable view of the [Database] at one revision, queries on it never see later changes.
+#[derive(Debug)]
+pub struct Snapshot {
+    state: Arc<State>,
+    /// The dependencies collected by each query currently being computed.
+    stack: Mutex<Vec<Vec<QueryKey>>>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    revision: u64,
+    interner: Arc<RwLock<FileInterner>>,
+    files: HashMap<FileId, Input<Option<FileInput>>>,
+    file_set: Input<Arc<Vec<FileId>>>,
+    manifests: HashMap<FileId, Input<Option<Arc<String>>>>,
+    manifest_set: Input<Arc<Vec<FileId>>>,
+    configuration: Input<Arc<Configuration>>,
+    memos: Mutex<HashMap<QueryKey, Memo>>,
+}
+
+#[derive(Debug, Default)]
+struct FileInterner {
+    ids: HashMap<Url, FileId>,
+    urls: Vec<Url>,
+}
+
+#[derive(Clone, Debug, Default)]
+struct Input<T> {
+    value: T,
+    changed_at: u64,
+}
+
+#[derive(Clone, Debug)]
+struct FileInput {
+    text: Arc<String>,
+    /// A tree of the text already parsed by the caller.
+    parse: Option<Arc<Parse>>,
+    /// The declarations of the text loaded from the index, so the text is only parsed when needed.
+    lowered: Option<(Arc<ItemTree>, Arc<ItemSourceMap>)>,
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+enum QueryKey {
+    FileText(FileId),
+    FileSet,
+    Manifest(FileId),
+    ManifestSet,
+    Configuration,
+    ParsedManifest(FileId),
+    PackageGraph,
+    Parse(FileId),
+    Lower(FileId),
+    ItemTree(FileId),
+    ItemSources(FileId),
+    DefMap,
+    FileScope(FileId),
+    Infer(FileId),
+    Diagnostics(FileId),
+}
+
+#[derive(Clone, Debug, Eq, PartialEq)]
+enum QueryValue {
+    ParsedManifest(Arc<ParsedManifest>),
+    PackageGraph(Arc<PackageGraph>),
+    Parse(Arc<Parse>),
+    Lower(Arc<ItemTree>, Arc<ItemSourceMap>),
+    ItemTree(Arc<ItemTree>),
+    ItemSources(Arc<ItemSourceMap>),
+    DefMap(Arc<DefMap>),
+    FileScope(Arc<FileScope>),
+    Infer(Arc<InferenceResult>),
+    Diagnostics(Arc<Vec<FileDiagnostic>>),
+}
+
+#[derive(Clone, Debug)]
+struct Memo {
+    value: QueryValue,
+    /// The revision the value last changed in.
+    changed_at: u64,
+    /// The revision the value was last known to be up to date in.
+    verified_at: u64,
+    deps: Arc<Vec<QueryKey>>,
+}
+
+impl Database {
+    /// The current revision, bumped on every input change.
+    pub fn revision(&self) -> u64 {
+        self.state.revision
+    }
+    /// Get the id of a known file.
+    pub fn file_id(&self, url: &Url) -> Option<FileId> {
+        self.state.interner.read().unwrap().ids.get(url).copied()
+    }
+    /// Take a snapshot of the current revision.
+    pub fn snapshot(&self) -> Snapshot {
+        Snapshot { state: self.state.clone(), stack: Mutex::new(vec![]) }
+    }
+    /// Set the text of a source file, `parse` is a tree of the text if the caller already has one.
+    pub fn set_file_text(&mut self, url: Url, text: String, parse: Option<Parse>) -> FileId {
+        self.set_file_input(url, FileInput { text: Arc::new(text), parse: parse.map(Arc::new), lowered: None })
+    }
+    /// Set the text of a source file with its declarations already known, from the on-disk index.
+    pub fn set_file_items(&mut self, url: Url, text: String, tree: ItemTree, sources: ItemSourceMap) -> FileId {
+        let lowered = Some((Arc::new(tree), Arc::new(sources)));
+        self.set_file_input(url, FileInput { text: Arc::new(text), parse: None, lowered })
+    }
+    fn set_file_input(&mut self, url: Url, input: FileInput) -> FileId {
+        let file = self.state.interner.write().unwrap().intern(url);
+        let unchanged = match self.state.files.get(&file) {
+            Some(Input { value: Some(old), .. }) => old.text == input.text,
+            _ => false,
+        };
+        if unchanged {
+            return file;
+        }
+        let state = self.next();
+        let revision = state.revision;
+        if state.files.insert(file, Input { value: Some(input), changed_at: revision }).and_then(|old| old.value).is_none() {
+            insert_sorted(&mut state.file_set, file, revision);
+        }
+        file
+    }
+    /// Forget a source file.
+    pub fn remove_file(&mut self, url: &Url) {
+        let file = match self.file_id(url) {
+            Some(s) => s,
+            None => return,
+        };
+        if !self.state.file_set.value.contains(&file) {
+            return;
+        }
+        let state = self.next();
+        let revision = state.revision;
+        state.files.insert(file, Input { value: None, changed_at: revision });
+        remove_sorted(&mut state.file_set, file, revision);
+    }
+    /// Set the text of a `fleet.json5` manifest.
+    pub fn set_manifest(&mut self, url: Url, text: String) -> FileId {
+        let file = self.state.interner.write().unwrap().intern(url);
+        if let Some(Input { value: Some(old), .. }) = self.state.manifests.get(&file) {
+            if **old == text {
+                return file;
+            }
+        }
+        let state = self.next();
+        let revision = state.revision;
+        if state
+            .manifests
+            .insert(file, Input { value: Some(Arc::new(text)), changed_at: revision })
+            .and_then(|old| old.value)
+            .is_none()
+        {
+            insert_sorted(&mut state.manifest_set, file, revision);
+        }
+        file
+    }
+    /// Forget a `fleet.json5` manifest.
+    pub fn remove_manifest(&mut self, url: &Url) {
+        let file = match self.file_id(url) {
+            Some(s) => s,
+            None => return,
+        };
+        if !self.state.manifest_set.value.contains(&file) {
+            return;
+        }
+        let state = self.next();
+        let revision = state.revision;
+        state.manifests.insert(file, Input { value: None, changed_at: revision });
+        remove_sorted(&mut state.manifest_set, file, revision);
+    }
+    /// Set the settings of the client and its workspace folders.
+    pub fn set_configuration(&mut self, configuration: Configuration) {
+        if *self.state.configuration.value == configuration {
+            return;
+        }
+        let state = self.next();
+        state.configuration = Input { value: Arc::new(configuration), changed_at: state.revision };
+    }
+    /// Start a new revision, snapshots taken before keep the old one.
+    fn next(&mut self) -> &mut State {
+        // without snapshots alive nobody sees the old revision, it is updated in place
+        if Arc::get_mut(&mut self.state).is_some() {
+            let state = Arc::get_mut(&mut self.state).expect("the state is not shared");
+            state.revision += 1;
+            return state;
+        }
+        let old = &self.state;
+        let state = State {
+            revision: old.revision + 1,
+            interner: old.interner.clone(),
+            files: old.files.clone(),
+            file_set: old.file_set.clone(),
+            manifests: old.manifests.clone(),
+            manifest_set: old.manifest_set.clone(),
+            configuration: old.configuration.clone(),
+            memos: Mutex::new(old.memos.lock().unwrap().clone()),
+        };
+        self.state = Arc::new(state);
+        Arc::get_mut(&mut self.state).expect("the new state is not shared yet")
+    }
+}
+
+impl Clone for Snapshot {
+    fn clone(&self) -> Self {
+        Snapshot { state: self.state.clone(), stack: Mutex::new(vec![]) }
+    }
+}
+
+impl Snapshot {
+    /// The revision of the snapshot.
+    pub fn revision(&self) -> u64 {
+        self.state.revision
+    }
+    /// Get the id of a known file.
+    pub fn file_id(&self, url: &Url) -> Option<FileId> {
+        self.state.interner.read().unwrap().ids.get(url).copied()
+    }
+    /// Get the uri of a file.
+    pub fn file_url(&self, file: FileId) -> Url {
+        self.state.interner.read().unwrap().urls[file.0 as usize].clone()
+    }
+    /// All source files, sorted by id.
+    pub fn files(&self) -> Arc<Vec<FileId>> {
+        self.record(QueryKey::FileSet);
+        self.state.file_set.value.clone()
+    }
+    /// The text of a source file.
+    pub fn file_text(&self, file: FileId) -> Option<Arc<String>> {
+        self.record(QueryKey::FileText(file));
+        self.file_input(file).map(|input| input.text.clone())
+    }
+    /// All `fleet.json5` manifests, sorted by id.
+    pub fn manifests(&self) -> Arc<Vec<FileId>> {
+        self.record(QueryKey::ManifestSet);
+        self.state.manifest_set.value.clone()
+    }
+    /// The text of a `fleet.json5` manifest.
+    pub fn manifest_text(&self, file: FileId) -> Option<Arc<String>> {
+        self.record(QueryKey::Manifest(file));
+        self.state.manifests.get(&file).and_then(|input| input.value.clone())
+    }
+    /// The settings of the client and its workspace folders.
+    pub fn configuration(&self) -> Arc<Configuration> {
+        self.record(QueryKey::Configuration);
+        self.state.configuration.value.clone()
+    }
+    /// The typed content of a manifest and its problems.
+    pub fn parsed_manifest(&self, file: FileId) -> Arc<ParsedManifest> {
+        match self.query(QueryKey::ParsedManifest(file)) {
+            QueryValue::ParsedManifest(value) => value,
+            _ => unreachable!(),
+        }
+    }
+    /// The packages of all manifests.
+    pub fn package_graph(&self) -> Arc<PackageGraph> {
+        match self.query(QueryKey::PackageGraph) {
+            QueryValue::PackageGraph(value) => value,
+            _ => unreachable!(),
+        }
+    }
+    /// The package a source file belongs to.
+    pub fn package_of(&self, file: FileId) -> Option<PackageId> {
+        let path = self.file_url(file).to_file_path().ok()?;
+        self.package_graph().package_of(&path)
+    }
+    /// The syntax tree of a source file, empty for unknown files.
+    pub fn parse(&self, file: FileId) -> Arc<Parse> {
+        match self.query(QueryKey::Parse(file)) {
+            QueryValue::Parse(value) => value,
+            _ => unreachable!(),
+        }
+    }
+    /// The declarations of a source file.
+    pub fn item_tree(&self, file: FileId) -> Arc<ItemTree> {
+        match self.query(QueryKey::ItemTree(file)) {
+            QueryValue::ItemTree(value) => value,
+            _ => unreachable!(),
+        }
+    }
+    /// The positions of the declarations of a source file.
+    pub fn item_sources(&self, file: FileId) -> Arc<ItemSourceMap> {
+        match self.query(QueryKey::ItemSources(file)) {
+            QueryValue::ItemSources(value) => value,
+            _ => unreachable!(),
+        }
+    }
+    /// The declarations of all source files.
+    pub fn def_map(&self) -> Arc<DefMap> {
+        match self.query(QueryKey::DefMap) {
+            QueryValue::DefMap(value) => value,
+            _ => unreachable!(),
+        }
+    }
+    /// The resolved imports of a source file.
+    pub fn file_scope(&self, file: FileId) -> Arc<FileScope> {
+        match self.query(QueryKey::FileScope(file)) {
+            QueryValue::FileScope(value) => value,
+            _ => unreachable!(),
+        }
+    }
+    /// The resolved names and inferred types of a source file.
+    pub fn infer(&self, file: FileId) -> Arc<InferenceResult> {
+        match self.query(QueryKey::Infer(file)) {
+            QueryValue::Infer(value) => value,
+            _ => unreachable!(),
+        }
+    }
+    /// The syntax and semantic problems of a source file.
+    pub fn diagnostics(&self, file: FileId) -> Arc<Vec<FileDiagnostic>> {
+        match self.query(QueryKey::Diagnostics(file)) {
+            QueryValue::Diagnostics(value) => value,
+            _ => unreachable!(),
+        }
+    }
+    fn file_input(&self, file: FileId) -> Option<&FileInput> {
+        self.state.files.get(&file).and_then(|input| input.value.as_ref())
+    }
+    /// Add the key to the dependencies of the query being computed.
+    fn record(&self, key: QueryKey) {
+        if let Some(frame) = self.stack.lock().unwrap().last_mut() {
+            frame.push(key);
+        }
+    }
+    fn query(&self, key: QueryKey) -> QueryValue {
+        self.record(key.clone());
+        self.memo(&key).value
+    }
+    /// The revision an input or a derived value last changed in.
+    fn changed_at(&self, key: &QueryKey) -> u64 {
+        let state = &self.state;
+        match key {
+            QueryKey::FileText(file) => state.files.get(file).map_or(0, |input| input.changed_at),
+            QueryKey::FileSet => state.file_set.changed_at,
+            QueryKey::Manifest(file) => state.manifests.get(file).map_or(0, |input| input.changed_at),
+            QueryKey::ManifestSet => state.manifest_set.changed_at,
+            QueryKey::Configuration => state.configuration.changed_at,
+            _ => self.memo(key).changed_at,
+        }
+    }
+    /// Get an up to date memo of a derived query, reusing the old value if none of its dependencies changed.
+    fn memo(&self, key: &QueryKey) -> Memo {
+        let revision = self.state.revision;
+        let old = self.state.memos.lock().unwrap().get(key).cloned();
+        if let Some(memo) = &old {
+            if memo.verified_at == revision {
+                return memo.clone();
+            }
+            if memo.deps.iter().all(|dep| self.changed_at(dep) <= memo.verified_at) {
+                let memo = Memo { verified_at: revision, ..memo.clone() };
+                self.state.memos.lock().unwrap().insert(key.clone(), memo.clone());
+                return memo;
+            }
+        }
+        self.stack.lock().unwrap().push(vec![]);
+        let value = self.compute(key);
+        let deps = self.stack.lock().unwrap().pop().unwrap_or_default();
+        let changed_at = match old {
+            Some(old) if old.value == value => old.changed_at,
+            _ => revision,
+        };
+        let memo = Memo { value, changed_at, verified_at: revision, deps: Arc::new(deps) };
+        self.state.memos.lock().unwrap().insert(key.clone(), memo.clone());
+        memo
+    }
+    fn compute(&self, key: &QueryKey) -> QueryValue {
+        match *key {
+            QueryKey::ParsedManifest(file) => {
+                let text = self.manifest_text(file).unwrap_or_default();
+                QueryValue::ParsedManifest(Arc::new(ParsedManifest::parse(&text)))
+            }
+            QueryKey::PackageGraph => {
+                let mut parsed = vec![];
+                for file in self.manifests().iter() {
+                    if let Ok(path) = self.file_url(*file).to_file_path() {
+                        parsed.push((*file, path, self.parsed_manifest(*file)));
+                    }
+                }
+                let manifests: Vec<_> = parsed
+                    .iter()
+                    .filter_map(|(file, path, parsed)| Some((*file, path.clone(), parsed.manifest.as_ref()?)))
+                    .collect();
+                QueryValue::PackageGraph(Arc::new(PackageGraph::build(&manifests)))
+            }
+            QueryKey::Parse(file) => {
+                self.record(QueryKey::FileText(file));
+                let parse = match self.file_input(file) {
+                    Some(FileInput { parse: Some(parse), .. }) => parse.clone(),
+                    Some(FileInput { text, .. }) => Arc::new(Parse::new(text)),
+                    None => Arc::new(Parse::new("")),
+                };
+                QueryValue::Parse(parse)
+            }
+            QueryKey::Lower(file) => {
+                self.record(QueryKey::FileText(file));
+                if let Some(FileInput { lowered: Some((tree, sources)), .. }) = self.file_input(file) {
+                    return QueryValue::Lower(tree.clone(), sources.clone());
+                }
+                let (tree, sources) = ItemTree::lower(&self.parse(file));
+                QueryValue::Lower(Arc::new(tree), Arc::new(sources))
+            }
+            QueryKey::ItemTree(file) => match self.query(QueryKey::Lower(file)) {
+                QueryValue::Lower(tree, _) => QueryValue::ItemTree(tree),
+                _ => unreachable!(),
+            },
+            QueryKey::ItemSources(file) => match self.query(QueryKey::Lower(file)) {
+                QueryValue::Lower(_, sources) => QueryValue::ItemSources(sources),
+                _ => unreachable!(),
+            },
+            QueryKey::DefMap => {
+                let trees: Vec<(FileId, Arc<ItemTree>, Option<PackageId>)> =
+                    self.files().iter().map(|file| (*file, self.item_tree(*file), self.package_of(*file))).collect();
+                let trees: Vec<(FileId, &ItemTree, Option<PackageId>)> =
+                    trees.iter().map(|(file, tree, package)| (*file, tree.as_ref(), *package)).collect();
+                QueryValue::DefMap(Arc::new(DefMap::build(&trees, self.package_graph())))
+            }
+            QueryKey::FileScope(file) => {
+                QueryValue::FileScope(Arc::new(FileScope::build(&self.item_tree(file), &self.def_map(), file)))
+            }
+            QueryKey::Infer(file) => QueryValue::Infer(Arc::new(infer_file(self, file))),
+            QueryKey::Diagnostics(file) => QueryValue::Diagnostics(Arc::new(file_diagnostics(self, file))),
+            QueryKey::FileText(_)
+            | QueryKey::FileSet
+            | QueryKey::Manifest(_)
+            | QueryKey::ManifestSet
+            | QueryKey::Configuration => {
+                unreachable!("inputs are not computed")
+            }
+        }
+    }
+}
+
+impl FileInterner {
+    fn intern(&mut self, url: Url) -> FileId {
+        if let Some(id) = self.ids.get(&url) {
+            return *id;
+        }
+        let id = FileId(self.urls.len() as u32);
+        self.urls.push(url.clone());
+        self.ids.insert(url, id);
+        id
+    }
+}
+
+fn insert_sorted(set: &mut Input<Arc<Vec<FileId>>>, file: FileId, revision: u64) {
+    let mut files: BTreeSet<FileId> = set.value.iter().copied().collect();
+    files.insert(file);
+    *set = Input { value: Arc::new(files.into_iter().collect()), changed_at: revision };
+}
+
+fn remove_sorted(set: &mut Input<Arc<Vec<FileId>>>, file: FileId, revision: u64) {
+    let files = set.value.iter().copied().filter(|id| *id != file).collect();
+    *set = Input { value: Arc::new(files), changed_at: revision };
+}
@@ -0,0 +1,105 @@
+use tower_lsp::lsp_types::{CodeDescription, DiagnosticSeverity, Url};
+
+/// A registered diagnostic, with a stable code to look it up and suppress it.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct DiagnosticCode {
+    /// The stable code, such as `V0001`.
+    pub code: &'static str,
+    /// A short kebab-case name.
+    pub name: &'static str,
+    /// The default severity.
+    pub severity: DiagnosticSeverity,
+    /// Whether `valkyrie.lints` can change the severity, problems that stop the analysis can not.
+    pub lint: bool,
+    /// The message with `{0}`, `{1}`... placeholders for the arguments.
+    pub template: &'static str,
+    /// The long explanation in Markdown.
+    pub explanation: &'static str,
+}
+
+/// The scheme of the explanation links, clients resolve them with `valkyrie/explainDiagnostic`.
+pub const EXPLANATION_SCHEME: &str = "valkyrie-explain";
+
+/// The file could not be parsed.
+pub const SYNTAX_ERROR: DiagnosticCode = DiagnosticCode {
+    code: "V0001",
+    name: "syntax-error",
+    severity: DiagnosticSeverity::ERROR,
+    lint: false,
+    template: "{0}",
+    explanation: include_str!("codes/V0001.md"),
+};
+
+/// A name below a workspace namespace does not exist.
+pub const UNRESOLVED_NAME: DiagnosticCode = DiagnosticCode {
+    code: "V0002",
+    name: "unresolved-name",
+    severity: DiagnosticSeverity::ERROR,
+    lint: true,
+    template: "cannot find `{0}` in namespace `{1}`",
+    explanation: include_str!("codes/V0002.md"),
+};
+
+/// A type is declared more than once.
+pub const DUPLICATE_TYPE: DiagnosticCode = DiagnosticCode {
+    code: "V0003",
+    name: "duplicate-type",
+    severity: DiagnosticSeverity::ERROR,
+    lint: true,
+    template: "type `{0}` is defined {1} times",
+    explanation: include_str!("codes/V0003.md"),
+};
+
+/// A `fleet.json5` manifest is malformed.
+pub const INVALID_MANIFEST: DiagnosticCode = DiagnosticCode {
+    code: "V0004",
+    name: "invalid-manifest",
+    severity: DiagnosticSeverity::ERROR,
+    lint: true,
+    template: "{0}",
+    explanation: include_str!("codes/V0004.md"),
+};
+
+/// A `fleet.json5` manifest contains an unknown key.
+pub const UNKNOWN_MANIFEST_KEY: DiagnosticCode = DiagnosticCode {
+    code: "V0005",
+    name: "unknown-manifest-key",
+    severity: DiagnosticSeverity::WARNING,
+    lint: true,
+    template: "unknown key `{0}` in manifest",
+    explanation: include_str!("codes/V0005.md"),
+};
+
+/// A dependency of a manifest can not be found.
+pub const UNRESOLVED_DEPENDENCY: DiagnosticCode = DiagnosticCode {
+    code: "V0006",
+    name: "unresolved-dependency",
+    severity: DiagnosticSeverity::ERROR,
+    lint: true,
+    template: "cannot find a package for dependency `{0}`",
+    explanation: include_str!("codes/V0006.md"),
+};
+
+/// All registered codes, sorted by code.
+pub const DIAGNOSTIC_CODES: &[DiagnosticCode] =
+    &[SYNTAX_ERROR, UNRESOLVED_NAME, DUPLICATE_TYPE, INVALID_MANIFEST, UNKNOWN_MANIFEST_KEY, UNRESOLVED_DEPENDENCY];
+
+impl DiagnosticCode {
+    /// Find a code by its code or its name.
+    pub fn find(code: &str) -> Option<&'static DiagnosticCode> {
+        DIAGNOSTIC_CODES.iter().find(|item| item.code.eq_ignore_ascii_case(code) || item.name == code)
+    }
+    /// Fill the placeholders of the template.
+    pub fn format(&self, args: &[&str]) -> String {
+        let mut message = self.template.to_string();
+        for (index, arg) in args.iter().enumerate() {
+            message = message.replace(&format!("{{{}}}", index), arg);
+        }
+        message
+    }
+    /// The link to the explanation.
+    pub fn description(&self) -> Option<CodeDescription> {
+        let href = Url::parse(&format!("{}:{}", EXPLANATION_SCHEME, self.code)).ok()?;
+        Some(CodeDescription { href })
+    }
+}
@@ -0,0 +1,246 @@
+use std::{
+    collections::HashMap,
+    ops::Range,
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+    time::Duration,
+};
+
+use tower_lsp::{
+    Client,
+    lsp_types::{Diagnostic, DiagnosticSeverity, NumberOrString, Url},
+};
+
+pub use self::codes::{
+    DIAGNOSTIC_CODES, DUPLICATE_TYPE, DiagnosticCode, EXPLANATION_SCHEME, INVALID_MANIFEST, SYNTAX_ERROR, UNKNOWN_MANIFEST_KEY,
+    UNRESOLVED_DEPENDENCY, UNRESOLVED_NAME,
+};
+pub(crate) use self::pull::{document_diagnostics, workspace_diagnostics};
+use crate::{
+    Database, Definition, DocumentStore, FileId, LineIndex, LintLevel, Snapshot,
+    ast::{self, AstNode},
+};
+
+mod codes;
+mod pull;
+
+/// A problem found in a file, positioned by bytes so it does not depend on the position encoding.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FileDiagnostic {
+    /// The byte span of the problem.
+    pub span: Range<usize>,
+    /// The registered code.
+    pub code: &'static DiagnosticCode,
+    /// The severity to report.
+    pub severity: DiagnosticSeverity,
+    /// The message to report.
+    pub message: String,
+}
+
+impl FileDiagnostic {
+    /// A diagnostic with the default severity of the code and its template filled with the arguments.
+    pub fn new(code: &'static DiagnosticCode, span: Range<usize>, args: &[&str]) -> Self {
+        Self { span, code, severity: code.severity, message: code.format(args) }
+    }
+    /// Convert to the protocol type with the positions of the text.
+    pub fn to_lsp(&self, index: &LineIndex) -> Diagnostic {
+        Diagnostic {
+            range: index.range(self.span.clone()),
+            severity: Some(self.severity),
+            code: Some(NumberOrString::String(self.code.code.to_string())),
+            code_description: self.code.description(),
+            source: Some("valkyrie".to_string()),
+            message: self.message.clone(),
+            ..Diagnostic::default()
+        }
+    }
+}
+
+/// Collect the problems of a file, at the levels configured by `valkyrie.lints`.
+pub(crate) fn file_diagnostics(db: &Snapshot, file: FileId) -> Vec<FileDiagnostic> {
+    let found = match db.manifest_text(file) {
+        Some(_) => manifest_diagnostics(db, file),
+        None => source_diagnostics(db, file),
+    };
+    let configuration = db.configuration();
+    let settings = configuration.settings(&db.file_url(file));
+    found
+        .into_iter()
+        .filter_map(|mut diagnostic| {
+            match settings.lint_level(diagnostic.code) {
+                Some(LintLevel::Allow) => return None,
+                Some(LintLevel::Warn) => diagnostic.severity = DiagnosticSeverity::WARNING,
+                Some(LintLevel::Deny) => diagnostic.severity = DiagnosticSeverity::ERROR,
+                None => {}
+            }
+            Some(diagnostic)
+        })
+        .collect()
+}
+
+/// Collect the syntax and semantic problems of a source file.
+fn source_diagnostics(db: &Snapshot, file: FileId) -> Vec<FileDiagnostic> {
+    let parse = db.parse(file);
+    let mut out: Vec<FileDiagnostic> =
+        parse.errors().iter().map(|error| FileDiagnostic::new(&SYNTAX_ERROR, error.span.clone(), &[&error.message])).collect();
+    // a name below a namespace of the workspace must exist, other roots are external packages
+    let infer = db.infer(file);
+    for path in parse.syntax().descendants().filter_map(ast::Path::cast) {
+        let mut namespace: Option<String> = None;
+        for segment in path.segments() {
+            let token = match segment.name_ref().and_then(|name| name.token()) {
+                Some(s) => s,
+                None => break,
+            };
+            let resolution = infer.resolutions.get(&token.span().start);
+            if let (Some(namespace), Some(resolution)) = (&namespace, resolution) {
+                if resolution.definitions.is_empty() {
+                    out.push(FileDiagnostic::new(&UNRESOLVED_NAME, token.span(), &[token.text(), namespace.as_str()]));
+                    break;
+                }
+            }
+            namespace = match resolution.and_then(|resolution| resolution.definitions.first()) {
+                Some(Definition::Namespace(path)) => Some(path.join("::")),
+                _ => None,
+            };
+        }
+    }
+    let tree = db.item_tree(file);
+    let sources = db.item_sources(file);
+    let map = db.def_map();
+    for (index, item) in tree.items.iter().enumerate() {
+        if !item.is_type() {
+            continue;
+        }
+        let types = map
+            .get(&item.namepath)
+            .iter()
+            .filter(|loc| map.can_see(file, loc.file) && db.item_tree(loc.file).item(loc.index).is_type())
+            .count();
+        if types > 1 {
+            let name_span = sources.items[index].name_span.clone();
+            out.push(FileDiagnostic::new(&DUPLICATE_TYPE, name_span, &[&item.namepath.join("::"), &types.to_string()]));
+        }
+    }
+    out.sort_by_key(|diagnostic| diagnostic.span.start);
+    out
+}
+
+/// Collect the problems of a `fleet.json5` manifest.
+fn manifest_diagnostics(db: &Snapshot, file: FileId) -> Vec<FileDiagnostic> {
+    let mut out = db.parsed_manifest(file).problems.clone();
+    // packages of the registry are not known, only a missing local path is a problem
+    for dependency in db.package_graph().unresolved(file).iter().filter(|dependency| dependency.path.is_some()) {
+        out.push(FileDiagnostic::new(&UNRESOLVED_DEPENDENCY, dependency.span.clone(), &[&dependency.name]));
+    }
+    out.sort_by_key(|diagnostic| diagnostic.span.start);
+    out
+}
+
+/// Publishes the diagnostics of documents to the client.
+#[derive(Clone, Debug)]
+pub(crate) struct DiagnosticPublisher {
+    client: Client,
+    documents: Arc<DocumentStore>,
+    database: Arc<RwLock<Database>>,
+    /// The last diagnostics sent for each file, to skip publishing the same again.
+    published: Arc<Mutex<HashMap<Url, Arc<Vec<FileDiagnostic>>>>>,
+    /// The client pulls diagnostics itself, nothing is pushed.
+    pull: Arc<AtomicBool>,
+}
+
+impl DiagnosticPublisher {
+    pub fn new(client: Client, documents: Arc<DocumentStore>, database: Arc<RwLock<Database>>) -> Self {
+        Self { client, documents, database, published: Arc::default(), pull: Arc::default() }
+    }
+    /// Stop pushing diagnostics, for clients that pull them.
+    pub fn set_pull(&self, pull: bool) {
+        self.pull.store(pull, Ordering::Relaxed);
+    }
+    fn is_pull(&self) -> bool {
+        self.pull.load(Ordering::Relaxed)
+    }
+    /// Publish after the delay, unless the document changed again in the meantime.
+    pub fn schedule(&self, uri: Url, delay: Duration) {
+        if self.is_pull() {
+            return;
+        }
+        let version = self.documents.version(&uri);
+        let this = self.clone();
+        tokio::spawn(async move {
+            tokio::time::sleep(delay).await;
+            if this.documents.version(&uri) == version {
+                this.publish(uri).await;
+            }
+        });
+    }
+    /// Publish the diagnostics of the file, then of the other opened files if an edit changed them.
+    pub async fn publish(&self, uri: Url) {
+        if self.is_pull() {
+            return;
+        }
+        let snapshot = self.database.read().unwrap().snapshot();
+        self.publish_file(&snapshot, &uri, true).await;
+        for other in self.documents.uris() {
+            if other != uri {
+                self.publish_file(&snapshot, &other, false).await;
+            }
+        }
+    }
+    /// Publish the diagnostics of the opened files that changed, after the settings changed.
+    pub async fn publish_all(&self) {
+        if self.is_pull() {
+            return;
+        }
+        let snapshot = self.database.read().unwrap().snapshot();
+        for uri in self.documents.uris() {
+            self.publish_file(&snapshot, &uri, false).await;
+        }
+    }
+    /// Remove all diagnostics of the file from the client.
+    pub async fn clear(&self, uri: Url) {
+        if self.is_pull() {
+            return;
+        }
+        self.published.lock().unwrap().remove(&uri);
+        self.client.publish_diagnostics(uri, vec![], None).await;
+    }
+    async fn publish_file(&self, snapshot: &Snapshot, uri: &Url, force: bool) {
+        let file = match snapshot.file_id(uri) {
+            Some(s) => s,
+            None => return,
+        };
+        let diagnostics = snapshot.diagnostics(file);
+        // the document changed after the snapshot, a later publish will follow
+        let (index, version) = match positions(snapshot, &self.documents, uri, file) {
+            Some(s) => s,
+            None => return,
+        };
+        {
+            let mut published = self.published.lock().unwrap();
+            if !force && published.get(uri) == Some(&diagnostics) {
+                return;
+            }
+            published.insert(uri.clone(), diagnostics.clone());
+        }
+        let diagnostics = diagnostics.iter().map(|diagnostic| diagnostic.to_lsp(&index)).collect();
+        self.client.publish_diagnostics(uri.clone(), diagnostics, version).await;
+    }
+}
+
+/// The line index of the file and the version of the opened document, none if the document changed after the snapshot.
+pub(crate) fn positions(
+    snapshot: &Snapshot,
+    documents: &DocumentStore,
+    uri: &Url,
+    file: FileId,
+) -> Option<(LineIndex, Option<i32>)> {
+    let text = snapshot.file_text(file).or_else(|| snapshot.manifest_text(file)).unwrap_or_default();
+    match documents.get(uri) {
+        Some(document) if document.text != *text => None,
+        Some(document) => Some((document.line_index, Some(document.version))),
+        None => Some((LineIndex::new(&text, documents.encoding()), None)),
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+
+use tower_lsp::{
+    Client,
+    lsp_types::{
+        Diagnostic, DocumentDiagnosticParams, DocumentDiagnosticReport, DocumentDiagnosticReportResult,
+        FullDocumentDiagnosticReport, LSPAny, ProgressToken, RelatedFullDocumentDiagnosticReport,
+        RelatedUnchangedDocumentDiagnosticReport, UnchangedDocumentDiagnosticReport, Url, WorkspaceDiagnosticParams,
+        WorkspaceDiagnosticReport, WorkspaceDiagnosticReportResult, WorkspaceDocumentDiagnosticReport,
+        WorkspaceFullDocumentDiagnosticReport, WorkspaceUnchangedDocumentDiagnosticReport, notification::Notification,
+    },
+};
+
+use super::positions;
+use crate::{DocumentStore, FileId, Snapshot, ValkyrieErrorKind, ValkyrieResult, content_hash};
+
+/// How many files are sent in one partial workspace report.
+const PARTIAL_REPORT_FILES: usize = 64;
+
+/// The diagnostics of a file as sent to the client, identified by a hash of their content.
+struct Report {
+    items: Vec<Diagnostic>,
+    version: Option<i32>,
+    result_id: String,
+}
+
+/// `$/progress` carrying partial results, the value is the partial result type of the request.
+enum PartialResult {}
+
+impl Notification for PartialResult {
+    type Params = LSPAny;
+    const METHOD: &'static str = "$/progress";
+}
+
+fn report(snapshot: &Snapshot, documents: &DocumentStore, uri: &Url, file: FileId) -> Option<Report> {
+    let (index, version) = positions(snapshot, documents, uri, file)?;
+    let items: Vec<Diagnostic> = snapshot.diagnostics(file).iter().map(|diagnostic| diagnostic.to_lsp(&index)).collect();
+    let hash = content_hash(serde_json::to_string(&items).unwrap_or_default().as_bytes());
+    Some(Report { items, version, result_id: format!("{:016x}", hash) })
+}
+
+/// Answer `textDocument/diagnostic`, unchanged if the client already has the same result.
+pub(crate) fn document_diagnostics(
+    snapshot: &Snapshot,
+    documents: &DocumentStore,
+    params: DocumentDiagnosticParams,
+) -> ValkyrieResult<DocumentDiagnosticReportResult> {
+    let uri = params.text_document.uri;
+    let report = match snapshot.file_id(&uri) {
+        Some(file) => match report(snapshot, documents, &uri, file) {
+            Some(s) => s,
+            None => return Err(ValkyrieErrorKind::ContentModified { uri }.into()),
+        },
+        None => Report { items: vec![], version: None, result_id: String::new() },
+    };
+    let report = match params.previous_result_id {
+        Some(previous) if previous == report.result_id => {
+            DocumentDiagnosticReport::Unchanged(RelatedUnchangedDocumentDiagnosticReport {
+                related_documents: None,
+                unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport { result_id: report.result_id },
+            })
+        }
+        _ => DocumentDiagnosticReport::Full(RelatedFullDocumentDiagnosticReport {
+            related_documents: None,
+            full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                result_id: Some(report.result_id),
+                items: report.items,
+            },
+        }),
+    };
+    Ok(DocumentDiagnosticReportResult::Report(report))
+}
+
+/// Answer `workspace/diagnostic`, streamed in partial reports if the client asked for them.
+pub(crate) async fn workspace_diagnostics(
+    client: &Client,
+    snapshot: &Snapshot,
+    documents: &DocumentStore,
+    params: WorkspaceDiagnosticParams,
+) -> ValkyrieResult<WorkspaceDiagnosticReportResult> {
+    let previous: HashMap<Url, String> =
+        params.previous_result_ids.into_iter().map(|previous| (previous.uri, previous.value)).collect();
+    let token = params.partial_result_params.partial_result_token;
+    let mut items = vec![];
+    let files = snapshot.manifests().iter().chain(snapshot.files().iter()).copied().collect::<Vec<_>>();
+    for file in files.iter() {
+        let uri = snapshot.file_url(*file);
+        // opened documents that changed meanwhile are pulled again by the client
+        let report = match report(snapshot, documents, &uri, *file) {
+            Some(s) => s,
+            None => continue,
+        };
+        let version = report.version.map(i64::from);
+        let item = match previous.get(&uri) {
+            Some(previous) if *previous == report.result_id => {
+                WorkspaceDocumentDiagnosticReport::Unchanged(WorkspaceUnchangedDocumentDiagnosticReport {
+                    uri,
+                    version,
+                    unchanged_document_diagnostic_report: UnchangedDocumentDiagnosticReport { result_id: report.result_id },
+                })
+            }
+            _ => WorkspaceDocumentDiagnosticReport::Full(WorkspaceFullDocumentDiagnosticReport {
+                uri,
+                version,
+                full_document_diagnostic_report: FullDocumentDiagnosticReport {
+                    result_id: Some(report.result_id),
+                    items: report.items,
+                },
+            }),
+        };
+        items.push(item);
+        if let Some(token) = &token {
+            if items.len() == PARTIAL_REPORT_FILES {
+                send_partial(client, token, std::mem::take(&mut items)).await;
+            }
+        }
+    }
+    if let Some(token) = &token {
+        if !items.is_empty() {
+            send_partial(client, token, std::mem::take(&mut items)).await;
+        }
+    }
+    Ok(WorkspaceDiagnosticReportResult::Report(WorkspaceDiagnosticReport { items }))
+}
+
+async fn send_partial(client: &Client, token: &ProgressToken, items: Vec<WorkspaceDocumentDiagnosticReport>) {
+    let value = serde_json::to_value(WorkspaceDiagnosticReport { items }).unwrap_or_default();
+    let token = serde_json::to_value(token).unwrap_or_default();
+    client.send_notification::<PartialResult>(serde_json::json!({ "token": token, "value": value })).await;
+}
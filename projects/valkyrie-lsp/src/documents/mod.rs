@@ -0,0 +1,105 @@
+use std::{collections::HashMap, sync::RwLock};
+
+use tower_lsp::lsp_types::{TextDocumentContentChangeEvent, Url};
+
+use crate::{LineIndex, Parse, PositionEncoding, ValkyrieErrorKind, ValkyrieResult};
+
+/// All documents currently opened by the client, keyed by their uri.
+#[derive(Debug, Default)]
+pub struct DocumentStore {
+    encoding: RwLock<PositionEncoding>,
+    documents: RwLock<HashMap<Url, TextDocument>>,
+}
+
+/// The in-memory content of an opened document.
+#[derive(Clone, Debug)]
+pub struct TextDocument {
+    /// The language id the client reported on open.
+    pub language: String,
+    /// The version of the last applied change.
+    pub version: i32,
+    /// The full text after all applied changes.
+    pub text: String,
+    /// The line index of the text.
+    pub line_index: LineIndex,
+    /// The syntax tree of the text, updated incrementally.
+    pub parse: Parse,
+}
+
+impl DocumentStore {
+    /// The position encoding negotiated with the client.
+    pub fn encoding(&self) -> PositionEncoding {
+        *self.encoding.read().unwrap()
+    }
+    /// Set the negotiated position encoding, must be called before any document is opened.
+    pub fn set_encoding(&self, encoding: PositionEncoding) {
+        *self.encoding.write().unwrap() = encoding;
+    }
+    /// Start tracking a document, replacing any previous content with the same uri.
+    pub fn open(&self, uri: Url, language: String, version: i32, text: String) {
+        let document = TextDocument::new(language, version, text, self.encoding());
+        self.documents.write().unwrap().insert(uri, document);
+    }
+    /// Apply the content changes in order, the version must be newer than the stored one.
+    pub fn change(&self, uri: &Url, version: i32, changes: Vec<TextDocumentContentChangeEvent>) -> ValkyrieResult<()> {
+        let mut documents = self.documents.write().unwrap();
+        let document = match documents.get_mut(uri) {
+            Some(s) => s,
+            None => return Err(ValkyrieErrorKind::NotOpened { uri: uri.clone() }.into()),
+        };
+        if version <= document.version {
+            return Err(
+                ValkyrieErrorKind::StaleVersion { uri: uri.clone(), current: document.version, received: version }.into()
+            );
+        }
+        for change in changes {
+            document.apply_change(change);
+        }
+        document.version = version;
+        Ok(())
+    }
+    /// Stop tracking a document, returns the last known content.
+    pub fn close(&self, uri: &Url) -> Option<TextDocument> {
+        self.documents.write().unwrap().remove(uri)
+    }
+    /// Get a copy of the document content.
+    pub fn get(&self, uri: &Url) -> Option<TextDocument> {
+        self.documents.read().unwrap().get(uri).cloned()
+    }
+    /// Get the version of the document, if opened.
+    pub fn version(&self, uri: &Url) -> Option<i32> {
+        self.documents.read().unwrap().get(uri).map(|document| document.version)
+    }
+    /// Check if the document is opened.
+    pub fn contains(&self, uri: &Url) -> bool {
+        self.documents.read().unwrap().contains_key(uri)
+    }
+    /// Get the uris of all opened documents.
+    pub fn uris(&self) -> Vec<Url> {
+        self.documents.read().unwrap().keys().cloned().collect()
+    }
+}
+
+impl TextDocument {
+    /// Create a document, index its lines and parse it.
+    pub fn new(language: String, version: i32, text: String, encoding: PositionEncoding) -> Self {
+        let line_index = LineIndex::new(&text, encoding);
+        let parse = Parse::new(&text);
+        Self { language, version, text, line_index, parse }
+    }
+    /// Apply a single content change, a change without range replaces the whole text.
+    pub fn apply_change(&mut self, change: TextDocumentContentChangeEvent) {
+        match change.range {
+            Some(range) => {
+                let span = self.line_index.span(range);
+                self.text.replace_range(span.clone(), &change.text);
+                self.parse = self.parse.reparse(span, &change.text, &self.text);
+            }
+            None => {
+                self.text = change.text;
+                self.parse = Parse::new(&self.text);
+            }
+        }
+        self.line_index = LineIndex::new(&self.text, self.line_index.encoding());
+    }
+}
@@ -1,9 +1,30 @@
+use tower_lsp::jsonrpc::{self, ErrorCode};
+
 use super::*;
 
-impl From<ExampleErrorKind> for ExampleError {
-    fn from(value: ExampleErrorKind) -> Self {
-        Self {
-            kind: Box::new(value),
-        }
+impl From<ValkyrieErrorKind> for ValkyrieError {
+    fn from(value: ValkyrieErrorKind) -> Self {
+        Self { kind: Box::new(value) }
+    }
+}
+
+impl From<std::io::Error> for ValkyrieError {
+    fn from(value: std::io::Error) -> Self {
+        ValkyrieErrorKind::IoError { path: None, kind: value.kind(), message: value.to_string() }.into()
+    }
+}
+
+impl From<ValkyrieError> for jsonrpc::Error {
+    fn from(value: ValkyrieError) -> Self {
+        let code = match value.kind() {
+            ValkyrieErrorKind::StaleVersion { .. } | ValkyrieErrorKind::ContentModified { .. } => ErrorCode::ContentModified,
+            ValkyrieErrorKind::InvalidUri { .. }
+            | ValkyrieErrorKind::InvalidName { .. }
+            | ValkyrieErrorKind::NotOpened { .. } => ErrorCode::InvalidParams,
+            ValkyrieErrorKind::IoError { .. }
+            | ValkyrieErrorKind::ThemeError { .. }
+            | ValkyrieErrorKind::InternalError { .. } => ErrorCode::InternalError,
+        };
+        jsonrpc::Error { code, message: value.to_string().into(), data: None }
     }
-}
\ No newline at end of file
+}
@@ -1,23 +1,33 @@
 use super::*;
 
-impl Error for ExampleError {}
+impl Error for ValkyrieError {}
 
-
-impl Debug for ExampleError {
+impl Debug for ValkyrieError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         Debug::fmt(&self.kind, f)
     }
 }
 
-impl Display for ExampleError {
+impl Display for ValkyrieError {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         Display::fmt(&self.kind, f)
     }
 }
 
-
-impl Display for ExampleErrorKind {
+impl Display for ValkyrieErrorKind {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        match self { ExampleErrorKind::UnknownError => { write!(f, "UnknownError") } }
+        match self {
+            ValkyrieErrorKind::IoError { path: Some(path), message, .. } => write!(f, "{}: {}", path.display(), message),
+            ValkyrieErrorKind::IoError { path: None, message, .. } => f.write_str(message),
+            ValkyrieErrorKind::ThemeError { path, message } => write!(f, "invalid theme `{}`: {}", path.display(), message),
+            ValkyrieErrorKind::InvalidUri { uri } => write!(f, "`{}` is not a local file", uri),
+            ValkyrieErrorKind::InvalidName { name } => write!(f, "`{}` is not a valid identifier", name),
+            ValkyrieErrorKind::NotOpened { uri } => write!(f, "document `{}` is not opened", uri),
+            ValkyrieErrorKind::StaleVersion { uri, current, received } => {
+                write!(f, "document `{}` is at version {}, ignore change with version {}", uri, current, received)
+            }
+            ValkyrieErrorKind::ContentModified { uri } => write!(f, "document `{}` changed during the request", uri),
+            ValkyrieErrorKind::InternalError { message } => write!(f, "internal error: {}", message),
+        }
     }
-}
\ No newline at end of file
+}
@@ -1,24 +1,95 @@
-use std::fmt::{Debug, Formatter};
-use std::error::Error;
-use std::fmt::Display;
+use std::{
+    error::Error,
+    fmt::{Debug, Display, Formatter},
+    path::{Path, PathBuf},
+};
+
+use tower_lsp::lsp_types::Url;
 
-mod display;
 mod convert;
+mod display;
 
 /// The result type of this crate.
-pub type Result<T> = std::result::Result<T, ExampleError>;
+pub type ValkyrieResult<T> = std::result::Result<T, ValkyrieError>;
 
-/// A boxed error kind, wrapping an [ExampleErrorKind].
+/// A boxed error kind, wrapping a [ValkyrieErrorKind].
 #[derive(Clone)]
-pub struct ExampleError {
-    kind: Box<ExampleErrorKind>,
+pub struct ValkyrieError {
+    kind: Box<ValkyrieErrorKind>,
 }
 
-/// The kind of [ExampleError].
-#[derive(Debug, Copy, Clone)]
-pub enum ExampleErrorKind {
-    /// An unknown error.
-    UnknownError
+/// The kind of [ValkyrieError].
+#[derive(Debug, Clone)]
+pub enum ValkyrieErrorKind {
+    /// Reading or writing a file failed.
+    IoError {
+        /// The file, if known.
+        path: Option<PathBuf>,
+        /// The kind of failure.
+        kind: std::io::ErrorKind,
+        /// The message of the underlying error.
+        message: String,
+    },
+    /// A color theme file is malformed.
+    ThemeError {
+        /// The theme file.
+        path: PathBuf,
+        /// The message to report.
+        message: String,
+    },
+    /// A uri does not name a local file.
+    InvalidUri {
+        /// The uri as received.
+        uri: String,
+    },
+    /// A rename to a name that is not an identifier.
+    InvalidName {
+        /// The name as received.
+        name: String,
+    },
+    /// The document was never opened, or is already closed.
+    NotOpened {
+        /// The uri of the document.
+        uri: Url,
+    },
+    /// A change is not newer than the stored document.
+    StaleVersion {
+        /// The uri of the document.
+        uri: Url,
+        /// The version currently stored.
+        current: i32,
+        /// The version carried by the change.
+        received: i32,
+    },
+    /// The document changed while a request on it was computed.
+    ///
+    /// Cancelled requests need no error, `tower-lsp` drops their handler and answers `RequestCancelled` itself.
+    ContentModified {
+        /// The uri of the document.
+        uri: Url,
+    },
+    /// The analysis failed on a bug of the server.
+    InternalError {
+        /// What went wrong.
+        message: String,
+    },
 }
 
-
+impl ValkyrieError {
+    /// The kind of the error.
+    pub fn kind(&self) -> &ValkyrieErrorKind {
+        &self.kind
+    }
+    /// An error for a uri that does not name a local file.
+    pub fn invalid_uri(uri: &Url) -> Self {
+        ValkyrieErrorKind::InvalidUri { uri: uri.to_string() }.into()
+    }
+    /// An error reading or writing a file.
+    pub fn io(path: &Path, error: std::io::Error) -> Self {
+        ValkyrieErrorKind::IoError { path: Some(path.to_path_buf()), kind: error.kind(), message: error.to_string() }.into()
+    }
+    /// An error for a failure of the server itself.
+    pub fn internal(message: impl Into<String>) -> Self {
+        ValkyrieErrorKind::InternalError { message: message.into() }.into()
+    }
+}
@@ -0,0 +1,128 @@
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{ItemSourceMap, ItemTree, ValkyrieError, ValkyrieResult};
+
+/// The cache directory below a workspace folder.
+pub const INDEX_DIRECTORY: &str = "target/language-server";
+
+/// The file name of the index in the [INDEX_DIRECTORY].
+const INDEX_FILE: &str = "index.json";
+
+/// Identifies the file, also guards against reading another file by accident.
+const INDEX_MAGIC: &str = "valkyrie-symbol-index";
+
+/// Bumped whenever the layout of the cached declarations changes.
+const INDEX_FORMAT: u32 = 1;
+
+/// The declarations of source files by the hash of their text, persisted between sessions.
+///
+/// The file is a header line with the format, the server version and a checksum, followed by the entries. Reading a
+/// missing, corrupt or outdated file gives an empty index.
+#[derive(Clone, Debug, Default)]
+pub struct SymbolIndex {
+    entries: HashMap<u64, Arc<IndexEntry>>,
+}
+
+/// The declarations of one text in a [SymbolIndex].
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct IndexEntry {
+    /// The declarations.
+    pub tree: ItemTree,
+    /// The positions of the declarations.
+    pub sources: ItemSourceMap,
+}
+
+#[derive(Debug, Eq, PartialEq, Deserialize, Serialize)]
+struct IndexHeader {
+    magic: String,
+    format: u32,
+    version: String,
+    checksum: String,
+}
+
+impl SymbolIndex {
+    /// The path of the index of a workspace folder.
+    pub fn path(root: &Path) -> PathBuf {
+        root.join(INDEX_DIRECTORY).join(INDEX_FILE)
+    }
+    /// Read the index of a workspace folder, empty if there is none or it can not be trusted.
+    pub fn load(root: &Path) -> SymbolIndex {
+        std::fs::read(Self::path(root)).ok().and_then(|bytes| Self::decode(&bytes)).unwrap_or_default()
+    }
+    /// Write the index of a workspace folder, replacing the old one at once.
+    pub fn save(&self, root: &Path) -> ValkyrieResult<()> {
+        let path = Self::path(root);
+        let directory = root.join(INDEX_DIRECTORY);
+        std::fs::create_dir_all(&directory).map_err(|e| ValkyrieError::io(&directory, e))?;
+        let temporary = path.with_extension("json.tmp");
+        std::fs::write(&temporary, self.encode()?).map_err(|e| ValkyrieError::io(&temporary, e))?;
+        std::fs::rename(&temporary, &path).map_err(|e| ValkyrieError::io(&path, e))
+    }
+    /// The number of cached texts.
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+    /// Check if nothing is cached.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+    /// The declarations of a text, if cached.
+    pub fn get(&self, text: &str) -> Option<Arc<IndexEntry>> {
+        self.entries.get(&content_hash(text.as_bytes())).cloned()
+    }
+    /// Cache the declarations of a text.
+    pub fn insert(&mut self, text: &str, tree: ItemTree, sources: ItemSourceMap) {
+        self.entries.insert(content_hash(text.as_bytes()), Arc::new(IndexEntry { tree, sources }));
+    }
+    fn encode(&self) -> ValkyrieResult<Vec<u8>> {
+        // sorted so the same index always gives the same bytes
+        let mut entries: Vec<(String, &IndexEntry)> =
+            self.entries.iter().map(|(hash, entry)| (format!("{hash:016x}"), entry.as_ref())).collect();
+        entries.sort_by(|a, b| a.0.cmp(&b.0));
+        let body = serde_json::to_vec(&entries).map_err(|e| ValkyrieError::internal(e.to_string()))?;
+        let header = IndexHeader {
+            magic: INDEX_MAGIC.to_string(),
+            format: INDEX_FORMAT,
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            checksum: format!("{:016x}", content_hash(&body)),
+        };
+        let mut out = serde_json::to_vec(&header).map_err(|e| ValkyrieError::internal(e.to_string()))?;
+        out.push(b'\n');
+        out.extend(body);
+        Ok(out)
+    }
+    fn decode(bytes: &[u8]) -> Option<SymbolIndex> {
+        let split = bytes.iter().position(|byte| *byte == b'\n')?;
+        let header: IndexHeader = serde_json::from_slice(&bytes[..split]).ok()?;
+        let body = &bytes[split + 1..];
+        let trusted = header.magic == INDEX_MAGIC
+            && header.format == INDEX_FORMAT
+            && header.version == env!("CARGO_PKG_VERSION")
+            && header.checksum == format!("{:016x}", content_hash(body));
+        if !trusted {
+            return None;
+        }
+        let entries: Vec<(String, IndexEntry)> = serde_json::from_slice(body).ok()?;
+        let mut out = SymbolIndex::default();
+        for (hash, entry) in entries {
+            out.entries.insert(u64::from_str_radix(&hash, 16).ok()?, Arc::new(entry));
+        }
+        Some(out)
+    }
+}
+
+/// A 64-bit FNV-1a hash, stable across platforms and compiler versions unlike the std hashers.
+pub fn content_hash(bytes: &[u8]) -> u64 {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    hash
+}
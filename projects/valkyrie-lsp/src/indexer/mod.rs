@@ -0,0 +1,278 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::{Path, PathBuf},
+    sync::{
+        Arc, Mutex, RwLock,
+        atomic::{AtomicBool, AtomicU64, Ordering},
+    },
+};
+
+use tower_lsp::{
+    Client,
+    lsp_types::{
+        MessageType, NumberOrString, ProgressParams, ProgressParamsValue, ProgressToken, Url, WorkDoneProgress,
+        WorkDoneProgressBegin, WorkDoneProgressCreateParams, WorkDoneProgressEnd, WorkDoneProgressReport,
+        notification::Progress, request::WorkDoneProgressCreate,
+    },
+};
+
+use crate::{
+    Database, DocumentStore, PackageId, SymbolIndex,
+    config::Logger,
+    diagnostics::DiagnosticPublisher,
+    project::{WorkspaceFolders, dependency_manifests, find_manifests, find_sources, is_manifest},
+};
+
+/// How many source files are read before they are committed to the database and the progress is reported.
+const INDEX_BATCH_FILES: usize = 32;
+
+/// Loads the workspace folders into the database in the background, reporting work-done progress.
+///
+/// The database lock is released between batches, so requests answer from the files indexed so far.
+#[derive(Clone, Debug)]
+pub(crate) struct Indexer {
+    client: Client,
+    logger: Logger,
+    documents: Arc<DocumentStore>,
+    database: Arc<RwLock<Database>>,
+    folders: Arc<RwLock<WorkspaceFolders>>,
+    diagnostics: DiagnosticPublisher,
+    /// The client accepts progress tokens created by the server.
+    progress: Arc<AtomicBool>,
+    /// The cancellation flag of each running progress.
+    running: Arc<Mutex<HashMap<ProgressToken, Arc<AtomicBool>>>>,
+    /// The number of the next progress token.
+    next_token: Arc<AtomicU64>,
+}
+
+/// A work-done progress of the client, or nothing if the client does not support them.
+struct IndexProgress {
+    token: Option<ProgressToken>,
+    cancelled: Arc<AtomicBool>,
+}
+
+impl Indexer {
+    pub fn new(
+        client: Client,
+        logger: Logger,
+        documents: Arc<DocumentStore>,
+        database: Arc<RwLock<Database>>,
+        folders: Arc<RwLock<WorkspaceFolders>>,
+        diagnostics: DiagnosticPublisher,
+    ) -> Self {
+        Self {
+            client,
+            logger,
+            documents,
+            database,
+            folders,
+            diagnostics,
+            progress: Arc::default(),
+            running: Arc::default(),
+            next_token: Arc::default(),
+        }
+    }
+    /// Report the progress of indexing, for clients with `window.workDoneProgress`.
+    pub fn set_progress(&self, progress: bool) {
+        self.progress.store(progress, Ordering::Relaxed);
+    }
+    /// Stop the indexing reported under the token, the files indexed so far are kept.
+    pub fn cancel(&self, token: &ProgressToken) {
+        if let Some(cancelled) = self.running.lock().unwrap().get(token) {
+            cancelled.store(true, Ordering::Relaxed);
+        }
+    }
+    /// Index the folders in a background task.
+    pub fn spawn(&self, roots: Vec<PathBuf>) {
+        let this = self.clone();
+        tokio::spawn(async move {
+            for root in roots {
+                this.index_folder(&root).await;
+            }
+        });
+    }
+    /// Load a folder, its packages and their dependencies and save the index of the folder, then publish the problems
+    /// of the manifests.
+    pub async fn index_folder(&self, root: &Path) {
+        let progress = self.begin(root).await;
+        let manifests = self.load_folder(root, &progress).await;
+        let cancelled = progress.cancelled.load(Ordering::Relaxed);
+        // a partial index would only miss files, but it is not worth the write
+        if !cancelled {
+            self.save_index(root).await;
+        }
+        self.end(progress, cancelled).await;
+        for manifest in manifests {
+            self.diagnostics.publish(manifest).await;
+        }
+    }
+    /// Forget the files only a removed folder loaded, opened documents are kept.
+    pub async fn unload_folder(&self, root: &Path) {
+        self.save_index(root).await;
+        let files = self.folders.write().unwrap().remove(root);
+        for uri in files {
+            if self.documents.contains(&uri) {
+                continue;
+            }
+            forget(&mut self.database.write().unwrap(), &uri);
+            self.diagnostics.clear(uri).await;
+        }
+    }
+    /// Write the declarations of the source files a folder loaded to its index.
+    pub async fn save_index(&self, root: &Path) {
+        let files = match self.folders.read().unwrap().files(root) {
+            Some(s) => s,
+            None => return,
+        };
+        let snapshot = self.database.read().unwrap().snapshot();
+        let mut index = SymbolIndex::default();
+        for uri in files {
+            let file = match snapshot.file_id(&uri) {
+                Some(s) => s,
+                None => continue,
+            };
+            if let Some(text) = snapshot.file_text(file) {
+                index.insert(&text, (*snapshot.item_tree(file)).clone(), (*snapshot.item_sources(file)).clone());
+            }
+        }
+        if let Err(e) = index.save(root) {
+            self.logger.log(MessageType::WARNING, e).await;
+        }
+    }
+    /// Load the manifests under a workspace folder and of their dependencies, then the source files of all these
+    /// packages, returns the manifests.
+    ///
+    /// Opened documents are kept, the client owns their text. Unchanged source files take their declarations from the
+    /// index of the folder instead of being parsed.
+    async fn load_folder(&self, root: &Path, progress: &IndexProgress) -> Vec<Url> {
+        let roots = self.folders.read().unwrap().roots();
+        let index = SymbolIndex::load(root);
+        let mut members = vec![];
+        let files = read_files(&find_manifests(&[root.to_path_buf()]));
+        members.extend(self.commit(&index, files));
+        // dependencies may have dependencies of their own, load until nothing new is found
+        let mut seen = BTreeSet::new();
+        loop {
+            let graph = self.database.read().unwrap().snapshot().package_graph();
+            let pending: Vec<PathBuf> =
+                dependency_manifests(&graph, &roots).into_iter().filter(|path| seen.insert(path.clone())).collect();
+            if pending.is_empty() {
+                break;
+            }
+            self.commit(&index, read_files(&pending));
+        }
+        let snapshot = self.database.read().unwrap().snapshot();
+        let graph = snapshot.package_graph();
+        let mut packages: Vec<PackageId> =
+            members.iter().filter_map(|uri| graph.package_of_manifest(snapshot.file_id(uri)?)).collect();
+        let dependencies: Vec<PackageId> = packages.iter().flat_map(|id| graph.transitive_dependencies(*id)).collect();
+        packages.extend(dependencies);
+        packages.sort();
+        packages.dedup();
+        let mut loaded: Vec<Url> = packages.iter().map(|id| snapshot.file_url(graph.package(*id).manifest)).collect();
+        let manifests = loaded.clone();
+        let source_roots: Vec<PathBuf> = packages.iter().flat_map(|id| graph.package(*id).source_roots.clone()).collect();
+        drop(snapshot);
+        let sources = find_sources(&source_roots);
+        for (batch, paths) in sources.chunks(INDEX_BATCH_FILES).enumerate() {
+            if progress.cancelled.load(Ordering::Relaxed) {
+                break;
+            }
+            loaded.extend(self.commit(&index, read_files(paths)));
+            let done = (batch * INDEX_BATCH_FILES + paths.len()).min(sources.len());
+            self.report(progress, done, sources.len()).await;
+        }
+        self.folders.write().unwrap().set_files(root, loaded);
+        manifests
+    }
+    /// Put the files read from disk into the database at once, unless they are opened, returns their uris.
+    fn commit(&self, index: &SymbolIndex, files: Vec<(PathBuf, Url, String)>) -> Vec<Url> {
+        let mut database = self.database.write().unwrap();
+        let mut out = vec![];
+        for (path, uri, text) in files {
+            out.push(uri.clone());
+            if self.documents.contains(&uri) {
+                continue;
+            }
+            if is_manifest(&path) {
+                database.set_manifest(uri, text);
+                continue;
+            }
+            match index.get(&text) {
+                Some(entry) => database.set_file_items(uri, text, entry.tree.clone(), entry.sources.clone()),
+                None => database.set_file_text(uri, text, None),
+            };
+        }
+        out
+    }
+    async fn begin(&self, root: &Path) -> IndexProgress {
+        let cancelled = Arc::new(AtomicBool::new(false));
+        if !self.progress.load(Ordering::Relaxed) {
+            return IndexProgress { token: None, cancelled };
+        }
+        let number = self.next_token.fetch_add(1, Ordering::Relaxed);
+        let token = NumberOrString::String(format!("valkyrie/indexing/{number}"));
+        let create = WorkDoneProgressCreateParams { token: token.clone() };
+        // without a token from the client nothing may be reported
+        if self.client.send_request::<WorkDoneProgressCreate>(create).await.is_err() {
+            return IndexProgress { token: None, cancelled };
+        }
+        self.running.lock().unwrap().insert(token.clone(), cancelled.clone());
+        let begin = WorkDoneProgressBegin {
+            title: "Indexing".to_string(),
+            cancellable: Some(true),
+            message: Some(root.display().to_string()),
+            percentage: Some(0),
+        };
+        self.notify(&token, WorkDoneProgress::Begin(begin)).await;
+        IndexProgress { token: Some(token), cancelled }
+    }
+    async fn report(&self, progress: &IndexProgress, done: usize, total: usize) {
+        if let Some(token) = &progress.token {
+            let report = WorkDoneProgressReport {
+                cancellable: Some(true),
+                message: Some(format!("{done}/{total} files")),
+                percentage: Some((done * 100 / total.max(1)) as u32),
+            };
+            self.notify(token, WorkDoneProgress::Report(report)).await;
+        }
+    }
+    async fn end(&self, progress: IndexProgress, cancelled: bool) {
+        if let Some(token) = progress.token {
+            self.running.lock().unwrap().remove(&token);
+            let message = match cancelled {
+                true => "cancelled",
+                false => "done",
+            };
+            self.notify(&token, WorkDoneProgress::End(WorkDoneProgressEnd { message: Some(message.to_string()) })).await;
+        }
+    }
+    async fn notify(&self, token: &ProgressToken, value: WorkDoneProgress) {
+        let params = ProgressParams { token: token.clone(), value: ProgressParamsValue::WorkDone(value) };
+        self.client.send_notification::<Progress>(params).await;
+    }
+}
+
+/// Read the files from disk, skipping unreadable ones.
+fn read_files(paths: &[PathBuf]) -> Vec<(PathBuf, Url, String)> {
+    let mut out = vec![];
+    for path in paths {
+        if let (Ok(uri), Ok(text)) = (Url::from_file_path(path), std::fs::read_to_string(path)) {
+            out.push((path.clone(), uri, text));
+        }
+    }
+    out
+}
+
+/// Remove a manifest or source file from the database.
+pub(crate) fn forget(database: &mut Database, uri: &Url) {
+    match is_manifest_uri(uri) {
+        true => database.remove_manifest(uri),
+        false => database.remove_file(uri),
+    }
+}
+
+/// Check if the uri names a `fleet.json5` manifest.
+pub(crate) fn is_manifest_uri(uri: &Url) -> bool {
+    uri.to_file_path().is_ok_and(|path| is_manifest(&path))
+}
@@ -0,0 +1,594 @@
+use serde::{Deserialize, Serialize};
+
+/// The kind of a token or a syntax node.
+///
+/// Tokens come first, in the order trivia, literals, punctuations, operators and keywords, then the nodes.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Ord, PartialOrd, Hash, Serialize, Deserialize)]
+#[repr(u16)]
+pub enum SyntaxKind {
+    /// Spaces, tabs and line breaks.
+    Whitespace,
+    /// `// comment`
+    LineComment,
+    /// `/* comment */`, may nest.
+    BlockComment,
+    /// `/// document` or `/** document */`
+    DocComment,
+    /// A character sequence that is not a valid token, or a node wrapping tokens the parser could not use.
+    Error,
+    /// The end of input, seen by the parser but never part of a tree.
+    Eof,
+    /// `name`, or a quoted `` `name` ``.
+    Ident,
+    /// `42`, `0xFF`, `1_000u32`
+    Integer,
+    /// `3.14`, `1e-9`, `2.5f64`
+    Decimal,
+    /// `"text"` or `'text'`
+    String,
+    /// `(`
+    LParen,
+    /// `)`
+    RParen,
+    /// `[`
+    LBracket,
+    /// `]`
+    RBracket,
+    /// `{`
+    LBrace,
+    /// `}`
+    RBrace,
+    /// `,`
+    Comma,
+    /// `;`
+    Semicolon,
+    /// `:`
+    Colon,
+    /// `::`
+    ColonColon,
+    /// `.`
+    Dot,
+    /// `..`
+    DotDot,
+    /// `..<`
+    DotDotLt,
+    /// `...`
+    DotDotDot,
+    /// `@`
+    At,
+    /// `#`
+    Hash,
+    /// `$`
+    Dollar,
+    /// `?`
+    Question,
+    /// `?.`
+    QuestionDot,
+    /// `??`
+    QuestionQuestion,
+    /// `->`
+    Arrow,
+    /// `=>`
+    FatArrow,
+    /// `|>`
+    PipeGt,
+    /// `=`
+    Eq,
+    /// `==`
+    EqEq,
+    /// `!=`
+    BangEq,
+    /// `<`
+    Lt,
+    /// `>`, `>>` is lexed as two tokens so nested generics can close.
+    Gt,
+    /// `<=`
+    LtEq,
+    /// `>=`
+    GtEq,
+    /// `<<`
+    Shl,
+    /// `+`
+    Plus,
+    /// `-`
+    Minus,
+    /// `*`
+    Star,
+    /// `**`
+    StarStar,
+    /// `/`
+    Slash,
+    /// `%`
+    Percent,
+    /// `^`
+    Caret,
+    /// `&`
+    Amp,
+    /// `&&`
+    AmpAmp,
+    /// `|`
+    Pipe,
+    /// `||`
+    PipePipe,
+    /// `!`
+    Bang,
+    /// `~`
+    Tilde,
+    /// `+=`
+    PlusEq,
+    /// `-=`
+    MinusEq,
+    /// `*=`
+    StarEq,
+    /// `/=`
+    SlashEq,
+    /// `%=`
+    PercentEq,
+    /// `^=`
+    CaretEq,
+    /// `&=`
+    AmpEq,
+    /// `|=`
+    PipeEq,
+    /// `<<=`
+    ShlEq,
+    /// `namespace`
+    NamespaceKw,
+    /// `using`
+    UsingKw,
+    /// `as`
+    AsKw,
+    /// `class`
+    ClassKw,
+    /// `structure`
+    StructureKw,
+    /// `trait`
+    TraitKw,
+    /// `union`
+    UnionKw,
+    /// `extends`
+    ExtendsKw,
+    /// `imply`
+    ImplyKw,
+    /// `micro`
+    MicroKw,
+    /// `let`
+    LetKw,
+    /// `type`
+    TypeKw,
+    /// `new`
+    NewKw,
+    /// `if`
+    IfKw,
+    /// `else`
+    ElseKw,
+    /// `while`
+    WhileKw,
+    /// `loop`
+    LoopKw,
+    /// `for`
+    ForKw,
+    /// `in`
+    InKw,
+    /// `match`
+    MatchKw,
+    /// `case`
+    CaseKw,
+    /// `return`
+    ReturnKw,
+    /// `break`
+    BreakKw,
+    /// `continue`
+    ContinueKw,
+    /// `yield`
+    YieldKw,
+    /// `raise`
+    RaiseKw,
+    /// `try`
+    TryKw,
+    /// `catch`
+    CatchKw,
+    /// `is`
+    IsKw,
+    /// `not`
+    NotKw,
+    /// `true`
+    TrueKw,
+    /// `false`
+    FalseKw,
+    /// `null`
+    NullKw,
+    /// `self`
+    SelfKw,
+    /// `Self`
+    SelfTypeKw,
+    /// `public`
+    PublicKw,
+    /// `private`
+    PrivateKw,
+    /// `protected`
+    ProtectedKw,
+    /// `static`
+    StaticKw,
+    /// `final`
+    FinalKw,
+    /// `mut`
+    MutKw,
+    /// The root of a file.
+    SourceFile,
+    /// `namespace a::b;` or `namespace a::b { ... }`
+    NamespaceDecl,
+    /// `using a::b::{c, d as e};`
+    UsingDecl,
+    /// A path in a using declaration, with an optional alias, glob or nested list.
+    UseTree,
+    /// `{c, d as e}`
+    UseTreeList,
+    /// `as e`
+    UseAlias,
+    /// `class Name<T>: Super { ... }`, or a `structure`.
+    ClassDecl,
+    /// `trait Name<T>: Super { ... }`
+    TraitDecl,
+    /// `imply Type: Trait { ... }` or `extends Type: Trait { ... }`
+    ImplyDecl,
+    /// `union Name<T> { Variant(T), ... }`
+    UnionDecl,
+    /// `Variant(T)` in a union.
+    VariantDecl,
+    /// `micro name<T>(params) -> Type { ... }`, or a method in a declaration body.
+    FunctionDecl,
+    /// `name: Type = default` in a class.
+    FieldDecl,
+    /// `type Name<T> = Type;`
+    TypeAliasDecl,
+    /// The braced body of a namespace, class, trait, imply or union.
+    ItemList,
+    /// `#[name(...)]` or `@name(...)`
+    Attribute,
+    /// Unstructured tokens in balanced delimiters, used by attribute arguments.
+    TokenTree,
+    /// `public static`
+    ModifierList,
+    /// The name a declaration introduces.
+    Name,
+    /// A name referring to a declaration.
+    NameRef,
+    /// `a::b::c`
+    Path,
+    /// One segment of a path, with optional generic arguments.
+    PathSegment,
+    /// `<T, U: Bound>`
+    GenericParamList,
+    /// `U: Bound`
+    GenericParam,
+    /// `<A, B>` after a path segment.
+    GenericArgList,
+    /// `: A, B` after a declaration name.
+    SuperTypeList,
+    /// `(a: A, b: B)`
+    ParamList,
+    /// `a: A = default`
+    Param,
+    /// `-> Type`
+    ReturnType,
+    /// `a::B<C>`
+    PathType,
+    /// `(A, B)`
+    TupleType,
+    /// `(A, B) -> C`
+    FunctionType,
+    /// `let pattern: Type = value;`
+    LetStmt,
+    /// An expression used as statement, with an optional `;`.
+    ExprStmt,
+    /// A number, string, boolean or null literal.
+    Literal,
+    /// A path used as an expression, `self` included.
+    PathExpr,
+    /// `(a)`
+    ParenExpr,
+    /// `(a, b)`
+    TupleExpr,
+    /// `[a, b]`
+    ArrayExpr,
+    /// `{ statements; tail }`
+    BlockExpr,
+    /// `-a`, `!a`, `~a` or `not a`
+    PrefixExpr,
+    /// `a + b`, assignments, ranges and comparisons included.
+    BinaryExpr,
+    /// `a as Type` or `a is Type`
+    CastExpr,
+    /// `f(a, b)`
+    CallExpr,
+    /// `(a, b)`
+    ArgList,
+    /// `a[b]`
+    IndexExpr,
+    /// `a.b` or `a?.b`
+    FieldExpr,
+    /// `a?`
+    TryExpr,
+    /// `new Type(a, b)`
+    NewExpr,
+    /// `if a { } else { }`
+    IfExpr,
+    /// `while a { }`
+    WhileExpr,
+    /// `loop { }`
+    LoopExpr,
+    /// `for a in b { }`
+    ForExpr,
+    /// `match a { case ... }`
+    MatchExpr,
+    /// `case pattern if guard => value`
+    MatchArm,
+    /// `return a`
+    ReturnExpr,
+    /// `break`
+    BreakExpr,
+    /// `continue`
+    ContinueExpr,
+    /// `raise a`
+    RaiseExpr,
+    /// `yield a`
+    YieldExpr,
+    /// `try { } catch e { }`
+    TryCatchExpr,
+    /// `catch e { }`
+    CatchClause,
+    /// `a`, `mut a` or `_`
+    IdentPat,
+    /// `(a, b)`
+    TuplePat,
+    /// `1` or `"a"` in a pattern.
+    LiteralPat,
+    /// `Some(a)` or `a::B` in a pattern.
+    VariantPat,
+}
+
+impl SyntaxKind {
+    /// All keywords, in declaration order.
+    pub const KEYWORDS: &'static [SyntaxKind] = &[
+        SyntaxKind::NamespaceKw,
+        SyntaxKind::UsingKw,
+        SyntaxKind::AsKw,
+        SyntaxKind::ClassKw,
+        SyntaxKind::StructureKw,
+        SyntaxKind::TraitKw,
+        SyntaxKind::UnionKw,
+        SyntaxKind::ExtendsKw,
+        SyntaxKind::ImplyKw,
+        SyntaxKind::MicroKw,
+        SyntaxKind::LetKw,
+        SyntaxKind::TypeKw,
+        SyntaxKind::NewKw,
+        SyntaxKind::IfKw,
+        SyntaxKind::ElseKw,
+        SyntaxKind::WhileKw,
+        SyntaxKind::LoopKw,
+        SyntaxKind::ForKw,
+        SyntaxKind::InKw,
+        SyntaxKind::MatchKw,
+        SyntaxKind::CaseKw,
+        SyntaxKind::ReturnKw,
+        SyntaxKind::BreakKw,
+        SyntaxKind::ContinueKw,
+        SyntaxKind::YieldKw,
+        SyntaxKind::RaiseKw,
+        SyntaxKind::TryKw,
+        SyntaxKind::CatchKw,
+        SyntaxKind::IsKw,
+        SyntaxKind::NotKw,
+        SyntaxKind::TrueKw,
+        SyntaxKind::FalseKw,
+        SyntaxKind::NullKw,
+        SyntaxKind::SelfKw,
+        SyntaxKind::SelfTypeKw,
+        SyntaxKind::PublicKw,
+        SyntaxKind::PrivateKw,
+        SyntaxKind::ProtectedKw,
+        SyntaxKind::StaticKw,
+        SyntaxKind::FinalKw,
+        SyntaxKind::MutKw,
+    ];
+    /// All operators and punctuations, in declaration order.
+    pub const OPERATORS: &'static [SyntaxKind] = &[
+        SyntaxKind::LParen,
+        SyntaxKind::RParen,
+        SyntaxKind::LBracket,
+        SyntaxKind::RBracket,
+        SyntaxKind::LBrace,
+        SyntaxKind::RBrace,
+        SyntaxKind::Comma,
+        SyntaxKind::Semicolon,
+        SyntaxKind::Colon,
+        SyntaxKind::ColonColon,
+        SyntaxKind::Dot,
+        SyntaxKind::DotDot,
+        SyntaxKind::DotDotLt,
+        SyntaxKind::DotDotDot,
+        SyntaxKind::At,
+        SyntaxKind::Hash,
+        SyntaxKind::Dollar,
+        SyntaxKind::Question,
+        SyntaxKind::QuestionDot,
+        SyntaxKind::QuestionQuestion,
+        SyntaxKind::Arrow,
+        SyntaxKind::FatArrow,
+        SyntaxKind::PipeGt,
+        SyntaxKind::Eq,
+        SyntaxKind::EqEq,
+        SyntaxKind::BangEq,
+        SyntaxKind::Lt,
+        SyntaxKind::Gt,
+        SyntaxKind::LtEq,
+        SyntaxKind::GtEq,
+        SyntaxKind::Shl,
+        SyntaxKind::Plus,
+        SyntaxKind::Minus,
+        SyntaxKind::Star,
+        SyntaxKind::StarStar,
+        SyntaxKind::Slash,
+        SyntaxKind::Percent,
+        SyntaxKind::Caret,
+        SyntaxKind::Amp,
+        SyntaxKind::AmpAmp,
+        SyntaxKind::Pipe,
+        SyntaxKind::PipePipe,
+        SyntaxKind::Bang,
+        SyntaxKind::Tilde,
+        SyntaxKind::PlusEq,
+        SyntaxKind::MinusEq,
+        SyntaxKind::StarEq,
+        SyntaxKind::SlashEq,
+        SyntaxKind::PercentEq,
+        SyntaxKind::CaretEq,
+        SyntaxKind::AmpEq,
+        SyntaxKind::PipeEq,
+        SyntaxKind::ShlEq,
+    ];
+
+    /// Check if the kind is whitespace or a comment, doc comments included.
+    pub fn is_trivia(self) -> bool {
+        matches!(self, SyntaxKind::Whitespace | SyntaxKind::LineComment | SyntaxKind::BlockComment | SyntaxKind::DocComment)
+    }
+    /// Check if the kind is a keyword.
+    pub fn is_keyword(self) -> bool {
+        (SyntaxKind::NamespaceKw..=SyntaxKind::MutKw).contains(&self)
+    }
+    /// Check if the kind is an operator or a punctuation.
+    pub fn is_operator(self) -> bool {
+        (SyntaxKind::LParen..=SyntaxKind::ShlEq).contains(&self)
+    }
+    /// Check if the kind is a literal value, `true`, `false` and `null` included.
+    pub fn is_literal(self) -> bool {
+        matches!(
+            self,
+            SyntaxKind::Integer
+                | SyntaxKind::Decimal
+                | SyntaxKind::String
+                | SyntaxKind::TrueKw
+                | SyntaxKind::FalseKw
+                | SyntaxKind::NullKw
+        )
+    }
+    /// Check if the kind is a syntax node.
+    pub fn is_node(self) -> bool {
+        self >= SyntaxKind::SourceFile
+    }
+    /// Check if the kind is a declaration modifier.
+    pub fn is_modifier(self) -> bool {
+        (SyntaxKind::PublicKw..=SyntaxKind::MutKw).contains(&self)
+    }
+    /// Find the keyword spelled by the text.
+    pub fn from_keyword(text: &str) -> Option<SyntaxKind> {
+        SyntaxKind::KEYWORDS.iter().copied().find(|kind| kind.as_str() == Some(text))
+    }
+    /// Find the operator or punctuation spelled by the text.
+    pub fn from_operator(text: &str) -> Option<SyntaxKind> {
+        SyntaxKind::OPERATORS.iter().copied().find(|kind| kind.as_str() == Some(text))
+    }
+    /// The fixed spelling of keywords, operators and punctuations.
+    pub fn as_str(self) -> Option<&'static str> {
+        let text = match self {
+            SyntaxKind::LParen => "(",
+            SyntaxKind::RParen => ")",
+            SyntaxKind::LBracket => "[",
+            SyntaxKind::RBracket => "]",
+            SyntaxKind::LBrace => "{",
+            SyntaxKind::RBrace => "}",
+            SyntaxKind::Comma => ",",
+            SyntaxKind::Semicolon => ";",
+            SyntaxKind::Colon => ":",
+            SyntaxKind::ColonColon => "::",
+            SyntaxKind::Dot => ".",
+            SyntaxKind::DotDot => "..",
+            SyntaxKind::DotDotLt => "..<",
+            SyntaxKind::DotDotDot => "...",
+            SyntaxKind::At => "@",
+            SyntaxKind::Hash => "#",
+            SyntaxKind::Dollar => "$",
+            SyntaxKind::Question => "?",
+            SyntaxKind::QuestionDot => "?.",
+            SyntaxKind::QuestionQuestion => "??",
+            SyntaxKind::Arrow => "->",
+            SyntaxKind::FatArrow => "=>",
+            SyntaxKind::PipeGt => "|>",
+            SyntaxKind::Eq => "=",
+            SyntaxKind::EqEq => "==",
+            SyntaxKind::BangEq => "!=",
+            SyntaxKind::Lt => "<",
+            SyntaxKind::Gt => ">",
+            SyntaxKind::LtEq => "<=",
+            SyntaxKind::GtEq => ">=",
+            SyntaxKind::Shl => "<<",
+            SyntaxKind::Plus => "+",
+            SyntaxKind::Minus => "-",
+            SyntaxKind::Star => "*",
+            SyntaxKind::StarStar => "**",
+            SyntaxKind::Slash => "/",
+            SyntaxKind::Percent => "%",
+            SyntaxKind::Caret => "^",
+            SyntaxKind::Amp => "&",
+            SyntaxKind::AmpAmp => "&&",
+            SyntaxKind::Pipe => "|",
+            SyntaxKind::PipePipe => "||",
+            SyntaxKind::Bang => "!",
+            SyntaxKind::Tilde => "~",
+            SyntaxKind::PlusEq => "+=",
+            SyntaxKind::MinusEq => "-=",
+            SyntaxKind::StarEq => "*=",
+            SyntaxKind::SlashEq => "/=",
+            SyntaxKind::PercentEq => "%=",
+            SyntaxKind::CaretEq => "^=",
+            SyntaxKind::AmpEq => "&=",
+            SyntaxKind::PipeEq => "|=",
+            SyntaxKind::ShlEq => "<<=",
+            SyntaxKind::NamespaceKw => "namespace",
+            SyntaxKind::UsingKw => "using",
+            SyntaxKind::AsKw => "as",
+            SyntaxKind::ClassKw => "class",
+            SyntaxKind::StructureKw => "structure",
+            SyntaxKind::TraitKw => "trait",
+            SyntaxKind::UnionKw => "union",
+            SyntaxKind::ExtendsKw => "extends",
+            SyntaxKind::ImplyKw => "imply",
+            SyntaxKind::MicroKw => "micro",
+            SyntaxKind::LetKw => "let",
+            SyntaxKind::TypeKw => "type",
+            SyntaxKind::NewKw => "new",
+            SyntaxKind::IfKw => "if",
+            SyntaxKind::ElseKw => "else",
+            SyntaxKind::WhileKw => "while",
+            SyntaxKind::LoopKw => "loop",
+            SyntaxKind::ForKw => "for",
+            SyntaxKind::InKw => "in",
+            SyntaxKind::MatchKw => "match",
+            SyntaxKind::CaseKw => "case",
+            SyntaxKind::ReturnKw => "return",
+            SyntaxKind::BreakKw => "break",
+            SyntaxKind::ContinueKw => "continue",
+            SyntaxKind::YieldKw => "yield",
+            SyntaxKind::RaiseKw => "raise",
+            SyntaxKind::TryKw => "try",
+            SyntaxKind::CatchKw => "catch",
+            SyntaxKind::IsKw => "is",
+            SyntaxKind::NotKw => "not",
+            SyntaxKind::TrueKw => "true",
+            SyntaxKind::FalseKw => "false",
+            SyntaxKind::NullKw => "null",
+            SyntaxKind::SelfKw => "self",
+            SyntaxKind::SelfTypeKw => "Self",
+            SyntaxKind::PublicKw => "public",
+            SyntaxKind::PrivateKw => "private",
+            SyntaxKind::ProtectedKw => "protected",
+            SyntaxKind::StaticKw => "static",
+            SyntaxKind::FinalKw => "final",
+            SyntaxKind::MutKw => "mut",
+            _ => return None,
+        };
+        Some(text)
+    }
+}
@@ -0,0 +1,282 @@
+use std::{ops::Range, str::Chars};
+
+pub use self::kind::SyntaxKind;
+
+mod kind;
+
+/// A token of the source text, the spans of all tokens cover the text without gaps.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Token {
+    /// The kind of the token.
+    pub kind: SyntaxKind,
+    /// The byte span of the token.
+    pub span: Range<usize>,
+}
+
+/// A problem found while lexing, the offending text is still covered by a token.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LexError {
+    /// The byte span of the problem.
+    pub span: Range<usize>,
+    /// The message to report.
+    pub message: String,
+}
+
+/// The lossless token stream of a source text.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Lexed {
+    /// All tokens in source order, trivia included.
+    pub tokens: Vec<Token>,
+    /// All problems in source order.
+    pub errors: Vec<LexError>,
+}
+
+/// Split the text into tokens, concatenating the token texts yields the input again.
+pub fn lex(text: &str) -> Lexed {
+    let mut lexer = Lexer { text, chars: text.chars(), output: Lexed::default() };
+    while !lexer.chars.as_str().is_empty() {
+        let start = lexer.offset();
+        let kind = lexer.token(start);
+        let end = lexer.offset();
+        lexer.output.tokens.push(Token { kind, span: start..end });
+    }
+    lexer.output
+}
+
+struct Lexer<'i> {
+    text: &'i str,
+    chars: Chars<'i>,
+    output: Lexed,
+}
+
+impl<'i> Lexer<'i> {
+    fn offset(&self) -> usize {
+        self.text.len() - self.chars.as_str().len()
+    }
+    fn peek(&self) -> char {
+        self.chars.clone().next().unwrap_or('\0')
+    }
+    fn peek2(&self) -> char {
+        let mut chars = self.chars.clone();
+        chars.next();
+        chars.next().unwrap_or('\0')
+    }
+    fn bump(&mut self) -> char {
+        self.chars.next().unwrap_or('\0')
+    }
+    fn eat(&mut self, char: char) -> bool {
+        let eaten = self.peek() == char;
+        if eaten {
+            self.bump();
+        }
+        eaten
+    }
+    fn eat_while(&mut self, predicate: impl Fn(char) -> bool) {
+        while !self.chars.as_str().is_empty() && predicate(self.peek()) {
+            self.bump();
+        }
+    }
+    fn error(&mut self, start: usize, message: impl Into<String>) {
+        let span = start..self.offset();
+        self.output.errors.push(LexError { span, message: message.into() });
+    }
+    fn token(&mut self, start: usize) -> SyntaxKind {
+        let first = self.bump();
+        match first {
+            c if c.is_whitespace() => {
+                self.eat_while(char::is_whitespace);
+                SyntaxKind::Whitespace
+            }
+            '/' if self.peek() == '/' => self.line_comment(),
+            '/' if self.peek() == '*' => self.block_comment(start),
+            '"' | '\'' => self.string(start, first),
+            '`' => self.quoted_ident(start),
+            c if c.is_ascii_digit() => self.number(start, first),
+            c if is_ident_start(c) => {
+                self.eat_while(is_ident_continue);
+                let text = &self.text[start..self.offset()];
+                SyntaxKind::from_keyword(text).unwrap_or(SyntaxKind::Ident)
+            }
+            _ => match self.operator(first) {
+                Some(s) => s,
+                None => {
+                    self.error(start, format!("unknown character `{}`", first.escape_debug()));
+                    SyntaxKind::Error
+                }
+            },
+        }
+    }
+    fn line_comment(&mut self) -> SyntaxKind {
+        self.bump();
+        // `////` is a separator line, not a document
+        let document = self.peek() == '/' && self.peek2() != '/';
+        self.eat_while(|c| c != '\n' && c != '\r');
+        if document { SyntaxKind::DocComment } else { SyntaxKind::LineComment }
+    }
+    fn block_comment(&mut self, start: usize) -> SyntaxKind {
+        self.bump();
+        // `/**/` is an empty comment, not a document
+        let document = self.peek() == '*' && self.peek2() != '*' && self.peek2() != '/';
+        let mut depth = 1;
+        while depth > 0 {
+            if self.chars.as_str().is_empty() {
+                self.error(start, "unterminated block comment");
+                break;
+            }
+            match self.bump() {
+                '/' if self.eat('*') => depth += 1,
+                '*' if self.eat('/') => depth -= 1,
+                _ => {}
+            }
+        }
+        if document { SyntaxKind::DocComment } else { SyntaxKind::BlockComment }
+    }
+    fn string(&mut self, start: usize, quote: char) -> SyntaxKind {
+        loop {
+            if self.chars.as_str().is_empty() {
+                self.error(start, "unterminated string literal");
+                break;
+            }
+            match self.bump() {
+                '\\' => {
+                    self.bump();
+                }
+                c if c == quote => break,
+                _ => {}
+            }
+        }
+        SyntaxKind::String
+    }
+    fn quoted_ident(&mut self, start: usize) -> SyntaxKind {
+        self.eat_while(|c| c != '`' && c != '\n' && c != '\r');
+        if !self.eat('`') {
+            self.error(start, "unterminated quoted identifier");
+        }
+        SyntaxKind::Ident
+    }
+    fn number(&mut self, start: usize, first: char) -> SyntaxKind {
+        if first == '0' && matches!(self.peek(), 'x' | 'o' | 'b') {
+            let radix = match self.bump() {
+                'x' => 16,
+                'o' => 8,
+                _ => 2,
+            };
+            let digits = self.offset();
+            self.eat_while(|c| c.is_digit(radix) || c == '_');
+            if self.offset() == digits {
+                self.error(start, "missing digits after the integer base prefix");
+            }
+            self.eat_while(is_ident_continue);
+            return SyntaxKind::Integer;
+        }
+        let mut kind = SyntaxKind::Integer;
+        self.eat_while(|c| c.is_ascii_digit() || c == '_');
+        // `1..2` and `1.method()` are not decimals
+        if self.peek() == '.' && self.peek2().is_ascii_digit() {
+            self.bump();
+            self.eat_while(|c| c.is_ascii_digit() || c == '_');
+            kind = SyntaxKind::Decimal;
+        }
+        if matches!(self.peek(), 'e' | 'E') {
+            let mut lookahead = self.chars.clone();
+            lookahead.next();
+            let sign = matches!(lookahead.clone().next(), Some('+' | '-'));
+            if sign {
+                lookahead.next();
+            }
+            if lookahead.next().is_some_and(|c| c.is_ascii_digit()) {
+                self.bump();
+                if sign {
+                    self.bump();
+                }
+                self.eat_while(|c| c.is_ascii_digit() || c == '_');
+                kind = SyntaxKind::Decimal;
+            }
+        }
+        // type suffix, `1u8` or `2.0f32`
+        self.eat_while(is_ident_continue);
+        kind
+    }
+    fn operator(&mut self, first: char) -> Option<SyntaxKind> {
+        let kind = match first {
+            '(' => SyntaxKind::LParen,
+            ')' => SyntaxKind::RParen,
+            '[' => SyntaxKind::LBracket,
+            ']' => SyntaxKind::RBracket,
+            '{' => SyntaxKind::LBrace,
+            '}' => SyntaxKind::RBrace,
+            ',' => SyntaxKind::Comma,
+            ';' => SyntaxKind::Semicolon,
+            '@' => SyntaxKind::At,
+            '#' => SyntaxKind::Hash,
+            '$' => SyntaxKind::Dollar,
+            '~' => SyntaxKind::Tilde,
+            // never glue `>`, the parser joins adjacent tokens when it needs `>>`
+            '>' if self.eat('=') => SyntaxKind::GtEq,
+            '>' => SyntaxKind::Gt,
+            ':' if self.eat(':') => SyntaxKind::ColonColon,
+            ':' => SyntaxKind::Colon,
+            '.' if self.peek() == '.' && self.peek2() == '.' => {
+                self.bump();
+                self.bump();
+                SyntaxKind::DotDotDot
+            }
+            '.' if self.peek() == '.' && self.peek2() == '<' => {
+                self.bump();
+                self.bump();
+                SyntaxKind::DotDotLt
+            }
+            '.' if self.eat('.') => SyntaxKind::DotDot,
+            '.' => SyntaxKind::Dot,
+            '?' if self.eat('.') => SyntaxKind::QuestionDot,
+            '?' if self.eat('?') => SyntaxKind::QuestionQuestion,
+            '?' => SyntaxKind::Question,
+            '=' if self.eat('=') => SyntaxKind::EqEq,
+            '=' if self.eat('>') => SyntaxKind::FatArrow,
+            '=' => SyntaxKind::Eq,
+            '!' if self.eat('=') => SyntaxKind::BangEq,
+            '!' => SyntaxKind::Bang,
+            '<' if self.peek() == '<' && self.peek2() == '=' => {
+                self.bump();
+                self.bump();
+                SyntaxKind::ShlEq
+            }
+            '<' if self.eat('<') => SyntaxKind::Shl,
+            '<' if self.eat('=') => SyntaxKind::LtEq,
+            '<' => SyntaxKind::Lt,
+            '+' if self.eat('=') => SyntaxKind::PlusEq,
+            '+' => SyntaxKind::Plus,
+            '-' if self.eat('>') => SyntaxKind::Arrow,
+            '-' if self.eat('=') => SyntaxKind::MinusEq,
+            '-' => SyntaxKind::Minus,
+            '*' if self.eat('*') => SyntaxKind::StarStar,
+            '*' if self.eat('=') => SyntaxKind::StarEq,
+            '*' => SyntaxKind::Star,
+            '/' if self.eat('=') => SyntaxKind::SlashEq,
+            '/' => SyntaxKind::Slash,
+            '%' if self.eat('=') => SyntaxKind::PercentEq,
+            '%' => SyntaxKind::Percent,
+            '^' if self.eat('=') => SyntaxKind::CaretEq,
+            '^' => SyntaxKind::Caret,
+            '&' if self.eat('&') => SyntaxKind::AmpAmp,
+            '&' if self.eat('=') => SyntaxKind::AmpEq,
+            '&' => SyntaxKind::Amp,
+            '|' if self.eat('|') => SyntaxKind::PipePipe,
+            '|' if self.eat('=') => SyntaxKind::PipeEq,
+            '|' if self.eat('>') => SyntaxKind::PipeGt,
+            '|' => SyntaxKind::Pipe,
+            _ => return None,
+        };
+        Some(kind)
+    }
+}
+
+/// Check if the char can start an identifier, non-ascii letters such as CJK are allowed.
+pub fn is_ident_start(char: char) -> bool {
+    char == '_' || char.is_alphabetic()
+}
+
+/// Check if the char can continue an identifier.
+pub fn is_ident_continue(char: char) -> bool {
+    char == '_' || char.is_alphanumeric()
+}
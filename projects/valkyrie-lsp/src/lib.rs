@@ -8,35 +8,269 @@
     html_favicon_url = "https://raw.githubusercontent.com/oovm/shape-rs/dev/projects/images/Trapezohedron.svg"
 )]
 
+mod catalog;
+mod config;
+mod database;
+mod diagnostics;
+mod documents;
 mod errors;
+mod index;
+mod indexer;
+mod lexer;
+mod line_index;
+mod navigation;
+mod project;
+mod protocol;
+mod render;
+mod request;
+mod semantic;
+mod syntax;
 
 use std::future::Future;
+use std::collections::BTreeMap;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+use crate::diagnostics::{document_diagnostics, workspace_diagnostics, DiagnosticPublisher};
+use crate::config::Logger;
+use crate::indexer::{forget, is_manifest_uri, Indexer};
+use crate::navigation::{find_references, goto_declaration, goto_definition, goto_implementation, goto_type_definition, hover, rename_edits, rename_range, workspace_symbols};
+use crate::project::{external_libraries, WorkspaceFolders};
+use crate::request::RequestGuard;
 use std::pin::Pin;
-pub use crate::errors::{ExampleErrorKind, ExampleError};
+pub use crate::errors::{ValkyrieError, ValkyrieErrorKind, ValkyrieResult};
+pub use crate::catalog::{Associativity, Fixity, KeywordDoc, OperatorDoc, KEYWORD_DOCS, OPERATOR_DOCS};
+pub use crate::config::{
+    configuration_section, Configuration, DiagnosticSettings, FormatterSettings, InlayHintSettings, LintLevel, LogLevel, LogSettings,
+    Settings, ThemeKind, ThemeSettings, CONFIGURATION_SECTION,
+};
+pub use crate::database::{Database, FileId, Snapshot};
+pub use crate::diagnostics::{DiagnosticCode, FileDiagnostic, DIAGNOSTIC_CODES, EXPLANATION_SCHEME};
+pub use crate::project::{
+    Dependency, Manifest, Package, PackageGraph, PackageId, ParsedManifest, MANIFEST_NAME, SOURCE_EXTENSIONS, VENDOR_DIRECTORY,
+};
+pub use crate::render::{
+    AnsiRenderer, Color, ColorSchema, DocumentFormat, DocumentRenderer, HtmlRenderer, MarkdownRenderer, PlainRenderer,
+};
+pub use crate::protocol::{
+    DiagnosticExplanation, ExplainDiagnostic, ExplainDiagnosticParams, ExternalLibraries, ExternalLibrariesParams, ExternalLibrary,
+};
+pub use crate::documents::{DocumentStore, TextDocument};
+pub use crate::index::{content_hash, IndexEntry, SymbolIndex, INDEX_DIRECTORY};
+pub use crate::lexer::{is_ident_continue, is_ident_start, lex, LexError, Lexed, SyntaxKind, Token};
+pub use crate::line_index::{LineIndex, PositionEncoding};
+pub use crate::semantic::{
+    DefMap, Definition, FileScope, Import, InferenceResult, ItemData, ItemKind, ItemLoc, ItemSource, ItemSourceMap, ItemTree, Local,
+    ParamData, Resolution, Resolver, Ty, TypeRef,
+};
+pub use crate::syntax::{
+    ast, Checkpoint, GreenBuilder, GreenElement, GreenNode, GreenToken, Parse, SyntaxElement, SyntaxError, SyntaxNode, SyntaxToken,
+    TokenAtOffset,
+};
 
 use tower_lsp::jsonrpc::{Error, Result};
 use tower_lsp::lsp_types::*;
+use tower_lsp::lsp_types::notification::{DidChangeConfiguration, Notification, WorkDoneProgressCancel};
+use tower_lsp::lsp_types::request::Request;
 use tower_lsp::{Client, ClientSocket, LanguageServer, LspService};
 use tower_lsp::lsp_types::request::{GotoDeclarationParams, GotoDeclarationResponse, GotoImplementationParams, GotoImplementationResponse, GotoTypeDefinitionParams, GotoTypeDefinitionResponse};
 
 #[derive(Debug)]
 pub struct ValkyrieLanguageServer {
     proxy: Client,
+    documents: Arc<DocumentStore>,
+    database: Arc<RwLock<Database>>,
+    diagnostics: DiagnosticPublisher,
+    /// The workspace folders of the client.
+    folders: Arc<RwLock<WorkspaceFolders>>,
+    indexer: Indexer,
+    logger: Logger,
+    /// The capabilities the client sent on initialize.
+    capabilities: RwLock<ClientCapabilities>,
+    /// The settings from the initialization options, the settings of the client override them.
+    base_settings: RwLock<Settings>,
 }
 
 impl ValkyrieLanguageServer {
     pub fn launch() -> (LspService<ValkyrieLanguageServer>, ClientSocket) {
-        LspService::new(|client| ValkyrieLanguageServer { proxy: client })
+        LspService::build(|client| {
+            let documents = Arc::new(DocumentStore::default());
+            let database = Arc::new(RwLock::new(Database::default()));
+            let folders = Arc::new(RwLock::new(WorkspaceFolders::default()));
+            let diagnostics = DiagnosticPublisher::new(client.clone(), documents.clone(), database.clone());
+            let logger = Logger::new(client.clone());
+            ValkyrieLanguageServer {
+                indexer: Indexer::new(
+                    client.clone(),
+                    logger.clone(),
+                    documents.clone(),
+                    database.clone(),
+                    folders.clone(),
+                    diagnostics.clone(),
+                ),
+                diagnostics,
+                logger,
+                proxy: client,
+                documents,
+                database,
+                folders,
+                capabilities: RwLock::default(),
+                base_settings: RwLock::default(),
+            }
+        })
+        .custom_method(ExplainDiagnostic::METHOD, ValkyrieLanguageServer::explain_diagnostic)
+        .custom_method(ExternalLibraries::METHOD, ValkyrieLanguageServer::external_libraries)
+        .custom_method(WorkDoneProgressCancel::METHOD, ValkyrieLanguageServer::work_done_progress_cancel)
+        .finish()
+    }
+    /// Answer `valkyrie/explainDiagnostic` from the code registry.
+    pub async fn explain_diagnostic(&self, params: ExplainDiagnosticParams) -> Result<Option<DiagnosticExplanation>> {
+        Ok(DiagnosticCode::find(&params.code).map(|code| DiagnosticExplanation {
+            code: code.code.to_string(),
+            name: code.name.to_string(),
+            explanation: MarkupContent { kind: MarkupKind::Markdown, value: code.explanation.to_string() },
+        }))
+    }
+    /// Answer `valkyrie/externalLibraries` from the package graph.
+    pub async fn external_libraries(&self, params: ExternalLibrariesParams) -> Result<Vec<ExternalLibrary>> {
+        let snapshot = self.snapshot();
+        let graph = snapshot.package_graph();
+        // a file outside of all packages sees every library
+        let from = match &params.uri {
+            Some(uri) => {
+                let path = uri.to_file_path().map_err(|_| ValkyrieError::invalid_uri(uri))?;
+                let manifest = snapshot.file_id(uri).and_then(|file| graph.package_of_manifest(file));
+                manifest.or_else(|| graph.package_of(&path))
+            }
+            None => None,
+        };
+        Ok(external_libraries(&graph, &self.folders.read().unwrap().roots(), from))
+    }
+    /// Handle `window/workDoneProgress/cancel`, which `tower-lsp` does not route itself.
+    pub async fn work_done_progress_cancel(&self, params: WorkDoneProgressCancelParams) {
+        self.indexer.cancel(&params.token);
+    }
+    /// Take a snapshot of the database for a request, later changes are not visible in it.
+    pub fn snapshot(&self) -> Snapshot {
+        self.database.read().unwrap().snapshot()
+    }
+    /// Read the settings again and apply them.
+    ///
+    /// Clients supporting `workspace/configuration` are asked for the settings of each workspace folder, others push
+    /// them with `workspace/didChangeConfiguration`.
+    async fn load_configuration(&self, pushed: Option<&LSPAny>) {
+        let base = self.base_settings.read().unwrap().clone();
+        let pull = self.capabilities.read().unwrap().workspace.as_ref().and_then(|workspace| workspace.configuration);
+        let mut configuration = Configuration { global: base.clone(), folders: BTreeMap::new() };
+        let mut problems = vec![];
+        if pull == Some(true) {
+            let roots = self.folders.read().unwrap().roots();
+            let scopes = std::iter::once(None).chain(roots.iter().map(|root| Url::from_file_path(root).ok()));
+            let items = scopes
+                .map(|scope_uri| ConfigurationItem { scope_uri, section: Some(CONFIGURATION_SECTION.to_string()) })
+                .collect();
+            let values = match self.proxy.configuration(items).await {
+                Ok(s) => s,
+                Err(e) => {
+                    self.logger.log(MessageType::WARNING, format!("cannot read the settings: {}", e)).await;
+                    return;
+                }
+            };
+            for (index, value) in values.iter().enumerate() {
+                let (settings, found) = base.merge(value);
+                problems.extend(found);
+                match index.checked_sub(1).and_then(|index| roots.get(index)) {
+                    Some(root) => {
+                        configuration.folders.insert(root.clone(), settings);
+                    }
+                    None => configuration.global = settings,
+                }
+            }
+        }
+        else {
+            let section = match pushed {
+                Some(s) if !s.is_null() => configuration_section(s),
+                _ => return,
+            };
+            let (settings, found) = base.merge(section);
+            problems.extend(found);
+            configuration.global = settings;
+        }
+        problems.sort();
+        problems.dedup();
+        self.apply_configuration(configuration, problems).await;
+    }
+    /// Check if the client accepts `LocationLink` answers to a goto request.
+    fn link_support(&self, goto: impl Fn(&TextDocumentClientCapabilities) -> Option<&GotoCapability>) -> bool {
+        let capabilities = self.capabilities.read().unwrap();
+        capabilities.text_document.as_ref().and_then(|text| goto(text)?.link_support).unwrap_or(false)
+    }
+    /// Use new settings, report their problems to the user and update the diagnostics they change.
+    async fn apply_configuration(&self, configuration: Configuration, problems: Vec<String>) {
+        self.logger.set_level(configuration.global.log.level);
+        self.database.write().unwrap().set_configuration(configuration);
+        if !problems.is_empty() {
+            self.proxy.show_message(MessageType::WARNING, format!("invalid settings: {}", problems.join(", "))).await;
+        }
+        self.diagnostics.publish_all().await;
+    }
+    /// Copy the current content of an opened document into the database.
+    fn sync_document(&self, uri: &Url) {
+        if let Some(document) = self.documents.get(uri) {
+            let mut database = self.database.write().unwrap();
+            match is_manifest_uri(uri) {
+                true => database.set_manifest(uri.clone(), document.text),
+                false => database.set_file_text(uri.clone(), document.text, Some(document.parse)),
+            };
+        }
+    }
+    /// After a document is closed, the database follows the file on disk again, returns false if the file is gone.
+    fn reload_from_disk(&self, uri: &Url) -> bool {
+        let text = uri.to_file_path().ok().and_then(|path| std::fs::read_to_string(path).ok());
+        let mut database = self.database.write().unwrap();
+        match (text, is_manifest_uri(uri)) {
+            (Some(text), true) => {
+                database.set_manifest(uri.clone(), text);
+                true
+            }
+            (Some(text), false) => {
+                database.set_file_text(uri.clone(), text, None);
+                true
+            }
+            (None, _) => {
+                forget(&mut database, uri);
+                false
+            }
+        }
     }
 }
 
 #[tower_lsp::async_trait]
 impl LanguageServer for ValkyrieLanguageServer {
-    async fn initialize(&self, _: InitializeParams) -> Result<InitializeResult> {
+    async fn initialize(&self, params: InitializeParams) -> Result<InitializeResult> {
+        let encoding = PositionEncoding::negotiate(&params.capabilities);
+        self.documents.set_encoding(encoding);
+        *self.capabilities.write().unwrap() = params.capabilities.clone();
+        let options = params.initialization_options.clone().unwrap_or_default();
+        let (settings, problems) = Settings::default().merge(configuration_section(&options));
+        *self.base_settings.write().unwrap() = settings.clone();
+        self.apply_configuration(Configuration { global: settings, folders: BTreeMap::new() }, problems).await;
+        *self.folders.write().unwrap() = WorkspaceFolders::from_params(&params);
+        let progress = params.capabilities.window.as_ref().and_then(|window| window.work_done_progress);
+        self.indexer.set_progress(progress.unwrap_or(false));
+        let pull = params.capabilities.text_document.as_ref().and_then(|text| text.diagnostic.as_ref()).is_some();
+        self.diagnostics.set_pull(pull);
         Ok(InitializeResult {
             capabilities: ServerCapabilities {
-                position_encoding: None,
-                text_document_sync: None,
+                position_encoding: Some(encoding.kind()),
+                text_document_sync: Some(TextDocumentSyncCapability::Options(TextDocumentSyncOptions {
+                    open_close: Some(true),
+                    change: Some(TextDocumentSyncKind::INCREMENTAL),
+                    will_save: None,
+                    will_save_wait_until: None,
+                    save: Some(TextDocumentSyncSaveOptions::SaveOptions(SaveOptions {
+                        include_text: Some(false),
+                    })),
+                })),
                 selection_range_provider: None,
                 hover_provider: Some(HoverProviderCapability::Options(HoverOptions {
                     work_done_progress_options: WorkDoneProgressOptions {
@@ -63,14 +297,8 @@ impl LanguageServer for ValkyrieLanguageServer {
                         work_done_progress: Some(true),
                     },
                 })),
-                type_definition_provider: Some(TypeDefinitionProviderCapability::Options(StaticTextDocumentRegistrationOptions {
-                    id: None,
-                    document_selector: Some(vec![]),
-                })),
-                implementation_provider: Some(ImplementationProviderCapability::Options(StaticTextDocumentRegistrationOptions {
-                    id: None,
-                    document_selector: Some(vec![]),
-                })),
+                type_definition_provider: Some(TypeDefinitionProviderCapability::Simple(true)),
+                implementation_provider: Some(ImplementationProviderCapability::Simple(true)),
                 references_provider: Some(OneOf::Right(ReferencesOptions {
                     work_done_progress_options: WorkDoneProgressOptions {
                         work_done_progress: Some(true),
@@ -78,7 +306,7 @@ impl LanguageServer for ValkyrieLanguageServer {
                 })),
                 document_highlight_provider: None,
                 document_symbol_provider: None,
-                workspace_symbol_provider: None,
+                workspace_symbol_provider: Some(OneOf::Left(true)),
                 code_action_provider: Some(CodeActionProviderCapability::Options(CodeActionOptions {
                     code_action_kinds: None,
                     resolve_provider: Some(true),
@@ -90,13 +318,19 @@ impl LanguageServer for ValkyrieLanguageServer {
                 document_formatting_provider: None,
                 document_range_formatting_provider: None,
                 document_on_type_formatting_provider: None,
-                rename_provider: None,
+                rename_provider: Some(OneOf::Right(RenameOptions {
+                    prepare_provider: Some(true),
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                })),
                 document_link_provider: None,
                 color_provider: None,
                 folding_range_provider: None,
                 execute_command_provider: None,
                 workspace: Some(WorkspaceServerCapabilities {
-                    workspace_folders: None,
+                    workspace_folders: Some(WorkspaceFoldersServerCapabilities {
+                        supported: Some(true),
+                        change_notifications: Some(OneOf::Left(true)),
+                    }),
                     file_operations: None,
                 }),
                 call_hierarchy_provider: None,
@@ -105,31 +339,64 @@ impl LanguageServer for ValkyrieLanguageServer {
                 linked_editing_range_provider: None,
                 inline_value_provider: None,
                 inlay_hint_provider: None,
-                diagnostic_provider: None,
+                diagnostic_provider: Some(DiagnosticServerCapabilities::Options(DiagnosticOptions {
+                    identifier: Some("valkyrie".to_string()),
+                    inter_file_dependencies: true,
+                    workspace_diagnostics: true,
+                    work_done_progress_options: WorkDoneProgressOptions::default(),
+                })),
                 experimental: None,
             },
             server_info: Some(ServerInfo {
                 name: "Valkyrie Language Server".to_string(),
                 version: Some(env!("CARGO_PKG_VERSION").to_string()),
             }),
-            offset_encoding: Some("utf-8".to_string()),
+            offset_encoding: Some(encoding.kind().as_str().to_string()),
         })
     }
 
     async fn initialized(&self, _: InitializedParams) {
-        self.proxy
-            .log_message(MessageType::INFO, "server initialized!")
-            .await;
+        self.logger.log(MessageType::INFO, "server initialized!").await;
+        // clients reading the settings with `workspace/configuration` only send changes when asked to
+        let workspace = self.capabilities.read().unwrap().workspace.clone().unwrap_or_default();
+        if workspace.did_change_configuration.and_then(|change| change.dynamic_registration) == Some(true) {
+            let registration = Registration {
+                id: "valkyrie/didChangeConfiguration".to_string(),
+                method: DidChangeConfiguration::METHOD.to_string(),
+                register_options: None,
+            };
+            if let Err(e) = self.proxy.register_capability(vec![registration]).await {
+                self.logger.log(MessageType::WARNING, e).await;
+            }
+        }
+        self.load_configuration(None).await;
+        let roots = self.folders.read().unwrap().roots();
+        self.indexer.spawn(roots);
     }
     async fn shutdown(&self) -> Result<()> {
+        let roots = self.folders.read().unwrap().roots();
+        for root in roots {
+            self.indexer.save_index(&root).await;
+        }
         Ok(())
     }
 
     async fn did_open(&self, params: DidOpenTextDocumentParams) {
-
+        let document = params.text_document;
+        self.documents.open(document.uri.clone(), document.language_id, document.version, document.text);
+        self.sync_document(&document.uri);
+        self.diagnostics.publish(document.uri).await;
     }
     async fn did_change(&self, params: DidChangeTextDocumentParams) {
-
+        let document = params.text_document;
+        if let Err(e) = self.documents.change(&document.uri, document.version, params.content_changes) {
+            // the document is unchanged, nothing to analyze again
+            self.logger.log(MessageType::WARNING, e).await;
+            return;
+        }
+        self.sync_document(&document.uri);
+        let debounce = self.snapshot().configuration().settings(&document.uri).diagnostics.debounce;
+        self.diagnostics.schedule(document.uri, Duration::from_millis(debounce));
     }
     async fn will_save(&self, params: WillSaveTextDocumentParams) {
 
@@ -140,49 +407,53 @@ impl LanguageServer for ValkyrieLanguageServer {
     }
 
     async fn did_save(&self, params: DidSaveTextDocumentParams) {
-
+        self.diagnostics.publish(params.text_document.uri).await;
     }
     async fn did_close(&self, params: DidCloseTextDocumentParams) {
-
+        let uri = params.text_document.uri;
+        self.documents.close(&uri);
+        // files of the workspace keep their diagnostics from disk, others are forgotten
+        if self.folders.read().unwrap().contains(&uri) && self.reload_from_disk(&uri) {
+            self.diagnostics.publish(uri).await;
+        }
+        else {
+            forget(&mut self.database.write().unwrap(), &uri);
+            self.diagnostics.clear(uri).await;
+        }
     }
     async fn goto_declaration(&self, params: GotoDeclarationParams) -> Result<Option<GotoDeclarationResponse>> {
-        let here1 = Location {
-            uri: params.text_document_position_params.text_document.uri.clone(),
-            range: Range {
-                start: Position {
-                    line: 0,
-                    character: 0,
-                },
-                end: params.text_document_position_params.position,
-            },
-        };
-        let here2 = Location {
-            uri: params.text_document_position_params.text_document.uri.clone(),
-            range: Range {
-                start: params.text_document_position_params.position,
-                end: Position {
-                    line: 0,
-                    character: 0,
-                },
-            },
-        };
-        Ok(Some(GotoDefinitionResponse::Array(vec![here1, here2])))
+        let position = params.text_document_position_params;
+        let guard = RequestGuard::for_document(self.documents.clone(), &position.text_document.uri);
+        let link_support = self.link_support(|text| text.declaration.as_ref());
+        Ok(goto_declaration(&self.snapshot(), &guard, &position.text_document.uri, position.position, link_support)?)
     }
 
     async fn goto_definition(&self, params: GotoDefinitionParams) -> Result<Option<GotoDefinitionResponse>> {
+        let position = params.text_document_position_params;
+        let guard = RequestGuard::for_document(self.documents.clone(), &position.text_document.uri);
+        let link_support = self.link_support(|text| text.definition.as_ref());
         // a function or method may have multiple definition locations
-        Ok(Some(GotoDefinitionResponse::Array(vec![])))
+        Ok(goto_definition(&self.snapshot(), &guard, &position.text_document.uri, position.position, link_support)?)
     }
     async fn goto_type_definition(&self, params: GotoTypeDefinitionParams) -> Result<Option<GotoTypeDefinitionResponse>> {
+        let position = params.text_document_position_params;
+        let guard = RequestGuard::for_document(self.documents.clone(), &position.text_document.uri);
+        let link_support = self.link_support(|text| text.type_definition.as_ref());
         // each type has only one declaration position
         // But in the case of repeated definitions by mistake, there will be multiple declaration locations
-        Ok(Some(GotoDefinitionResponse::Array(vec![])))
+        Ok(goto_type_definition(&self.snapshot(), &guard, &position.text_document.uri, position.position, link_support)?)
     }
     async fn goto_implementation(&self, params: GotoImplementationParams) -> Result<Option<GotoImplementationResponse>> {
-        Ok(Some(GotoDefinitionResponse::Array(vec![])))
+        let position = params.text_document_position_params;
+        let guard = RequestGuard::for_document(self.documents.clone(), &position.text_document.uri);
+        let link_support = self.link_support(|text| text.implementation.as_ref());
+        Ok(goto_implementation(&self.snapshot(), &guard, &position.text_document.uri, position.position, link_support)?)
     }
     async fn references(&self, params: ReferenceParams) -> Result<Option<Vec<Location>>> {
-        Ok(Some(vec![]))
+        let position = params.text_document_position;
+        let guard = RequestGuard::for_document(self.documents.clone(), &position.text_document.uri);
+        let include_declaration = params.context.include_declaration;
+        Ok(find_references(&self.snapshot(), &guard, &position.text_document.uri, position.position, include_declaration).await?)
     }
     async fn prepare_call_hierarchy(&self, params: CallHierarchyPrepareParams) -> Result<Option<Vec<CallHierarchyItem>>> {
         Err(Error::method_not_found())
@@ -232,8 +503,10 @@ impl LanguageServer for ValkyrieLanguageServer {
         Err(Error::method_not_found())
     }
     async fn hover(&self, params: HoverParams) -> Result<Option<Hover>> {
-        let debug = format!("```json\n{:#?}\n```", params);
-        Ok(Some(Hover { contents: HoverContents::Markup(MarkupContent { kind: MarkupKind::Markdown, value: debug }), range: None }))
+        let position = params.text_document_position_params;
+        let guard = RequestGuard::for_document(self.documents.clone(), &position.text_document.uri);
+        let format = DocumentFormat::for_hover(&self.capabilities.read().unwrap());
+        Ok(hover(&self.snapshot(), &guard, &position.text_document.uri, position.position, format)?)
     }
     async fn code_lens(&self, params: CodeLensParams) -> Result<Option<Vec<CodeLens>>> {
         Err(Error::method_not_found())
@@ -279,10 +552,10 @@ impl LanguageServer for ValkyrieLanguageServer {
         Err(Error::method_not_found())
     }
     async fn diagnostic(&self, params: DocumentDiagnosticParams) -> Result<DocumentDiagnosticReportResult> {
-        Err(Error::method_not_found())
+        Ok(document_diagnostics(&self.snapshot(), &self.documents, params)?)
     }
     async fn workspace_diagnostic(&self, params: WorkspaceDiagnosticParams) -> Result<WorkspaceDiagnosticReportResult> {
-        Err(Error::method_not_found())
+        Ok(workspace_diagnostics(&self.proxy, &self.snapshot(), &self.documents, params).await?)
     }
     async fn signature_help(&self, params: SignatureHelpParams) -> Result<Option<SignatureHelp>> {
         Err(Error::method_not_found())
@@ -329,25 +602,44 @@ impl LanguageServer for ValkyrieLanguageServer {
         Err(Error::method_not_found())
     }
     async fn rename(&self, params: RenameParams) -> Result<Option<WorkspaceEdit>> {
-        Err(Error::method_not_found())
+        let position = params.text_document_position;
+        let guard = RequestGuard::for_document(self.documents.clone(), &position.text_document.uri);
+        Ok(rename_edits(&self.snapshot(), &guard, &position.text_document.uri, position.position, &params.new_name).await?)
     }
     async fn prepare_rename(&self, params: TextDocumentPositionParams) -> Result<Option<PrepareRenameResponse>> {
-        Err(Error::method_not_found())
+        let guard = RequestGuard::for_document(self.documents.clone(), &params.text_document.uri);
+        Ok(rename_range(&self.snapshot(), &guard, &params.text_document.uri, params.position)?)
     }
     async fn linked_editing_range(&self, params: LinkedEditingRangeParams) -> Result<Option<LinkedEditingRanges>> {
         Err(Error::method_not_found())
     }
     async fn symbol(&self, params: WorkspaceSymbolParams) -> Result<Option<Vec<SymbolInformation>>> {
-        Err(Error::method_not_found())
+        let guard = RequestGuard::new(self.documents.clone());
+        Ok(Some(workspace_symbols(&self.snapshot(), &guard, &params.query).await?))
     }
     async fn symbol_resolve(&self, params: WorkspaceSymbol) -> Result<WorkspaceSymbol> {
         Err(Error::method_not_found())
     }
     async fn did_change_configuration(&self, params: DidChangeConfigurationParams) {
-
+        self.load_configuration(Some(&params.settings)).await;
     }
     async fn did_change_workspace_folders(&self, params: DidChangeWorkspaceFoldersParams) {
-
+        for folder in params.event.removed {
+            if let Ok(root) = folder.uri.to_file_path() {
+                self.indexer.unload_folder(&root).await;
+            }
+        }
+        let mut added = vec![];
+        for folder in params.event.added {
+            if let Ok(root) = folder.uri.to_file_path() {
+                if self.folders.write().unwrap().insert(root.clone()) {
+                    added.push(root);
+                }
+            }
+        }
+        self.indexer.spawn(added);
+        // the folders may have settings of their own
+        self.load_configuration(None).await;
     }
     async fn will_create_files(&self, params: CreateFilesParams) -> Result<Option<WorkspaceEdit>> {
         Err(Error::method_not_found())
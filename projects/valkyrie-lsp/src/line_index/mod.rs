@@ -0,0 +1,203 @@
+use std::ops::Range as Span;
+
+use tower_lsp::lsp_types::{ClientCapabilities, Position, PositionEncodingKind, Range};
+
+/// The unit a [Position] character offset is counted in, negotiated with the client on initialize.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash)]
+pub enum PositionEncoding {
+    /// Count in UTF-8 code units, aka bytes.
+    Utf8,
+    /// Count in UTF-16 code units, the mandatory encoding of the protocol.
+    #[default]
+    Utf16,
+    /// Count in UTF-32 code units, aka unicode scalar values.
+    Utf32,
+}
+
+/// Converts between byte offsets and [Position]s of a text in the negotiated encoding.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct LineIndex {
+    encoding: PositionEncoding,
+    text_length: usize,
+    /// The byte offset of each line start, the first line always starts at 0.
+    line_starts: Vec<usize>,
+    /// The byte offset of the line terminator of each line, or the text length for the last line.
+    line_ends: Vec<usize>,
+    /// The non-ascii characters of each line, as pairs of byte offset in line and the char.
+    wide_chars: Vec<Vec<(usize, char)>>,
+}
+
+impl PositionEncoding {
+    /// Pick an encoding from the client capabilities.
+    ///
+    /// UTF-8 is preferred since it needs no conversion, otherwise the first encoding the client lists is used.
+    pub fn negotiate(capabilities: &ClientCapabilities) -> Self {
+        let mut offered: Vec<PositionEncoding> = vec![];
+        if let Some(general) = &capabilities.general {
+            for kind in general.position_encodings.iter().flatten() {
+                offered.extend(PositionEncoding::from_kind(kind));
+            }
+        }
+        // clangd extension, used by clients predating position encoding negotiation
+        for name in capabilities.offset_encoding.iter().flatten() {
+            offered.extend(PositionEncoding::from_name(name));
+        }
+        if offered.contains(&PositionEncoding::Utf8) {
+            return PositionEncoding::Utf8;
+        }
+        offered.first().copied().unwrap_or_default()
+    }
+    /// Parse the encoding from the protocol kind.
+    pub fn from_kind(kind: &PositionEncodingKind) -> Option<Self> {
+        Self::from_name(kind.as_str())
+    }
+    /// Parse the encoding from its protocol name.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "utf-8" => Some(PositionEncoding::Utf8),
+            "utf-16" => Some(PositionEncoding::Utf16),
+            "utf-32" => Some(PositionEncoding::Utf32),
+            _ => None,
+        }
+    }
+    /// Get the protocol kind of the encoding.
+    pub fn kind(&self) -> PositionEncodingKind {
+        match self {
+            PositionEncoding::Utf8 => PositionEncodingKind::UTF8,
+            PositionEncoding::Utf16 => PositionEncodingKind::UTF16,
+            PositionEncoding::Utf32 => PositionEncodingKind::UTF32,
+        }
+    }
+    /// Count the code units of a char in this encoding.
+    pub fn char_length(&self, char: char) -> usize {
+        match self {
+            PositionEncoding::Utf8 => char.len_utf8(),
+            PositionEncoding::Utf16 => char.len_utf16(),
+            PositionEncoding::Utf32 => 1,
+        }
+    }
+    /// Count the code units of a text in this encoding.
+    pub fn text_length(&self, text: &str) -> usize {
+        match self {
+            PositionEncoding::Utf8 => text.len(),
+            _ => text.chars().map(|c| self.char_length(c)).sum(),
+        }
+    }
+}
+
+impl LineIndex {
+    /// Index the line structure of the text, `\n`, `\r\n` and `\r` are all line terminators.
+    pub fn new(text: &str, encoding: PositionEncoding) -> Self {
+        let mut index = LineIndex {
+            encoding,
+            text_length: text.len(),
+            line_starts: vec![0],
+            line_ends: vec![],
+            wide_chars: vec![vec![]],
+        };
+        let bytes = text.as_bytes();
+        let mut chars = text.char_indices();
+        while let Some((offset, char)) = chars.next() {
+            let line_start = *index.line_starts.last().unwrap_or(&0);
+            match char {
+                '\n' => index.push_line(offset, offset + 1),
+                '\r' if bytes.get(offset + 1) == Some(&b'\n') => {
+                    chars.next();
+                    index.push_line(offset, offset + 2)
+                }
+                '\r' => index.push_line(offset, offset + 1),
+                _ if !char.is_ascii() => {
+                    if let Some(line) = index.wide_chars.last_mut() {
+                        line.push((offset - line_start, char))
+                    }
+                }
+                _ => {}
+            }
+        }
+        index.line_ends.push(text.len());
+        index
+    }
+    fn push_line(&mut self, terminator: usize, next_start: usize) {
+        self.line_ends.push(terminator);
+        self.line_starts.push(next_start);
+        self.wide_chars.push(vec![]);
+    }
+    /// The encoding positions are counted in.
+    pub fn encoding(&self) -> PositionEncoding {
+        self.encoding
+    }
+    /// The number of lines, a trailing terminator starts an empty last line.
+    pub fn lines(&self) -> usize {
+        self.line_starts.len()
+    }
+    /// The byte length of the indexed text.
+    pub fn text_length(&self) -> usize {
+        self.text_length
+    }
+    /// The byte span of a line, excluding the terminator.
+    pub fn line_span(&self, line: usize) -> Option<Span<usize>> {
+        Some(*self.line_starts.get(line)?..*self.line_ends.get(line)?)
+    }
+    /// Convert a byte offset to a position, offsets past the end map to the end of text.
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text_length);
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        // offsets inside a `\r\n` terminator belong to the end of line
+        let column = offset.min(self.line_ends[line]) - self.line_starts[line];
+        Position { line: line as u32, character: self.encode_column(line, column) as u32 }
+    }
+    /// Convert a position to a byte offset.
+    ///
+    /// Lines past the end map to the end of text, characters past the line end map to the line end,
+    /// and characters inside a multi-unit char map to the start of that char.
+    pub fn offset(&self, position: Position) -> usize {
+        let line = position.line as usize;
+        if line >= self.lines() {
+            return self.text_length;
+        }
+        let line_length = self.line_ends[line] - self.line_starts[line];
+        self.line_starts[line] + self.decode_column(line, position.character as usize).min(line_length)
+    }
+    /// Convert a byte span to a range.
+    pub fn range(&self, span: Span<usize>) -> Range {
+        Range { start: self.position(span.start), end: self.position(span.end) }
+    }
+    /// Convert a range to a byte span, a reversed range is collapsed to its start.
+    pub fn span(&self, range: Range) -> Span<usize> {
+        let start = self.offset(range.start);
+        let end = self.offset(range.end).max(start);
+        start..end
+    }
+    /// Convert a byte column of the line to the negotiated encoding.
+    fn encode_column(&self, line: usize, column: usize) -> usize {
+        let mut encoded = column;
+        for (offset, char) in &self.wide_chars[line] {
+            if *offset >= column {
+                break;
+            }
+            encoded = encoded + self.encoding.char_length(*char) - char.len_utf8();
+        }
+        encoded
+    }
+    /// Convert a column in the negotiated encoding to a byte column of the line.
+    fn decode_column(&self, line: usize, column: usize) -> usize {
+        // walk the line in encoded units, ascii chars take one unit in every encoding
+        let mut bytes = 0;
+        let mut units = 0;
+        for (offset, char) in &self.wide_chars[line] {
+            let ascii = offset - bytes;
+            if units + ascii >= column {
+                return bytes + column - units;
+            }
+            units += ascii;
+            bytes = *offset;
+            let width = self.encoding.char_length(*char);
+            if units + width > column {
+                return bytes;
+            }
+            units += width;
+            bytes += char.len_utf8();
+        }
+        bytes + column - units
+    }
+}
@@ -0,0 +1,84 @@
+use tower_lsp::lsp_types::{GotoDefinitionResponse, Position, Url};
+
+use super::{NavigationTarget, definition::definition_targets, definitions_at, file_offset, goto_response};
+use crate::{Definition, ItemKind, ItemLoc, Snapshot, ValkyrieResult, request::RequestGuard};
+
+/// Answer `textDocument/declaration`, the import the name under the cursor comes through, or the abstract trait
+/// methods a method implements.
+///
+/// Other symbols are declared where they are defined.
+pub(crate) fn goto_declaration(
+    db: &Snapshot,
+    guard: &RequestGuard,
+    uri: &Url,
+    position: Position,
+    link_support: bool,
+) -> ValkyrieResult<Option<GotoDefinitionResponse>> {
+    let (file, offset) = match file_offset(db, guard, uri, position)? {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let (span, definitions) = match definitions_at(db, file, offset) {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let import = db.infer(file).resolutions.get(&span.start).and_then(|resolution| resolution.import);
+    if let Some(import) = import {
+        let source = &db.item_sources(file).imports[import as usize];
+        let target = NavigationTarget { file, full_span: source.span.clone(), focus_span: source.name_span.clone() };
+        return goto_response(db, guard, (uri, span), vec![target], link_support);
+    }
+    let mut targets = vec![];
+    for definition in &definitions {
+        let declared = match definition {
+            Definition::Item(loc) => {
+                abstract_methods(db, *loc).into_iter().map(|loc| NavigationTarget::item(db, loc)).collect()
+            }
+            _ => vec![],
+        };
+        let declared = match declared.is_empty() {
+            true => definition_targets(db, file, definition),
+            false => declared,
+        };
+        for target in declared {
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+    goto_response(db, guard, (uri, span), targets, link_support)
+}
+
+/// The methods without body of the traits above the container of a method, with the same name.
+pub(super) fn abstract_methods(db: &Snapshot, method: ItemLoc) -> Vec<ItemLoc> {
+    let tree = db.item_tree(method.file);
+    let data = tree.item(method.index);
+    let container = match data.parent {
+        Some(parent) if data.kind == ItemKind::Method => ItemLoc { file: method.file, index: parent },
+        _ => return vec![],
+    };
+    let map = db.def_map();
+    let mut out = vec![];
+    let mut pending = vec![container];
+    let mut seen = vec![container];
+    while let Some(current) = pending.pop() {
+        // the traits listing the current type as a sub type, the traits they extend next
+        for (super_type, subtypes) in &map.subtypes {
+            if !subtypes.contains(&current) || seen.contains(super_type) {
+                continue;
+            }
+            seen.push(*super_type);
+            let super_tree = db.item_tree(super_type.file);
+            if super_tree.item(super_type.index).kind != ItemKind::Trait {
+                continue;
+            }
+            pending.push(*super_type);
+            let methods = super_tree.members(super_type.index).filter(|index| {
+                let item = super_tree.item(*index);
+                item.kind == ItemKind::Method && item.name == data.name && !item.has_body
+            });
+            out.extend(methods.map(|index| ItemLoc { file: super_type.file, index }));
+        }
+    }
+    out
+}
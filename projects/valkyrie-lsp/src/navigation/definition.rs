@@ -0,0 +1,67 @@
+use tower_lsp::lsp_types::{GotoDefinitionResponse, Position, Url};
+
+use super::{NavigationTarget, definitions_at, file_offset, goto_response};
+use crate::{Definition, FileId, Snapshot, SyntaxKind, ValkyrieResult, request::RequestGuard};
+
+/// Answer `textDocument/definition`, every declaration of the symbol under the cursor.
+///
+/// Locals, items, imports, qualified paths and members are followed through name resolution, across files.
+pub(crate) fn goto_definition(
+    db: &Snapshot,
+    guard: &RequestGuard,
+    uri: &Url,
+    position: Position,
+    link_support: bool,
+) -> ValkyrieResult<Option<GotoDefinitionResponse>> {
+    let (file, offset) = match file_offset(db, guard, uri, position)? {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let (span, definitions) = match definitions_at(db, file, offset) {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let mut targets = vec![];
+    for definition in &definitions {
+        for target in definition_targets(db, file, definition) {
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+    goto_response(db, guard, (uri, span), targets, link_support)
+}
+
+/// Where a definition is declared, a namespace in every file declaring it.
+pub(super) fn definition_targets(db: &Snapshot, file: FileId, definition: &Definition) -> Vec<NavigationTarget> {
+    match definition {
+        Definition::Item(loc) => vec![NavigationTarget::item(db, *loc)],
+        Definition::Local(local) => {
+            let span = db.infer(file).locals[*local as usize].span.clone();
+            vec![NavigationTarget { file, full_span: span.clone(), focus_span: span }]
+        }
+        Definition::Namespace(path) => {
+            let map = db.def_map();
+            let files = map.namespaces.get(path).into_iter().flatten();
+            files.filter_map(|file| namespace_target(db, *file, definition)).collect()
+        }
+    }
+}
+
+/// The first `namespace` declaration of the file naming the namespace, focused on the last segment naming it.
+fn namespace_target(db: &Snapshot, file: FileId, namespace: &Definition) -> Option<NavigationTarget> {
+    let root = db.parse(file).syntax();
+    let infer = db.infer(file);
+    let mut declared: Vec<NavigationTarget> = infer
+        .resolutions
+        .iter()
+        .filter(|(_, resolution)| resolution.definitions.contains(namespace))
+        .filter_map(|(offset, _)| {
+            let token = root.token_at_offset(*offset).pick_best(|kind| usize::from(kind == SyntaxKind::Ident))?;
+            let declaration = token.ancestors().find(|node| node.kind() == SyntaxKind::NamespaceDecl)?;
+            Some(NavigationTarget { file, full_span: declaration.trimmed_span(), focus_span: token.span() })
+        })
+        .collect();
+    declared.sort_by_key(|target| target.focus_span.start);
+    declared.into_iter().next()
+}
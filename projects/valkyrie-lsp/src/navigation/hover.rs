@@ -0,0 +1,145 @@
+use std::ops::Range;
+
+use tower_lsp::lsp_types::{Hover, HoverContents, MarkupContent, Position, Url};
+
+use super::definitions_at;
+use crate::{
+    ColorSchema, Definition, DocumentFormat, DocumentRenderer, FileId, Fixity, ItemKind, ItemLoc, KeywordDoc, OperatorDoc,
+    Snapshot, SyntaxKind, Ty, ValkyrieResult, indexer::is_manifest_uri, request::RequestGuard,
+};
+
+/// Answer `textDocument/hover`, what the identifier under the cursor refers to, with its signature and docs.
+///
+/// Keywords and operators show their entry in the catalog, the text is rendered in the format the client accepts.
+pub(crate) fn hover(
+    db: &Snapshot,
+    guard: &RequestGuard,
+    uri: &Url,
+    position: Position,
+    format: DocumentFormat,
+) -> ValkyrieResult<Option<Hover>> {
+    let file = match db.file_id(uri) {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let index = guard.line_index(db, uri, file)?;
+    let offset = index.offset(position);
+    let mut out = format.renderer(ColorSchema::default());
+    let span = match definitions_at(db, file, offset) {
+        Some((span, definitions)) => {
+            // a name may be declared several times, all declarations are shown
+            for (index, definition) in definitions.iter().enumerate() {
+                if index > 0 {
+                    out.write_rule();
+                }
+                describe(db, file, definition, out.as_mut());
+            }
+            span
+        }
+        None if is_manifest_uri(uri) => return Ok(None),
+        None => match describe_token(db, file, offset, out.as_mut()) {
+            Some(s) => s,
+            None => return Ok(None),
+        },
+    };
+    Ok(Some(Hover {
+        contents: HoverContents::Markup(MarkupContent { kind: format.markup_kind(), value: out.finish() }),
+        range: Some(index.range(span)),
+    }))
+}
+
+fn describe(db: &Snapshot, file: FileId, definition: &Definition, out: &mut dyn DocumentRenderer) {
+    match definition {
+        Definition::Item(loc) => describe_item(db, *loc, out),
+        Definition::Local(local) => {
+            let infer = db.infer(file);
+            let local = &infer.locals[*local as usize];
+            let binding = if local.mutable { "let mut" } else { "let" };
+            let signature = match &local.ty {
+                Ty::Unknown => format!("{} {}", binding, local.name),
+                ty => format!("{} {}: {}", binding, local.name, ty),
+            };
+            out.write_keyword("local");
+            out.write_text(" ");
+            out.write_attribute(&local.name);
+            out.write_newline();
+            out.write_newline();
+            out.write_code(&signature);
+        }
+        Definition::Namespace(path) => {
+            out.write_keyword("namespace");
+            out.write_text(" ");
+            out.write_namespace(&path.join("::"));
+        }
+    }
+}
+
+/// The catalog entry of the keyword or operator at the offset, `>>` and `>>=` span several tokens.
+fn describe_token(db: &Snapshot, file: FileId, offset: usize, out: &mut dyn DocumentRenderer) -> Option<Range<usize>> {
+    let root = db.parse(file).syntax();
+    let token = root.token_at_offset(offset).pick_best(|kind| usize::from(kind.is_keyword() || kind.is_operator()))?;
+    let parent = token.parent();
+    let fixity = match parent.kind() {
+        SyntaxKind::PrefixExpr => Fixity::Prefix,
+        SyntaxKind::BinaryExpr | SyntaxKind::CastExpr => Fixity::Infix,
+        SyntaxKind::FieldExpr | SyntaxKind::TryExpr => Fixity::Postfix,
+        _ => {
+            KeywordDoc::find(token.kind())?.render(out);
+            return Some(token.span());
+        }
+    };
+    let (span, symbol) = match parent.kind() {
+        SyntaxKind::BinaryExpr => {
+            let tokens: Vec<_> = parent.child_tokens().filter(|t| !t.kind().is_trivia()).collect();
+            let symbol: String = tokens.iter().map(|t| t.text()).collect();
+            (tokens.first()?.span().start..tokens.last()?.span().end, symbol)
+        }
+        _ => (token.span(), token.text().to_string()),
+    };
+    match OperatorDoc::find(&symbol, fixity) {
+        Some(doc) => {
+            doc.render(out);
+            Some(span)
+        }
+        None => {
+            KeywordDoc::find(token.kind())?.render(out);
+            Some(token.span())
+        }
+    }
+}
+
+fn describe_item(db: &Snapshot, loc: ItemLoc, out: &mut dyn DocumentRenderer) {
+    let tree = db.item_tree(loc.file);
+    let item = tree.item(loc.index);
+    out.write_keyword(kind_name(item.kind));
+    out.write_text(" ");
+    let path = item.namepath.join("::");
+    match item.kind {
+        ItemKind::Function | ItemKind::Method => out.write_function(&path),
+        ItemKind::Trait => out.write_trait(&path),
+        ItemKind::Field | ItemKind::Variant => out.write_attribute(&path),
+        _ => out.write_class(&path),
+    }
+    out.write_newline();
+    out.write_newline();
+    out.write_code(&item.signature);
+    if let Some(docs) = &item.docs {
+        out.write_rule();
+        out.write_markdown(docs);
+    }
+}
+
+fn kind_name(kind: ItemKind) -> &'static str {
+    match kind {
+        ItemKind::Class => "class",
+        ItemKind::Structure => "structure",
+        ItemKind::Trait => "trait",
+        ItemKind::Imply => "imply",
+        ItemKind::Union => "union",
+        ItemKind::Variant => "variant",
+        ItemKind::Function => "function",
+        ItemKind::Method => "method",
+        ItemKind::Field => "field",
+        ItemKind::TypeAlias => "type alias",
+    }
+}
@@ -0,0 +1,92 @@
+use tower_lsp::lsp_types::{GotoDefinitionResponse, Position, Url};
+
+use super::{NavigationTarget, definitions_at, file_offset, goto_response};
+use crate::{Definition, ItemKind, ItemLoc, Snapshot, ValkyrieResult, request::RequestGuard};
+
+/// Answer `textDocument/implementation`, the types implementing the trait under the cursor, or the overrides of the
+/// trait method under the cursor.
+///
+/// Types implementing a sub trait implement the trait too, dependencies included.
+pub(crate) fn goto_implementation(
+    db: &Snapshot,
+    guard: &RequestGuard,
+    uri: &Url,
+    position: Position,
+    link_support: bool,
+) -> ValkyrieResult<Option<GotoDefinitionResponse>> {
+    let (file, offset) = match file_offset(db, guard, uri, position)? {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let (span, definitions) = match definitions_at(db, file, offset) {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let mut targets = vec![];
+    for definition in &definitions {
+        let loc = match definition {
+            Definition::Item(loc) => *loc,
+            _ => continue,
+        };
+        let tree = db.item_tree(loc.file);
+        let data = tree.item(loc.index);
+        let implementations = match (data.kind, data.parent) {
+            (ItemKind::Trait, _) => implementors(db, loc),
+            (ItemKind::Method, Some(parent)) if tree.item(parent).kind == ItemKind::Trait => {
+                overrides(db, ItemLoc { file: loc.file, index: parent }, &data.name)
+            }
+            _ => continue,
+        };
+        for target in implementations.into_iter().map(|loc| NavigationTarget::item(db, loc)) {
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+    goto_response(db, guard, (uri, span), targets, link_support)
+}
+
+/// The classes, structures, unions and `imply` blocks below a trait, through its sub traits.
+fn implementors(db: &Snapshot, r#trait: ItemLoc) -> Vec<ItemLoc> {
+    let map = db.def_map();
+    let mut out = vec![];
+    let mut pending = vec![r#trait];
+    let mut seen = vec![r#trait];
+    while let Some(current) = pending.pop() {
+        for subtype in map.subtypes.get(&current).into_iter().flatten() {
+            if seen.contains(subtype) {
+                continue;
+            }
+            seen.push(*subtype);
+            match db.item_tree(subtype.file).item(subtype.index).kind {
+                ItemKind::Trait => pending.push(*subtype),
+                _ => out.push(*subtype),
+            }
+        }
+    }
+    out
+}
+
+/// The methods with body of the same name in the types implementing a trait, and in their `imply` blocks.
+fn overrides(db: &Snapshot, r#trait: ItemLoc, name: &str) -> Vec<ItemLoc> {
+    let map = db.def_map();
+    let mut containers = vec![];
+    for implementor in implementors(db, r#trait) {
+        containers.push(implementor);
+        containers.extend(map.implementations.get(&implementor).into_iter().flatten().copied());
+    }
+    let mut out = vec![];
+    for container in containers {
+        let tree = db.item_tree(container.file);
+        let methods = tree.members(container.index).filter(|index| {
+            let item = tree.item(*index);
+            item.kind == ItemKind::Method && item.name == name && item.has_body
+        });
+        for loc in methods.map(|index| ItemLoc { file: container.file, index }) {
+            if !out.contains(&loc) {
+                out.push(loc);
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,131 @@
+use std::ops::Range;
+
+pub(crate) use self::{
+    declaration::goto_declaration,
+    definition::goto_definition,
+    hover::hover,
+    implementation::goto_implementation,
+    references::{find_references, rename_edits, rename_range},
+    symbols::workspace_symbols,
+    type_definition::goto_type_definition,
+};
+use tower_lsp::lsp_types::{GotoDefinitionResponse, Location, LocationLink, Position, Url};
+
+use crate::{
+    Definition, FileId, ItemKind, ItemLoc, Snapshot, ValkyrieResult, is_ident_continue, is_ident_start, request::RequestGuard,
+};
+
+mod declaration;
+mod definition;
+mod hover;
+mod implementation;
+mod references;
+mod symbols;
+mod type_definition;
+
+/// A place to jump to, the whole declaration and the part to select in it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) struct NavigationTarget {
+    pub file: FileId,
+    pub full_span: Range<usize>,
+    pub focus_span: Range<usize>,
+}
+
+impl NavigationTarget {
+    /// The declaration of an item, focused on its name.
+    pub fn item(db: &Snapshot, loc: ItemLoc) -> Self {
+        let source = &db.item_sources(loc.file).items[loc.index as usize];
+        Self { file: loc.file, full_span: source.span.clone(), focus_span: source.name_span.clone() }
+    }
+}
+
+/// The answer of the goto requests, links from the origin if the client supports them, plain locations otherwise.
+pub(crate) fn goto_response(
+    db: &Snapshot,
+    guard: &RequestGuard,
+    origin: (&Url, Range<usize>),
+    targets: Vec<NavigationTarget>,
+    link_support: bool,
+) -> ValkyrieResult<Option<GotoDefinitionResponse>> {
+    if targets.is_empty() {
+        return Ok(None);
+    }
+    let (origin_uri, origin_span) = origin;
+    let origin_range = match db.file_id(origin_uri) {
+        Some(file) => guard.line_index(db, origin_uri, file)?.range(origin_span),
+        None => return Ok(None),
+    };
+    let mut locations = vec![];
+    let mut links = vec![];
+    for target in targets {
+        let uri = db.file_url(target.file);
+        let index = guard.line_index(db, &uri, target.file)?;
+        match link_support {
+            true => links.push(LocationLink {
+                origin_selection_range: Some(origin_range),
+                target_uri: uri,
+                target_range: index.range(target.full_span),
+                target_selection_range: index.range(target.focus_span),
+            }),
+            false => locations.push(Location { uri, range: index.range(target.focus_span) }),
+        }
+    }
+    match link_support {
+        true => Ok(Some(GotoDefinitionResponse::Link(links))),
+        false => Ok(Some(GotoDefinitionResponse::Array(locations))),
+    }
+}
+
+/// The file and the offset of a position, none if the file is unknown.
+pub(crate) fn file_offset(
+    db: &Snapshot,
+    guard: &RequestGuard,
+    uri: &Url,
+    position: Position,
+) -> ValkyrieResult<Option<(FileId, usize)>> {
+    match db.file_id(uri) {
+        Some(file) => Ok(Some((file, guard.line_index(db, uri, file)?.offset(position)))),
+        None => Ok(None),
+    }
+}
+
+/// The identifier under the cursor and what it refers to, a reference, a declaration or a local binding.
+pub(crate) fn definitions_at(db: &Snapshot, file: FileId, offset: usize) -> Option<(Range<usize>, Vec<Definition>)> {
+    let text = db.file_text(file)?;
+    let span = ident_at(&text, offset)?;
+    let infer = db.infer(file);
+    if let Some(resolution) = infer.resolutions.get(&span.start) {
+        if !resolution.definitions.is_empty() {
+            return Some((span, resolution.definitions.clone()));
+        }
+    }
+    let tree = db.item_tree(file);
+    let declared: Vec<Definition> = db
+        .item_sources(file)
+        .items
+        .iter()
+        .enumerate()
+        .filter(|(index, source)| source.name_span == span && tree.items[*index].kind != ItemKind::Imply)
+        .map(|(index, _)| Definition::Item(ItemLoc { file, index: index as u32 }))
+        .collect();
+    if !declared.is_empty() {
+        return Some((span, declared));
+    }
+    let local = infer.locals.iter().position(|local| local.span == span)?;
+    Some((span, vec![Definition::Local(local as u32)]))
+}
+
+/// The span of the identifier touching the offset, the cursor may be right after it.
+pub(crate) fn ident_at(text: &str, offset: usize) -> Option<Range<usize>> {
+    let before = text.get(..offset)?;
+    let start =
+        before.char_indices().rev().take_while(|(_, char)| is_ident_continue(*char)).last().map_or(offset, |(index, _)| index);
+    let end = match text[offset..].char_indices().find(|(_, char)| !is_ident_continue(*char)) {
+        Some((index, _)) => offset + index,
+        None => text.len(),
+    };
+    match text[start..end].chars().next() {
+        Some(first) if is_ident_start(first) => Some(start..end),
+        _ => None,
+    }
+}
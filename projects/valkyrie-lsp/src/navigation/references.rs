@@ -0,0 +1,162 @@
+use std::{collections::HashMap, ops::Range};
+
+use tower_lsp::lsp_types::{Location, Position, PrepareRenameResponse, TextEdit, Url, WorkspaceEdit};
+
+use super::{definitions_at, ident_at};
+use crate::{
+    Definition, FileId, Snapshot, ValkyrieErrorKind, ValkyrieResult, is_ident_continue, is_ident_start, request::RequestGuard,
+};
+
+/// The file, the identifier span and the definitions under the cursor.
+type Target = (FileId, Range<usize>, Vec<Definition>);
+
+/// Answer `textDocument/references`, the uses of the symbol under the cursor in all files that can see it.
+pub(crate) async fn find_references(
+    db: &Snapshot,
+    guard: &RequestGuard,
+    uri: &Url,
+    position: Position,
+    include_declaration: bool,
+) -> ValkyrieResult<Option<Vec<Location>>> {
+    let (file, definitions) = match target(db, guard, uri, position)? {
+        Some((file, _, definitions)) => (file, definitions),
+        None => return Ok(None),
+    };
+    let spans = reference_spans(db, guard, file, &definitions, include_declaration).await?;
+    let mut out = vec![];
+    for (file, spans) in group(spans) {
+        let uri = db.file_url(file);
+        let index = guard.line_index(db, &uri, file)?;
+        out.extend(spans.into_iter().map(|span| Location { uri: uri.clone(), range: index.range(span) }));
+    }
+    Ok(Some(out))
+}
+
+/// Answer `textDocument/prepareRename`, the identifier under the cursor if it names a declaration or a local.
+pub(crate) fn rename_range(
+    db: &Snapshot,
+    guard: &RequestGuard,
+    uri: &Url,
+    position: Position,
+) -> ValkyrieResult<Option<PrepareRenameResponse>> {
+    match target(db, guard, uri, position)? {
+        Some((file, span, _)) => Ok(Some(PrepareRenameResponse::Range(guard.line_index(db, uri, file)?.range(span)))),
+        None => Ok(None),
+    }
+}
+
+/// Answer `textDocument/rename`, replace the declarations and all uses spelled with the old name.
+///
+/// Uses through an import alias keep the alias.
+pub(crate) async fn rename_edits(
+    db: &Snapshot,
+    guard: &RequestGuard,
+    uri: &Url,
+    position: Position,
+    new_name: &str,
+) -> ValkyrieResult<Option<WorkspaceEdit>> {
+    let mut chars = new_name.chars();
+    if !chars.next().is_some_and(is_ident_start) || !chars.all(is_ident_continue) {
+        return Err(ValkyrieErrorKind::InvalidName { name: new_name.to_string() }.into());
+    }
+    let (file, span, definitions) = match target(db, guard, uri, position)? {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    // the declared name, the cursor may be on an import alias
+    let old_name = match &definitions[0] {
+        Definition::Item(loc) => db.item_tree(loc.file).item(loc.index).name.clone(),
+        Definition::Local(local) => db.infer(file).locals[*local as usize].name.clone(),
+        Definition::Namespace(_) => db.file_text(file).unwrap_or_default()[span].to_string(),
+    };
+    let spans = reference_spans(db, guard, file, &definitions, true).await?;
+    let mut changes: HashMap<Url, Vec<TextEdit>> = HashMap::new();
+    for (file, spans) in group(spans) {
+        let uri = db.file_url(file);
+        let text = db.file_text(file).unwrap_or_default();
+        let index = guard.line_index(db, &uri, file)?;
+        let edits = spans
+            .into_iter()
+            .filter(|span| text[span.clone()] == old_name)
+            .map(|span| TextEdit { range: index.range(span), new_text: new_name.to_string() });
+        changes.entry(uri).or_default().extend(edits);
+    }
+    Ok(Some(WorkspaceEdit { changes: Some(changes), ..WorkspaceEdit::default() }))
+}
+
+/// The symbol under the cursor, namespaces can not be followed.
+fn target(db: &Snapshot, guard: &RequestGuard, uri: &Url, position: Position) -> ValkyrieResult<Option<Target>> {
+    let file = match db.file_id(uri) {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let offset = guard.line_index(db, uri, file)?.offset(position);
+    let (span, mut definitions) = match definitions_at(db, file, offset) {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    definitions.retain(|definition| !matches!(definition, Definition::Namespace(_)));
+    match definitions.is_empty() {
+        true => Ok(None),
+        false => Ok(Some((file, span, definitions))),
+    }
+}
+
+/// The spans of all names resolving to one of the definitions, locals are only searched in their own file.
+async fn reference_spans(
+    db: &Snapshot,
+    guard: &RequestGuard,
+    origin: FileId,
+    definitions: &[Definition],
+    include_declaration: bool,
+) -> ValkyrieResult<Vec<(FileId, Range<usize>)>> {
+    let map = db.def_map();
+    let mut out = vec![];
+    let mut files = vec![origin];
+    for definition in definitions {
+        if let Definition::Item(loc) = definition {
+            if include_declaration {
+                out.push((loc.file, db.item_sources(loc.file).items[loc.index as usize].name_span.clone()));
+            }
+            files.extend(db.files().iter().filter(|file| map.can_see(**file, loc.file)));
+        }
+    }
+    files.sort();
+    files.dedup();
+    for file in files {
+        guard.checkpoint().await?;
+        let text = db.file_text(file).unwrap_or_default();
+        let infer = db.infer(file);
+        if include_declaration && file == origin {
+            for definition in definitions {
+                if let Definition::Local(local) = definition {
+                    out.push((file, infer.locals[*local as usize].span.clone()));
+                }
+            }
+        }
+        for (offset, resolution) in &infer.resolutions {
+            let found = resolution.definitions.iter().any(|definition| match definition {
+                Definition::Local(_) => file == origin && definitions.contains(definition),
+                _ => definitions.contains(definition),
+            });
+            if let Some(span) = ident_at(&text, *offset).filter(|_| found) {
+                out.push((file, span));
+            }
+        }
+    }
+    out.sort_by_key(|(file, span)| (*file, span.start));
+    out.dedup();
+    Ok(out)
+}
+
+/// Group the spans by file, the spans are sorted by file already.
+fn group(spans: Vec<(FileId, Range<usize>)>) -> Vec<(FileId, Vec<Range<usize>>)> {
+    let mut out: Vec<(FileId, Vec<Range<usize>>)> = vec![];
+    for (file, span) in spans {
+        match out.last_mut() {
+            Some((last, spans)) if *last == file => spans.push(span),
+            _ => out.push((file, vec![span])),
+        }
+    }
+    out
+}
@@ -0,0 +1,59 @@
+use tower_lsp::lsp_types::{Location, SymbolInformation, SymbolKind};
+
+use crate::{ItemKind, Snapshot, ValkyrieResult, request::RequestGuard};
+
+/// Answer `workspace/symbol`, the declarations whose name contains the letters of the query in order, ignoring case.
+pub(crate) async fn workspace_symbols(
+    db: &Snapshot,
+    guard: &RequestGuard,
+    query: &str,
+) -> ValkyrieResult<Vec<SymbolInformation>> {
+    let query = query.to_lowercase();
+    let mut out = vec![];
+    for file in db.files().iter().copied() {
+        guard.checkpoint().await?;
+        let tree = db.item_tree(file);
+        let matched: Vec<usize> = (0..tree.items.len())
+            .filter(|index| tree.items[*index].kind != ItemKind::Imply && fuzzy_match(&query, &tree.items[*index].name))
+            .collect();
+        if matched.is_empty() {
+            continue;
+        }
+        let uri = db.file_url(file);
+        let index = guard.line_index(db, &uri, file)?;
+        let sources = db.item_sources(file);
+        for item in matched {
+            let data = &tree.items[item];
+            let container = &data.namepath[..data.namepath.len().saturating_sub(1)];
+            #[allow(deprecated)]
+            out.push(SymbolInformation {
+                name: data.name.clone(),
+                kind: symbol_kind(data.kind),
+                tags: None,
+                deprecated: None,
+                location: Location { uri: uri.clone(), range: index.range(sources.items[item].name_span.clone()) },
+                container_name: (!container.is_empty()).then(|| container.join("::")),
+            });
+        }
+    }
+    Ok(out)
+}
+
+fn fuzzy_match(query: &str, name: &str) -> bool {
+    let mut name = name.chars().flat_map(char::to_lowercase);
+    query.chars().all(|wanted| name.any(|char| char == wanted))
+}
+
+fn symbol_kind(kind: ItemKind) -> SymbolKind {
+    match kind {
+        ItemKind::Class | ItemKind::Imply => SymbolKind::CLASS,
+        ItemKind::Structure => SymbolKind::STRUCT,
+        ItemKind::Trait => SymbolKind::INTERFACE,
+        ItemKind::Union => SymbolKind::ENUM,
+        ItemKind::Variant => SymbolKind::ENUM_MEMBER,
+        ItemKind::Function => SymbolKind::FUNCTION,
+        ItemKind::Method => SymbolKind::METHOD,
+        ItemKind::Field => SymbolKind::FIELD,
+        ItemKind::TypeAlias => SymbolKind::TYPE_PARAMETER,
+    }
+}
@@ -0,0 +1,94 @@
+use tower_lsp::lsp_types::{GotoDefinitionResponse, Position, Url};
+
+use super::{NavigationTarget, definitions_at, file_offset, goto_response, ident_at};
+use crate::{Definition, ItemKind, ItemLoc, Resolver, Snapshot, Ty, TypeRef, ValkyrieResult, request::RequestGuard};
+
+/// Answer `textDocument/typeDefinition`, the declarations of the type of the name under the cursor.
+///
+/// Generic types lead to the container and to their arguments, a type declared twice by mistake to each declaration.
+pub(crate) fn goto_type_definition(
+    db: &Snapshot,
+    guard: &RequestGuard,
+    uri: &Url,
+    position: Position,
+    link_support: bool,
+) -> ValkyrieResult<Option<GotoDefinitionResponse>> {
+    let (file, offset) = match file_offset(db, guard, uri, position)? {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    let text = db.file_text(file).unwrap_or_default();
+    let span = match ident_at(&text, offset) {
+        Some(s) => s,
+        None => return Ok(None),
+    };
+    // the inferred type of values, the declared type of declarations
+    let mut types = vec![];
+    if let Some(ty) = db.infer(file).types.get(&span.start) {
+        ty_items(ty, &mut types);
+    }
+    if types.is_empty() {
+        for definition in definitions_at(db, file, offset).map(|(_, definitions)| definitions).unwrap_or_default() {
+            if let Definition::Item(loc) = definition {
+                declared_type_items(db, loc, &mut types);
+            }
+        }
+    }
+    let map = db.def_map();
+    let mut targets = vec![];
+    for loc in types {
+        let duplicates = map.get(&db.item_tree(loc.file).item(loc.index).namepath);
+        let locs = if duplicates.is_empty() { std::slice::from_ref(&loc) } else { duplicates };
+        for target in locs.iter().map(|loc| NavigationTarget::item(db, *loc)) {
+            if !targets.contains(&target) {
+                targets.push(target);
+            }
+        }
+    }
+    goto_response(db, guard, (uri, span), targets, link_support)
+}
+
+/// The declarations in a type, the container before its arguments, the return type of functions.
+fn ty_items(ty: &Ty, out: &mut Vec<ItemLoc>) {
+    match ty {
+        Ty::Item { item, args, .. } => {
+            out.push(*item);
+            args.iter().for_each(|arg| ty_items(arg, out));
+        }
+        Ty::Tuple(items) => items.iter().for_each(|item| ty_items(item, out)),
+        Ty::Function { ret, .. } => ty_items(ret, out),
+        Ty::Unknown | Ty::Generic(_) | Ty::Primitive(_) => {}
+    }
+}
+
+/// The type of a declaration, types are their own type.
+fn declared_type_items(db: &Snapshot, loc: ItemLoc, out: &mut Vec<ItemLoc>) {
+    let tree = db.item_tree(loc.file);
+    let item = tree.item(loc.index);
+    match item.kind {
+        ItemKind::Class | ItemKind::Structure | ItemKind::Trait | ItemKind::Union => out.push(loc),
+        ItemKind::Imply => {}
+        ItemKind::Variant | ItemKind::Function | ItemKind::Method | ItemKind::Field | ItemKind::TypeAlias => {
+            let map = db.def_map();
+            let scope = db.file_scope(loc.file);
+            let resolver = Resolver { map: &map, file: loc.file, tree: &tree, scope: &scope, namespace: &item.namespace };
+            if let Some(ty) = &item.ty {
+                type_ref_items(&resolver, &item.generics, ty, out);
+            }
+        }
+    }
+}
+
+fn type_ref_items(resolver: &Resolver, generics: &[String], ty: &TypeRef, out: &mut Vec<ItemLoc>) {
+    match ty {
+        TypeRef::Path { path, args } => {
+            if !(path.len() == 1 && generics.contains(&path[0])) {
+                out.extend(resolver.resolve_type(Some(ty)));
+            }
+            args.iter().for_each(|arg| type_ref_items(resolver, generics, arg, out));
+        }
+        TypeRef::Tuple(items) => items.iter().for_each(|item| type_ref_items(resolver, generics, item, out)),
+        TypeRef::Function { ret, .. } => type_ref_items(resolver, generics, ret, out),
+        TypeRef::Missing => {}
+    }
+}
@@ -0,0 +1,60 @@
+use std::path::{Path, PathBuf};
+
+use tower_lsp::lsp_types::Url;
+
+use super::{MANIFEST_NAME, PackageGraph, PackageId, VENDOR_DIRECTORY, graph::normalize};
+use crate::ExternalLibrary;
+
+/// The manifests on disk that would resolve a missing dependency, a local path or a vendored copy.
+///
+/// Vendored copies are searched below the package root and its ancestors inside the workspace folders.
+pub(crate) fn dependency_manifests(graph: &PackageGraph, roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut out = vec![];
+    for package in graph.packages() {
+        for dependency in graph.unresolved(package.manifest) {
+            let found = match &dependency.path {
+                Some(path) => Some(normalize(&package.root.join(path)).join(MANIFEST_NAME)),
+                None => package
+                    .root
+                    .ancestors()
+                    .take_while(|ancestor| *ancestor == package.root || roots.iter().any(|root| ancestor.starts_with(root)))
+                    .map(|ancestor| ancestor.join(VENDOR_DIRECTORY).join(&dependency.name).join(MANIFEST_NAME))
+                    .find(|manifest| manifest.is_file()),
+            };
+            if let Some(manifest) = found.filter(|manifest| manifest.is_file()) {
+                out.push(manifest);
+            }
+        }
+    }
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// The packages outside of the workspace folders or vendored, all of them or the ones a package depends on.
+pub(crate) fn external_libraries(graph: &PackageGraph, roots: &[PathBuf], from: Option<PackageId>) -> Vec<ExternalLibrary> {
+    let ids: Vec<PackageId> = match from {
+        Some(id) => graph.transitive_dependencies(id),
+        None => graph.ids().collect(),
+    };
+    let mut out: Vec<ExternalLibrary> = ids
+        .into_iter()
+        .map(|id| graph.package(id))
+        .filter(|package| is_external(&package.root, roots))
+        .filter_map(|package| {
+            Some(ExternalLibrary {
+                name: package.name.clone(),
+                version: package.version.clone(),
+                root: Url::from_directory_path(&package.root).ok()?,
+                language: "valkyrie".to_string(),
+            })
+        })
+        .collect();
+    out.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+    out
+}
+
+fn is_external(root: &Path, roots: &[PathBuf]) -> bool {
+    let vendored = root.parent().and_then(Path::file_name).is_some_and(|name| name == VENDOR_DIRECTORY);
+    vendored || !roots.iter().any(|workspace| root.starts_with(workspace))
+}
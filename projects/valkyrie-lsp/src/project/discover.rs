@@ -0,0 +1,65 @@
+use std::path::{Path, PathBuf};
+
+/// The file name of package manifests.
+pub const MANIFEST_NAME: &str = "fleet.json5";
+
+/// The extensions of source files.
+pub const SOURCE_EXTENSIONS: &[&str] = &["vk", "valkyrie"];
+
+/// The directory holding vendored copies of dependencies, one directory per package name.
+pub const VENDOR_DIRECTORY: &str = "vendor";
+
+/// Directories never searched, hidden directories are skipped too, vendored packages are loaded on demand.
+const SKIPPED_DIRECTORIES: &[&str] = &["target", "node_modules", VENDOR_DIRECTORY];
+
+/// Check if the path names a package manifest.
+pub fn is_manifest(path: &Path) -> bool {
+    path.file_name().is_some_and(|name| name == MANIFEST_NAME)
+}
+
+/// Check if the path names a source file.
+pub fn is_source(path: &Path) -> bool {
+    path.extension().and_then(|extension| extension.to_str()).is_some_and(|extension| SOURCE_EXTENSIONS.contains(&extension))
+}
+
+/// Find all manifests below the directories, sorted.
+pub fn find_manifests(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut out = vec![];
+    for root in roots {
+        walk(root, is_manifest, &mut out);
+    }
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// Find all source files below the directories, sorted.
+pub fn find_sources(roots: &[PathBuf]) -> Vec<PathBuf> {
+    let mut out = vec![];
+    for root in roots {
+        walk(root, is_source, &mut out);
+    }
+    out.sort();
+    out.dedup();
+    out
+}
+
+/// Collect the matching files, unreadable directories are skipped.
+fn walk(directory: &Path, filter: fn(&Path) -> bool, out: &mut Vec<PathBuf>) {
+    let entries = match std::fs::read_dir(directory) {
+        Ok(o) => o,
+        Err(_) => return,
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let name = entry.file_name();
+        let name = name.to_string_lossy();
+        match entry.file_type() {
+            Ok(kind) if kind.is_dir() && !name.starts_with('.') && !SKIPPED_DIRECTORIES.contains(&name.as_ref()) => {
+                walk(&path, filter, out)
+            }
+            Ok(kind) if kind.is_file() && filter(&path) => out.push(path),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::{
+    collections::BTreeMap,
+    path::{Component, Path, PathBuf},
+};
+
+use super::{Dependency, Manifest, VENDOR_DIRECTORY};
+use crate::FileId;
+
+/// A package of the workspace, the index in the [PackageGraph].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct PackageId(u32);
+
+/// A package described by a `fleet.json5` manifest.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Package {
+    /// The package name.
+    pub name: String,
+    /// The package version.
+    pub version: String,
+    /// The language edition.
+    pub edition: Option<String>,
+    /// The manifest file.
+    pub manifest: FileId,
+    /// The directory of the manifest.
+    pub root: PathBuf,
+    /// The absolute source directories.
+    pub source_roots: Vec<PathBuf>,
+    /// The packages of the workspace this package depends on.
+    pub dependencies: Vec<PackageId>,
+}
+
+/// The packages of the workspace and the dependencies between them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PackageGraph {
+    packages: Vec<Package>,
+    /// The dependencies of each manifest that have no package.
+    unresolved: BTreeMap<FileId, Vec<Dependency>>,
+}
+
+impl PackageGraph {
+    /// Build the graph from the manifests of the workspace and their paths.
+    pub fn build(manifests: &[(FileId, PathBuf, &Manifest)]) -> PackageGraph {
+        let mut graph = PackageGraph::default();
+        for (file, path, manifest) in manifests {
+            let root = path.parent().map(normalize).unwrap_or_default();
+            graph.packages.push(Package {
+                name: manifest.name.clone(),
+                version: manifest.version.clone(),
+                edition: manifest.edition.clone(),
+                manifest: *file,
+                source_roots: manifest.sources.iter().map(|source| normalize(&root.join(source))).collect(),
+                root,
+                dependencies: vec![],
+            });
+        }
+        for (index, (file, _, manifest)) in manifests.iter().enumerate() {
+            let root = graph.packages[index].root.clone();
+            let mut dependencies = vec![];
+            for dependency in &manifest.dependencies {
+                // a path names the package exactly, otherwise the nearest vendored copy or any package of the name
+                let found = match &dependency.path {
+                    Some(path) => {
+                        let target = normalize(&root.join(path));
+                        graph.packages.iter().position(|package| package.root == target)
+                    }
+                    None => {
+                        let vendored = vendor_paths(&root, &dependency.name)
+                            .find_map(|target| graph.packages.iter().position(|package| package.root == target));
+                        vendored.or_else(|| graph.packages.iter().position(|package| package.name == dependency.name))
+                    }
+                };
+                match found {
+                    Some(target) => dependencies.push(PackageId(target as u32)),
+                    None => graph.unresolved.entry(*file).or_default().push(dependency.clone()),
+                }
+            }
+            graph.packages[index].dependencies = dependencies;
+        }
+        graph
+    }
+    /// All packages, indexed by their id.
+    pub fn packages(&self) -> &[Package] {
+        &self.packages
+    }
+    /// Get a package by id.
+    pub fn package(&self, id: PackageId) -> &Package {
+        &self.packages[id.0 as usize]
+    }
+    /// The ids of all packages.
+    pub fn ids(&self) -> impl Iterator<Item = PackageId> {
+        (0..self.packages.len() as u32).map(PackageId)
+    }
+    /// The package of the manifest file.
+    pub fn package_of_manifest(&self, manifest: FileId) -> Option<PackageId> {
+        self.packages.iter().position(|package| package.manifest == manifest).map(|index| PackageId(index as u32))
+    }
+    /// The package a source file belongs to, the one with the deepest source root containing it.
+    pub fn package_of(&self, path: &Path) -> Option<PackageId> {
+        let path = normalize(path);
+        let mut best: Option<(usize, PackageId)> = None;
+        for id in self.ids() {
+            for root in &self.package(id).source_roots {
+                let depth = root.components().count();
+                if path.starts_with(root) && best.is_none_or(|(old, _)| depth > old) {
+                    best = Some((depth, id));
+                }
+            }
+        }
+        best.map(|(_, id)| id)
+    }
+    /// The dependencies of the manifest without a package.
+    pub fn unresolved(&self, manifest: FileId) -> &[Dependency] {
+        match self.unresolved.get(&manifest) {
+            Some(s) => s,
+            None => &[],
+        }
+    }
+    /// All packages the package depends on, directly or not, without itself.
+    pub fn transitive_dependencies(&self, id: PackageId) -> Vec<PackageId> {
+        let mut out: Vec<PackageId> = vec![];
+        let mut stack = self.package(id).dependencies.clone();
+        while let Some(next) = stack.pop() {
+            if next != id && !out.contains(&next) {
+                out.push(next);
+                stack.extend(self.package(next).dependencies.iter().copied());
+            }
+        }
+        out.sort();
+        out
+    }
+    /// Check if a file of one package may use the declarations of a file in another.
+    ///
+    /// Files outside of all packages see everything, files of a package see their own package and its direct
+    /// dependencies.
+    pub fn can_see(&self, from: Option<PackageId>, to: Option<PackageId>) -> bool {
+        match (from, to) {
+            (None, _) => true,
+            (Some(_), None) => false,
+            (Some(from), Some(to)) => from == to || self.package(from).dependencies.contains(&to),
+        }
+    }
+}
+
+/// The directories a vendored copy of the package may be in, `vendor/<name>` below the root and its ancestors, nearest first.
+pub(crate) fn vendor_paths<'a>(root: &'a Path, name: &'a str) -> impl Iterator<Item = PathBuf> + 'a {
+    root.ancestors().map(move |ancestor| ancestor.join(VENDOR_DIRECTORY).join(name))
+}
+
+/// Resolve `.` and `..` without touching the file system, the paths may not exist.
+pub(crate) fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                out.pop();
+            }
+            _ => out.push(component),
+        }
+    }
+    out
+}
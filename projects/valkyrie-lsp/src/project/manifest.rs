@@ -0,0 +1,186 @@
+use std::ops::Range;
+
+use serde_json::{Map, Value};
+
+use crate::{
+    FileDiagnostic,
+    diagnostics::{INVALID_MANIFEST, UNKNOWN_MANIFEST_KEY},
+};
+
+/// Keys of a manifest that are valid but not used by the language server.
+const IGNORED_KEYS: &[&str] = &["description", "authors", "license", "repository", "homepage", "keywords"];
+
+/// The typed content of a `fleet.json5` manifest.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Manifest {
+    /// The package name.
+    pub name: String,
+    /// The package version, `0.0.0` if not given.
+    pub version: String,
+    /// The language edition.
+    pub edition: Option<String>,
+    /// The source directories relative to the manifest, `src` if not given.
+    pub sources: Vec<String>,
+    /// The declared dependencies, sorted by name.
+    pub dependencies: Vec<Dependency>,
+}
+
+/// A dependency declared in a manifest.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Dependency {
+    /// The package name.
+    pub name: String,
+    /// The requested version.
+    pub version: Option<String>,
+    /// The directory of the package relative to the manifest.
+    pub path: Option<String>,
+    /// The byte span of the key in the manifest.
+    pub span: Range<usize>,
+}
+
+/// A manifest and the problems found while reading it.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct ParsedManifest {
+    /// The manifest, none if it is too broken to describe a package.
+    pub manifest: Option<Manifest>,
+    /// The problems found, positioned in the manifest.
+    pub problems: Vec<FileDiagnostic>,
+}
+
+impl ParsedManifest {
+    /// Read a manifest, never fails, problems are collected instead.
+    pub fn parse(text: &str) -> ParsedManifest {
+        let mut out = ParsedManifest::default();
+        let value = match json5::from_str::<Value>(text) {
+            Ok(o) => o,
+            Err(e) => {
+                let offset = e.position().map_or(0, |position| byte_offset(text, position.line, position.column));
+                let message = e.code().map_or_else(|| e.to_string(), |code| code.to_string());
+                out.error(offset..offset, &message);
+                return out;
+            }
+        };
+        let object = match value.as_object() {
+            Some(s) => s,
+            None => {
+                out.error(0..text.len(), "the manifest must be an object");
+                return out;
+            }
+        };
+        let keys = KeyFinder { text, from: 0 };
+        for key in object.keys() {
+            match key.as_str() {
+                "name" | "version" | "edition" | "sources" | "dependencies" => {}
+                _ if IGNORED_KEYS.contains(&key.as_str()) => {}
+                _ => out.problems.push(FileDiagnostic::new(&UNKNOWN_MANIFEST_KEY, keys.find(key), &[key])),
+            }
+        }
+        let name = match object.get("name") {
+            Some(Value::String(s)) if !s.is_empty() => s.clone(),
+            Some(_) => {
+                out.error(keys.find("name"), "`name` must be a non empty string");
+                return out;
+            }
+            None => {
+                out.error(0..0, "missing field `name`");
+                return out;
+            }
+        };
+        let version = out.string(object, "version", &keys).unwrap_or_else(|| "0.0.0".to_string());
+        let edition = out.string(object, "edition", &keys);
+        let sources = match object.get("sources") {
+            None => vec!["src".to_string()],
+            Some(Value::String(s)) => vec![s.clone()],
+            Some(Value::Array(array)) if array.iter().all(Value::is_string) => {
+                array.iter().filter_map(|item| item.as_str().map(str::to_string)).collect()
+            }
+            Some(_) => {
+                out.error(keys.find("sources"), "`sources` must be a string or an array of strings");
+                vec!["src".to_string()]
+            }
+        };
+        let dependencies = match object.get("dependencies") {
+            None => vec![],
+            Some(Value::Object(map)) => out.dependencies(map, &keys),
+            Some(_) => {
+                out.error(keys.find("dependencies"), "`dependencies` must be an object");
+                vec![]
+            }
+        };
+        out.manifest = Some(Manifest { name, version, edition, sources, dependencies });
+        out
+    }
+    fn error(&mut self, span: Range<usize>, message: &str) {
+        self.problems.push(FileDiagnostic::new(&INVALID_MANIFEST, span, &[message]));
+    }
+    fn string(&mut self, object: &Map<String, Value>, key: &str, keys: &KeyFinder) -> Option<String> {
+        match object.get(key)? {
+            Value::String(s) => Some(s.clone()),
+            _ => {
+                self.error(keys.find(key), &format!("`{key}` must be a string"));
+                None
+            }
+        }
+    }
+    fn dependencies(&mut self, map: &Map<String, Value>, keys: &KeyFinder) -> Vec<Dependency> {
+        // dependency names are searched after the `dependencies` key, they may repeat other keys
+        let start = keys.find("dependencies").end;
+        let mut out = vec![];
+        for (name, value) in map {
+            let span = KeyFinder { text: keys.text, from: start }.find(name);
+            let (version, path) = match value {
+                Value::String(s) => (Some(s.clone()), None),
+                Value::Object(object) => {
+                    let inner = KeyFinder { text: keys.text, from: span.end };
+                    (self.string(object, "version", &inner), self.string(object, "path", &inner))
+                }
+                _ => {
+                    self.error(span, &format!("dependency `{name}` must be a version string or an object"));
+                    continue;
+                }
+            };
+            out.push(Dependency { name: name.clone(), version, path, span });
+        }
+        out
+    }
+}
+
+/// Finds the span of a key in the manifest text, the parsed value has no positions.
+struct KeyFinder<'a> {
+    text: &'a str,
+    from: usize,
+}
+
+impl KeyFinder<'_> {
+    /// The span of the first key written after the start offset, the start of the text if not found.
+    fn find(&self, key: &str) -> Range<usize> {
+        let text = &self.text[self.from..];
+        let mut offset = 0;
+        while let Some(index) = text[offset..].find(key) {
+            let start = offset + index;
+            let end = start + key.len();
+            let before = text[..start].chars().next_back();
+            let after = text[end..].trim_start_matches(['"', '\'']).trim_start();
+            let quoted = matches!(before, Some('"' | '\''));
+            let bare = !before.is_some_and(|c| c.is_alphanumeric() || c == '_' || c == '$');
+            if (quoted || bare) && after.starts_with(':') {
+                return self.from + start..self.from + end;
+            }
+            offset = end;
+        }
+        0..0
+    }
+}
+
+/// Convert a zero based line and char column to a byte offset.
+fn byte_offset(text: &str, line: usize, column: usize) -> usize {
+    let mut start = 0;
+    for _ in 0..line {
+        match text[start..].find('\n') {
+            Some(index) => start += index + 1,
+            None => return text.len(),
+        }
+    }
+    let line = &text[start..];
+    start + line.char_indices().nth(column).map_or(line.len(), |(index, _)| index)
+}
@@ -0,0 +1,16 @@
+pub(crate) use self::{
+    dependencies::{dependency_manifests, external_libraries},
+    discover::{find_manifests, find_sources, is_manifest},
+    workspace::WorkspaceFolders,
+};
+pub use self::{
+    discover::{MANIFEST_NAME, SOURCE_EXTENSIONS, VENDOR_DIRECTORY},
+    graph::{Package, PackageGraph, PackageId},
+    manifest::{Dependency, Manifest, ParsedManifest},
+};
+
+mod dependencies;
+mod discover;
+mod graph;
+mod manifest;
+mod workspace;
@@ -0,0 +1,63 @@
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+use tower_lsp::lsp_types::{InitializeParams, Url};
+
+/// The workspace folders opened by the client and the files loaded from each of them.
+#[derive(Clone, Debug, Default)]
+pub(crate) struct WorkspaceFolders {
+    folders: BTreeMap<PathBuf, Vec<Url>>,
+}
+
+impl WorkspaceFolders {
+    /// The folders of the client, `workspace_folders` if given, else the deprecated `root_uri`.
+    pub fn from_params(params: &InitializeParams) -> Self {
+        let mut out = WorkspaceFolders::default();
+        let uris: Vec<&Url> = match &params.workspace_folders {
+            Some(folders) => folders.iter().map(|folder| &folder.uri).collect(),
+            None => params.root_uri.iter().collect(),
+        };
+        for uri in uris {
+            if let Ok(path) = uri.to_file_path() {
+                out.insert(path);
+            }
+        }
+        out
+    }
+    /// The root directories of all folders.
+    pub fn roots(&self) -> Vec<PathBuf> {
+        self.folders.keys().cloned().collect()
+    }
+    /// Add a folder, returns false if it was already open.
+    pub fn insert(&mut self, root: PathBuf) -> bool {
+        if self.folders.contains_key(&root) {
+            return false;
+        }
+        self.folders.insert(root, vec![]);
+        true
+    }
+    /// Record the files loaded for a folder.
+    pub fn set_files(&mut self, root: &Path, files: Vec<Url>) {
+        if let Some(loaded) = self.folders.get_mut(root) {
+            *loaded = files;
+        }
+    }
+    /// The files loaded for a folder, none if the folder is not open.
+    pub fn files(&self, root: &Path) -> Option<Vec<Url>> {
+        self.folders.get(root).cloned()
+    }
+    /// Remove a folder, returns the files it loaded that no other folder loaded too.
+    pub fn remove(&mut self, root: &Path) -> Vec<Url> {
+        let files = self.folders.remove(root).unwrap_or_default();
+        files.into_iter().filter(|file| !self.folders.values().any(|other| other.contains(file))).collect()
+    }
+    /// Check if the file is under one of the folders.
+    pub fn contains(&self, uri: &Url) -> bool {
+        match uri.to_file_path() {
+            Ok(path) => self.folders.keys().any(|root| path.starts_with(root)),
+            Err(_) => false,
+        }
+    }
+}
@@ -0,0 +1,63 @@
+use serde::{Deserialize, Serialize};
+use tower_lsp::lsp_types::{MarkupContent, Url, request::Request};
+
+/// `valkyrie/explainDiagnostic`, the long explanation of a diagnostic code.
+#[derive(Copy, Clone, Debug)]
+pub enum ExplainDiagnostic {}
+
+/// The parameters of [ExplainDiagnostic].
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct ExplainDiagnosticParams {
+    /// The code such as `V0001`, or the name of the diagnostic.
+    pub code: String,
+}
+
+/// The result of [ExplainDiagnostic].
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+pub struct DiagnosticExplanation {
+    /// The stable code.
+    pub code: String,
+    /// The kebab-case name.
+    pub name: String,
+    /// The explanation in Markdown.
+    pub explanation: MarkupContent,
+}
+
+impl Request for ExplainDiagnostic {
+    type Params = ExplainDiagnosticParams;
+    type Result = Option<DiagnosticExplanation>;
+    const METHOD: &'static str = "valkyrie/explainDiagnostic";
+}
+
+/// `valkyrie/externalLibraries`, the packages the workspace depends on that are not part of it.
+#[derive(Copy, Clone, Debug)]
+pub enum ExternalLibraries {}
+
+/// The parameters of [ExternalLibraries].
+#[derive(Clone, Debug, Default, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalLibrariesParams {
+    /// Only list the libraries the package of this file depends on, directly or not.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<Url>,
+}
+
+/// A library in the result of [ExternalLibraries].
+#[derive(Clone, Debug, Eq, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalLibrary {
+    /// The package name.
+    pub name: String,
+    /// The package version.
+    pub version: String,
+    /// The directory of the package manifest.
+    pub root: Url,
+    /// The language of the sources, always `valkyrie`.
+    pub language: String,
+}
+
+impl Request for ExternalLibraries {
+    type Params = ExternalLibrariesParams;
+    type Result = Vec<ExternalLibrary>;
+    const METHOD: &'static str = "valkyrie/externalLibraries";
+}
@@ -0,0 +1,59 @@
+use std::mem::take;
+
+use super::DocumentRenderer;
+
+const RESET: &str = "\x1b[0m";
+const BAD: &str = "\x1b[31m";
+const KEYWORD: &str = "\x1b[35m";
+const CLASS: &str = "\x1b[33m";
+const ATTRIBUTE: &str = "\x1b[36m";
+const CODE: &str = "\x1b[2m";
+
+/// Renders text with ANSI color escapes, for terminals.
+#[derive(Clone, Debug, Default)]
+pub struct AnsiRenderer {
+    buffer: String,
+}
+
+impl AnsiRenderer {
+    fn paint(&mut self, color: &str, text: &str) {
+        self.buffer.push_str(color);
+        self.buffer.push_str(text);
+        self.buffer.push_str(RESET);
+    }
+}
+
+impl DocumentRenderer for AnsiRenderer {
+    fn write_text(&mut self, text: &str) {
+        self.buffer.push_str(text);
+    }
+    fn write_keyword(&mut self, text: &str) {
+        self.paint(KEYWORD, text);
+    }
+    fn write_class(&mut self, text: &str) {
+        self.paint(CLASS, text);
+    }
+    fn write_attribute(&mut self, text: &str) {
+        self.paint(ATTRIBUTE, text);
+    }
+    fn write_newline(&mut self) {
+        self.buffer.push('\n');
+    }
+    fn write_bad(&mut self, text: &str) {
+        self.paint(BAD, text);
+    }
+    fn write_code(&mut self, code: &str) {
+        // indented like a code block, colors are not kept across lines by all terminals
+        let lines: Vec<String> = code.lines().map(|line| format!("{}    {}{}", CODE, line, RESET)).collect();
+        self.buffer.push_str(&lines.join("\n"));
+    }
+    fn write_markdown(&mut self, markdown: &str) {
+        self.buffer.push_str(markdown);
+    }
+    fn write_rule(&mut self) {
+        self.buffer.push_str("\n\n---\n\n");
+    }
+    fn finish(&mut self) -> String {
+        take(&mut self.buffer)
+    }
+}
@@ -0,0 +1,75 @@
+use std::mem::take;
+
+use super::{Color, ColorSchema, DocumentRenderer};
+
+/// Renders HTML with inline styles and `<br/>` line breaks, for JetBrains.
+#[derive(Clone, Debug, Default)]
+pub struct HtmlRenderer {
+    buffer: String,
+    schema: ColorSchema,
+}
+
+impl HtmlRenderer {
+    /// An empty renderer coloring with the schema.
+    pub fn new(schema: ColorSchema) -> Self {
+        Self { buffer: String::new(), schema }
+    }
+    fn paint(&mut self, color: Color, text: &str) {
+        self.buffer.push_str(&format!("<span style=\"color: {}\">{}</span>", color, escape(text)));
+    }
+}
+
+impl DocumentRenderer for HtmlRenderer {
+    fn write_text(&mut self, text: &str) {
+        self.buffer.push_str(&escape(text));
+    }
+    fn write_keyword(&mut self, text: &str) {
+        self.paint(self.schema.keyword, text);
+    }
+    fn write_class(&mut self, text: &str) {
+        self.paint(self.schema.class, text);
+    }
+    fn write_attribute(&mut self, text: &str) {
+        self.paint(self.schema.attribute, text);
+    }
+    fn write_function(&mut self, text: &str) {
+        self.paint(self.schema.function, text);
+    }
+    fn write_trait(&mut self, text: &str) {
+        self.paint(self.schema.r#trait, text);
+    }
+    fn write_namespace(&mut self, text: &str) {
+        self.paint(self.schema.namespace, text);
+    }
+    fn write_literal(&mut self, text: &str) {
+        self.paint(self.schema.literal, text);
+    }
+    fn write_comment(&mut self, text: &str) {
+        self.paint(self.schema.comment, text);
+    }
+    fn write_operator(&mut self, text: &str) {
+        self.paint(self.schema.operator, text);
+    }
+    fn write_newline(&mut self) {
+        self.buffer.push_str("<br/>");
+    }
+    fn write_bad(&mut self, text: &str) {
+        self.paint(self.schema.bad, text);
+    }
+    fn write_code(&mut self, code: &str) {
+        self.buffer.push_str(&format!("<pre>{}</pre>", escape(code)));
+    }
+    fn write_markdown(&mut self, markdown: &str) {
+        self.buffer.push_str(&escape(markdown).replace('\n', "<br/>"));
+    }
+    fn write_rule(&mut self) {
+        self.buffer.push_str("<hr/>");
+    }
+    fn finish(&mut self) -> String {
+        take(&mut self.buffer)
+    }
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
@@ -0,0 +1,68 @@
+use std::mem::take;
+
+use super::DocumentRenderer;
+
+/// Renders CommonMark, names in code spans and code in fenced blocks.
+#[derive(Clone, Debug, Default)]
+pub struct MarkdownRenderer {
+    buffer: String,
+}
+
+impl MarkdownRenderer {
+    /// A code span, fenced by more backticks than any run of backticks in the text.
+    fn code_span(&mut self, text: &str) {
+        let fence = "`".repeat(longest_backticks(text) + 1);
+        let pad = if text.starts_with('`') || text.ends_with('`') { " " } else { "" };
+        self.buffer.push_str(&format!("{0}{1}{2}{1}{0}", fence, pad, text));
+    }
+}
+
+impl DocumentRenderer for MarkdownRenderer {
+    fn write_text(&mut self, text: &str) {
+        self.buffer.push_str(&escape(text));
+    }
+    fn write_keyword(&mut self, text: &str) {
+        self.buffer.push_str(&format!("*{}*", escape(text)));
+    }
+    fn write_class(&mut self, text: &str) {
+        self.code_span(text);
+    }
+    fn write_attribute(&mut self, text: &str) {
+        self.code_span(text);
+    }
+    fn write_newline(&mut self) {
+        self.buffer.push('\n');
+    }
+    fn write_bad(&mut self, text: &str) {
+        self.buffer.push_str(&format!("~~{}~~", escape(text)));
+    }
+    fn write_code(&mut self, code: &str) {
+        let fence = "`".repeat(longest_backticks(code).max(2) + 1);
+        self.buffer.push_str(&format!("{0}valkyrie\n{1}\n{0}", fence, code));
+    }
+    fn write_markdown(&mut self, markdown: &str) {
+        self.buffer.push_str(markdown);
+    }
+    fn write_rule(&mut self) {
+        self.buffer.push_str("\n\n---\n\n");
+    }
+    fn finish(&mut self) -> String {
+        take(&mut self.buffer)
+    }
+}
+
+/// Escape the characters that start emphasis, code, links or HTML.
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '\\' | '`' | '*' | '_' | '[' | ']' | '<' | '>' | '~') {
+            out.push('\\');
+        }
+        out.push(c);
+    }
+    out
+}
+
+fn longest_backticks(text: &str) -> usize {
+    text.split(|c| c != '`').map(str::len).max().unwrap_or(0)
+}
@@ -0,0 +1,113 @@
+use tower_lsp::lsp_types::{ClientCapabilities, MarkupKind};
+
+pub use self::{
+    ansi::AnsiRenderer,
+    html::HtmlRenderer,
+    markdown::MarkdownRenderer,
+    plain::PlainRenderer,
+    schema::{Color, ColorSchema},
+};
+
+mod ansi;
+mod html;
+mod markdown;
+mod plain;
+mod schema;
+mod theme;
+
+/// Writes documentation in one output format, the operations say what the text is rather than how it looks.
+pub trait DocumentRenderer {
+    /// Text without a role, written as is.
+    fn write_text(&mut self, text: &str);
+    /// A keyword, or the kind of a declaration such as `class`.
+    fn write_keyword(&mut self, text: &str);
+    /// The name or path of a type.
+    fn write_class(&mut self, text: &str);
+    /// The name of a member or a local.
+    fn write_attribute(&mut self, text: &str);
+    /// The name or path of a function or a method.
+    fn write_function(&mut self, text: &str) {
+        self.write_attribute(text)
+    }
+    /// The name or path of a trait.
+    fn write_trait(&mut self, text: &str) {
+        self.write_class(text)
+    }
+    /// The path of a namespace.
+    fn write_namespace(&mut self, text: &str) {
+        self.write_class(text)
+    }
+    /// A number, string or other literal.
+    fn write_literal(&mut self, text: &str) {
+        self.write_attribute(text)
+    }
+    /// A comment.
+    fn write_comment(&mut self, text: &str) {
+        self.write_text(text)
+    }
+    /// An operator.
+    fn write_operator(&mut self, text: &str) {
+        self.write_attribute(text)
+    }
+    /// Keywords followed by a space each, such as `public static`.
+    fn write_modifiers(&mut self, modifiers: &[&str]) {
+        for modifier in modifiers {
+            self.write_keyword(modifier);
+            self.write_text(" ");
+        }
+    }
+    /// End the current line.
+    fn write_newline(&mut self);
+    /// Text that is wrong, such as an unknown name.
+    fn write_bad(&mut self, text: &str);
+    /// A block of Valkyrie code, on its own lines.
+    fn write_code(&mut self, code: &str);
+    /// Documentation written in Markdown, such as doc comments.
+    fn write_markdown(&mut self, markdown: &str);
+    /// Separate two sections.
+    fn write_rule(&mut self);
+    /// Take the output written so far.
+    fn finish(&mut self) -> String;
+}
+
+/// The output formats of documentation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub enum DocumentFormat {
+    /// HTML with inline styles, for embedders such as JetBrains plugins, not sent by the server.
+    Html,
+    /// CommonMark, for most LSP clients.
+    Markdown,
+    /// Text with ANSI escapes, for embedders printing to terminals, not sent by the server.
+    Ansi,
+    /// Text without markup, for LSP clients that prefer it.
+    Plain,
+}
+
+impl DocumentFormat {
+    /// A new empty renderer of this format, the schema colors HTML.
+    pub fn renderer(self, schema: ColorSchema) -> Box<dyn DocumentRenderer + Send> {
+        match self {
+            DocumentFormat::Html => Box::new(HtmlRenderer::new(schema)),
+            DocumentFormat::Markdown => Box::<MarkdownRenderer>::default(),
+            DocumentFormat::Ansi => Box::<AnsiRenderer>::default(),
+            DocumentFormat::Plain => Box::<PlainRenderer>::default(),
+        }
+    }
+    /// The format of hovers, the one the client prefers, Markdown if it does not say.
+    ///
+    /// LSP only knows Markdown and plain text, HTML and ANSI are only rendered through the API.
+    pub fn for_hover(capabilities: &ClientCapabilities) -> Self {
+        let formats = capabilities.text_document.as_ref().and_then(|text| text.hover.as_ref()?.content_format.as_ref());
+        match formats.and_then(|formats| formats.first()) {
+            Some(MarkupKind::PlainText) => DocumentFormat::Plain,
+            _ => DocumentFormat::Markdown,
+        }
+    }
+    /// The markup kind of the format, HTML and ANSI are plain text to LSP.
+    pub fn markup_kind(self) -> MarkupKind {
+        match self {
+            DocumentFormat::Markdown => MarkupKind::Markdown,
+            _ => MarkupKind::PlainText,
+        }
+    }
+}
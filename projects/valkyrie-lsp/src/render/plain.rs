@@ -0,0 +1,42 @@
+use std::mem::take;
+
+use super::DocumentRenderer;
+
+/// Renders text without markup, for clients that only accept plain text.
+#[derive(Clone, Debug, Default)]
+pub struct PlainRenderer {
+    buffer: String,
+}
+
+impl DocumentRenderer for PlainRenderer {
+    fn write_text(&mut self, text: &str) {
+        self.buffer.push_str(text);
+    }
+    fn write_keyword(&mut self, text: &str) {
+        self.buffer.push_str(text);
+    }
+    fn write_class(&mut self, text: &str) {
+        self.buffer.push_str(text);
+    }
+    fn write_attribute(&mut self, text: &str) {
+        self.buffer.push_str(text);
+    }
+    fn write_newline(&mut self) {
+        self.buffer.push('\n');
+    }
+    fn write_bad(&mut self, text: &str) {
+        self.buffer.push_str(text);
+    }
+    fn write_code(&mut self, code: &str) {
+        self.buffer.push_str(code);
+    }
+    fn write_markdown(&mut self, markdown: &str) {
+        self.buffer.push_str(markdown);
+    }
+    fn write_rule(&mut self) {
+        self.buffer.push_str("\n\n---\n\n");
+    }
+    fn finish(&mut self) -> String {
+        take(&mut self.buffer)
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    fmt::{Display, Formatter},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Serialize};
+
+/// An opaque RGB color, written `#RRGGBB`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+#[serde(try_from = "String", into = "String")]
+pub struct Color {
+    /// The red channel.
+    pub red: u8,
+    /// The green channel.
+    pub green: u8,
+    /// The blue channel.
+    pub blue: u8,
+}
+
+/// The colors of each semantic category of documentation.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct ColorSchema {
+    /// Wrong or unknown text.
+    pub bad: Color,
+    /// Keywords and modifiers.
+    pub keyword: Color,
+    /// Classes, structures and unions.
+    pub class: Color,
+    /// Fields, properties and locals.
+    pub attribute: Color,
+    /// Functions and methods.
+    pub function: Color,
+    /// Traits.
+    pub r#trait: Color,
+    /// Namespaces.
+    pub namespace: Color,
+    /// Numbers, strings and other literals.
+    pub literal: Color,
+    /// Comments.
+    pub comment: Color,
+    /// Operators.
+    pub operator: Color,
+}
+
+impl Color {
+    /// A color from its channels.
+    pub const fn rgb(red: u8, green: u8, blue: u8) -> Self {
+        Self { red, green, blue }
+    }
+    const fn hex(value: u32) -> Self {
+        Self::rgb((value >> 16) as u8, (value >> 8) as u8, value as u8)
+    }
+}
+
+impl FromStr for Color {
+    type Err = String;
+
+    /// Parse `#RRGGBB`, the `#` is optional and an alpha channel `#RRGGBBAA` is ignored.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let digits = s.strip_prefix('#').unwrap_or(s);
+        let valid = matches!(digits.len(), 6 | 8) && digits.chars().all(|c| c.is_ascii_hexdigit());
+        match u32::from_str_radix(digits.get(..6).unwrap_or_default(), 16) {
+            Ok(value) if valid => Ok(Color::hex(value)),
+            _ => Err(format!("expected a color `#RRGGBB`, found `{}`", s)),
+        }
+    }
+}
+
+impl TryFrom<String> for Color {
+    type Error = String;
+
+    fn try_from(value: String) -> Result<Self, Self::Error> {
+        value.parse()
+    }
+}
+
+impl From<Color> for String {
+    fn from(value: Color) -> Self {
+        value.to_string()
+    }
+}
+
+impl Display for Color {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.red, self.green, self.blue)
+    }
+}
+
+impl Default for ColorSchema {
+    fn default() -> Self {
+        Self::DARK
+    }
+}
+
+impl ColorSchema {
+    /// The names of the categories, as used in the `valkyrie.theme.colors` setting.
+    pub const CATEGORIES: &'static [&'static str] =
+        &["bad", "keyword", "class", "attribute", "function", "trait", "namespace", "literal", "comment", "operator"];
+    /// The colors of One Dark, for dark themes.
+    pub const DARK: ColorSchema = ColorSchema {
+        bad: Color::hex(0xF44747),
+        keyword: Color::hex(0xC679DD),
+        class: Color::hex(0xE5C17C),
+        attribute: Color::hex(0x57B6C2),
+        function: Color::hex(0x61AFEF),
+        r#trait: Color::hex(0xD19A66),
+        namespace: Color::hex(0xE06C75),
+        literal: Color::hex(0x98C379),
+        comment: Color::hex(0x7F848E),
+        operator: Color::hex(0xABB2BF),
+    };
+    /// The colors of One Light, for light themes.
+    pub const LIGHT: ColorSchema = ColorSchema {
+        bad: Color::hex(0xE45649),
+        keyword: Color::hex(0xA626A4),
+        class: Color::hex(0xC18401),
+        attribute: Color::hex(0x0184BC),
+        function: Color::hex(0x4078F2),
+        r#trait: Color::hex(0x986801),
+        namespace: Color::hex(0xCA1243),
+        literal: Color::hex(0x50A14F),
+        comment: Color::hex(0xA0A1A7),
+        operator: Color::hex(0x383A42),
+    };
+    /// The color of a category by its name.
+    pub fn category_mut(&mut self, name: &str) -> Option<&mut Color> {
+        let color = match name {
+            "bad" => &mut self.bad,
+            "keyword" => &mut self.keyword,
+            "class" => &mut self.class,
+            "attribute" => &mut self.attribute,
+            "function" => &mut self.function,
+            "trait" => &mut self.r#trait,
+            "namespace" => &mut self.namespace,
+            "literal" => &mut self.literal,
+            "comment" => &mut self.comment,
+            "operator" => &mut self.operator,
+            _ => return None,
+        };
+        Some(color)
+    }
+}
@@ -0,0 +1,272 @@
+use std::{collections::BTreeMap, path::Path};
+
+use serde_json::Value;
+
+use super::{Color, ColorSchema};
+use crate::{ValkyrieError, ValkyrieErrorKind, ValkyrieResult};
+
+/// The TextMate scope each category takes its color from in VS Code themes.
+const TEXTMATE_SCOPES: &[(&str, &str)] = &[
+    ("bad", "invalid.illegal.valkyrie"),
+    ("keyword", "keyword.control.valkyrie"),
+    ("class", "entity.name.type.class.valkyrie"),
+    ("attribute", "variable.other.property.valkyrie"),
+    ("function", "entity.name.function.valkyrie"),
+    ("trait", "entity.name.type.interface.valkyrie"),
+    ("namespace", "entity.name.namespace.valkyrie"),
+    ("literal", "constant.numeric.valkyrie"),
+    ("comment", "comment.line.valkyrie"),
+    ("operator", "keyword.operator.valkyrie"),
+];
+
+/// The semantic token types each category takes its color from in VS Code themes, the first one found wins.
+const SEMANTIC_TOKENS: &[(&str, &[&str])] = &[
+    ("keyword", &["keyword"]),
+    ("class", &["class", "struct", "type"]),
+    ("attribute", &["property", "variable"]),
+    ("function", &["function", "method"]),
+    ("trait", &["interface"]),
+    ("namespace", &["namespace"]),
+    ("literal", &["number", "string"]),
+    ("comment", &["comment"]),
+    ("operator", &["operator"]),
+];
+
+/// The attributes each category takes its color from in JetBrains color schemes.
+const JETBRAINS_KEYS: &[(&str, &str)] = &[
+    ("bad", "ERRORS_ATTRIBUTES"),
+    ("keyword", "DEFAULT_KEYWORD"),
+    ("class", "DEFAULT_CLASS_NAME"),
+    ("attribute", "DEFAULT_INSTANCE_FIELD"),
+    ("function", "DEFAULT_FUNCTION_DECLARATION"),
+    ("trait", "DEFAULT_INTERFACE_NAME"),
+    ("namespace", "DEFAULT_IDENTIFIER"),
+    ("literal", "DEFAULT_NUMBER"),
+    ("comment", "DEFAULT_LINE_COMMENT"),
+    ("operator", "DEFAULT_OPERATION_SIGN"),
+];
+
+impl ColorSchema {
+    /// Load a VS Code color theme, JSON with comments, or a JetBrains color scheme, `.icls` XML.
+    pub fn load_theme(path: &Path) -> ValkyrieResult<ColorSchema> {
+        let text = std::fs::read_to_string(path).map_err(|e| ValkyrieError::io(path, e))?;
+        let error = |message: String| ValkyrieErrorKind::ThemeError { path: path.to_path_buf(), message };
+        let schema = match text.trim_start().starts_with('<') {
+            true => ColorSchema::from_jetbrains_scheme(&text),
+            false => ColorSchema::from_theme(&json5::from_str::<Value>(&text).map_err(|e| error(e.to_string()))?),
+        };
+        match schema {
+            Some(s) => Ok(s),
+            None => Err(error("expected a VS Code color theme or a JetBrains color scheme".to_string()).into()),
+        }
+    }
+    /// The colors of a parsed VS Code theme, the categories it does not color keep the preset of its kind.
+    ///
+    /// Themes are recognized by `type`, `tokenColors` or `semanticTokenColors`.
+    pub fn from_theme(theme: &Value) -> Option<ColorSchema> {
+        match ["type", "tokenColors", "semanticTokenColors"].iter().any(|key| theme.get(key).is_some()) {
+            true => Some(vscode_schema(theme)),
+            false => None,
+        }
+    }
+    /// The colors of a JetBrains color scheme, the categories it does not color keep the preset of its kind.
+    ///
+    /// Schemes based on Darcula or with `dark` in their name are dark.
+    pub fn from_jetbrains_scheme(xml: &str) -> Option<ColorSchema> {
+        let mut tags = XmlTags { rest: xml };
+        let root = tags.next()?;
+        if root.name != "scheme" || root.closing {
+            return None;
+        }
+        let base = root.attribute("parent_scheme").or(root.attribute("name")).unwrap_or_default().to_lowercase();
+        let mut schema = match base.contains("darcula") || base.contains("dark") {
+            true => ColorSchema::DARK,
+            false => ColorSchema::LIGHT,
+        };
+        // the open tags below the root, a color is `<attributes><option name><value><option name value/>`
+        let mut open: Vec<XmlTag> = vec![];
+        let mut colors: BTreeMap<(&str, &str), Color> = BTreeMap::new();
+        for tag in tags {
+            if tag.closing {
+                open.pop();
+                continue;
+            }
+            if let [.., attributes, option, value] = open.as_slice() {
+                let color = tag.attribute("value").and_then(jetbrains_color);
+                let within = attributes.name == "attributes" && value.name == "value";
+                if let (true, Some(key), Some(part), Some(color)) =
+                    (within, option.attribute("name"), tag.attribute("name"), color)
+                {
+                    colors.insert((key, part), color);
+                }
+            }
+            if !tag.self_closing {
+                open.push(tag);
+            }
+        }
+        for (category, key) in JETBRAINS_KEYS {
+            // the text color, error attributes usually only have a wave underline
+            let color = ["FOREGROUND", "EFFECT_COLOR"].iter().find_map(|part| colors.get(&(*key, *part)));
+            if let (Some(slot), Some(color)) = (schema.category_mut(category), color) {
+                *slot = *color;
+            }
+        }
+        Some(schema)
+    }
+}
+
+fn vscode_schema(theme: &Value) -> ColorSchema {
+    let mut schema = match theme["type"].as_str() {
+        Some("light" | "hc-light") => ColorSchema::LIGHT,
+        _ => ColorSchema::DARK,
+    };
+    for (category, scope) in TEXTMATE_SCOPES {
+        if let (Some(slot), Some(color)) = (schema.category_mut(category), textmate_color(theme, scope)) {
+            *slot = color;
+        }
+    }
+    // semantic colors take precedence over TextMate colors, as in the editor
+    for (category, tokens) in SEMANTIC_TOKENS {
+        let color = tokens.iter().find_map(|token| color_of(&theme["semanticTokenColors"][*token]));
+        if let (Some(slot), Some(color)) = (schema.category_mut(category), color) {
+            *slot = color;
+        }
+    }
+    if let Some(color) = color_of(&theme["colors"]["editorError.foreground"]) {
+        schema.bad = color;
+    }
+    schema
+}
+
+/// The foreground of the most specific rule matching the scope, the later rule on ties.
+fn textmate_color(theme: &Value, scope: &str) -> Option<Color> {
+    let mut best: Option<(usize, Color)> = None;
+    for rule in theme["tokenColors"].as_array().into_iter().flatten() {
+        let color = match color_of(&rule["settings"]["foreground"]) {
+            Some(s) => s,
+            None => continue,
+        };
+        let selectors: Vec<&str> = match &rule["scope"] {
+            Value::String(s) => s.split(',').collect(),
+            Value::Array(s) => s.iter().filter_map(Value::as_str).collect(),
+            _ => continue,
+        };
+        for selector in selectors.into_iter().map(str::trim) {
+            if selector_matches(selector, scope) && best.is_none_or(|(length, _)| selector.len() >= length) {
+                best = Some((selector.len(), color));
+            }
+        }
+    }
+    best.map(|(_, color)| color)
+}
+
+/// A selector matches the scope and its children, descendant selectors are not supported.
+fn selector_matches(selector: &str, scope: &str) -> bool {
+    match scope.strip_prefix(selector) {
+        Some(rest) => !selector.is_empty() && !selector.contains(' ') && (rest.is_empty() || rest.starts_with('.')),
+        None => false,
+    }
+}
+
+/// A JetBrains color, hexadecimal RGB without `#` or leading zeros.
+fn jetbrains_color(value: &str) -> Option<Color> {
+    if value.is_empty() || value.len() > 6 {
+        return None;
+    }
+    let value = u32::from_str_radix(value, 16).ok()?;
+    Some(Color::rgb((value >> 16) as u8, (value >> 8) as u8, value as u8))
+}
+
+/// A start, end or empty element tag of an XML document, declarations and comments are skipped.
+#[derive(Debug)]
+struct XmlTag<'i> {
+    name: &'i str,
+    attributes: Vec<(&'i str, &'i str)>,
+    closing: bool,
+    self_closing: bool,
+}
+
+impl<'i> XmlTag<'i> {
+    fn attribute(&self, name: &str) -> Option<&'i str> {
+        self.attributes.iter().find(|(key, _)| *key == name).map(|(_, value)| *value)
+    }
+}
+
+/// The tags of an XML document in order, enough for color schemes, entities in values are kept as is.
+struct XmlTags<'i> {
+    rest: &'i str,
+}
+
+impl<'i> Iterator for XmlTags<'i> {
+    type Item = XmlTag<'i>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let start = self.rest.find('<')?;
+            self.rest = &self.rest[start..];
+            let skipped =
+                [("<!--", "-->"), ("<?", "?>"), ("<!", ">")].into_iter().find(|(open, _)| self.rest.starts_with(open));
+            if let Some((_, close)) = skipped {
+                let end = self.rest.find(close)?;
+                self.rest = &self.rest[end + close.len()..];
+                continue;
+            }
+            let end = tag_end(self.rest)?;
+            let body = &self.rest[1..end];
+            self.rest = &self.rest[end + 1..];
+            let (closing, body) = match body.strip_prefix('/') {
+                Some(body) => (true, body),
+                None => (false, body),
+            };
+            let (self_closing, body) = match body.strip_suffix('/') {
+                Some(body) => (true, body),
+                None => (false, body),
+            };
+            let name_end = body.find(|c: char| c.is_whitespace()).unwrap_or(body.len());
+            let attributes = xml_attributes(&body[name_end..]);
+            return Some(XmlTag { name: &body[..name_end], attributes, closing, self_closing });
+        }
+    }
+}
+
+/// The offset of the `>` closing the tag at the start, skipping quoted values.
+fn tag_end(tag: &str) -> Option<usize> {
+    let mut quote = None;
+    for (offset, c) in tag.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if q == c => quote = None,
+            (None, '>') => return Some(offset),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn xml_attributes(mut rest: &str) -> Vec<(&str, &str)> {
+    let mut out = vec![];
+    while let Some(equal) = rest.find('=') {
+        let key = rest[..equal].trim();
+        let value = rest[equal + 1..].trim_start();
+        let quote = match value.chars().next() {
+            Some(q @ ('"' | '\'')) => q,
+            _ => break,
+        };
+        let end = match value[1..].find(quote) {
+            Some(s) => s + 1,
+            None => break,
+        };
+        out.push((key, &value[1..end]));
+        rest = &value[end + 1..];
+    }
+    out
+}
+
+/// A color written directly, or as the `foreground` of a style.
+fn color_of(value: &Value) -> Option<Color> {
+    match value {
+        Value::String(s) => s.parse().ok(),
+        Value::Object(_) => value["foreground"].as_str()?.parse().ok(),
+        _ => None,
+    }
+}
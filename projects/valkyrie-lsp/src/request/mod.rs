@@ -0,0 +1,63 @@
+use std::sync::{Arc, Mutex};
+
+use tower_lsp::lsp_types::Url;
+
+use crate::{DocumentStore, FileId, LineIndex, Snapshot, ValkyrieErrorKind, ValkyrieResult, diagnostics::positions};
+
+/// Watches a long request for cancellation by the client and for edits of the opened documents it reads.
+///
+/// `tower-lsp` answers `$/cancelRequest` with `RequestCancelled` and drops the handler at its next await, so the work
+/// has to pass a [checkpoint](RequestGuard::checkpoint) between files. Must be created before the snapshot is taken.
+#[derive(Debug)]
+pub(crate) struct RequestGuard {
+    documents: Arc<DocumentStore>,
+    /// The version of each opened document the request read, when it read it.
+    versions: Mutex<Vec<(Url, i32)>>,
+}
+
+impl RequestGuard {
+    /// A guard of a workspace request, which reads documents only through [line_index](RequestGuard::line_index).
+    pub fn new(documents: Arc<DocumentStore>) -> Self {
+        Self { documents, versions: Mutex::default() }
+    }
+    /// A guard of a request on a document, edits of the document since now fail the request.
+    pub fn for_document(documents: Arc<DocumentStore>, uri: &Url) -> Self {
+        let guard = Self::new(documents);
+        if let Some(version) = guard.documents.version(uri) {
+            guard.read(uri, version);
+        }
+        guard
+    }
+    /// Give the client a chance to cancel the request, then check that no document it read was edited since.
+    pub async fn checkpoint(&self) -> ValkyrieResult<()> {
+        tokio::task::yield_now().await;
+        self.check()
+    }
+    /// Fail with `ContentModified` if an opened document the request read was edited or closed since.
+    pub fn check(&self) -> ValkyrieResult<()> {
+        for (uri, version) in self.versions.lock().unwrap().iter() {
+            if self.documents.version(uri) != Some(*version) {
+                return Err(ValkyrieErrorKind::ContentModified { uri: uri.clone() }.into());
+            }
+        }
+        Ok(())
+    }
+    /// The line index of a file in the snapshot, fails if the opened document has another text by now.
+    pub fn line_index(&self, snapshot: &Snapshot, uri: &Url, file: FileId) -> ValkyrieResult<LineIndex> {
+        match positions(snapshot, &self.documents, uri, file) {
+            Some((index, version)) => {
+                if let Some(version) = version {
+                    self.read(uri, version);
+                }
+                Ok(index)
+            }
+            None => Err(ValkyrieErrorKind::ContentModified { uri: uri.clone() }.into()),
+        }
+    }
+    fn read(&self, uri: &Url, version: i32) {
+        let mut versions = self.versions.lock().unwrap();
+        if !versions.iter().any(|(read, _)| read == uri) {
+            versions.push((uri.clone(), version));
+        }
+    }
+}
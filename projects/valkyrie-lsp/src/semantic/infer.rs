@@ -0,0 +1,803 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::{Display, Formatter},
+    ops::Range,
+    sync::Arc,
+};
+
+use super::{DefMap, Definition, FileScope, ItemKind, ItemLoc, ItemTree, Resolution, Resolver, TypeRef};
+use crate::{
+    FileId, Snapshot, SyntaxKind, SyntaxNode,
+    ast::{self, AstNode},
+};
+
+/// The type of a value, as far as it can be inferred.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Ty {
+    /// Nothing is known about the type.
+    Unknown,
+    /// A declared type with its generic arguments.
+    Item {
+        /// The declaration of the type.
+        item: ItemLoc,
+        /// The name of the type.
+        name: String,
+        /// The generic arguments.
+        args: Vec<Ty>,
+    },
+    /// A generic parameter.
+    Generic(String),
+    /// A type without declaration in the workspace, such as literal types.
+    Primitive(String),
+    /// `(A, B)`, the empty tuple is the unit type.
+    Tuple(Vec<Ty>),
+    /// `(A) -> B`
+    Function {
+        /// The parameter types.
+        params: Vec<Ty>,
+        /// The return type.
+        ret: Box<Ty>,
+    },
+}
+
+/// A local binding, a parameter or a name bound by a pattern.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Local {
+    /// The bound name.
+    pub name: String,
+    /// The span of the name at the binding.
+    pub span: Range<usize>,
+    /// The inferred type.
+    pub ty: Ty,
+    /// Check if the binding is `mut`.
+    pub mutable: bool,
+}
+
+/// Names and types of a file, resolved and inferred.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct InferenceResult {
+    /// All local bindings in the file.
+    pub locals: Vec<Local>,
+    /// The resolution of each name reference, by the start offset of its token.
+    pub resolutions: HashMap<usize, Resolution>,
+    /// The type of each name with a value, declarations and references, by the start offset of its token.
+    pub types: HashMap<usize, Ty>,
+}
+
+impl Display for Ty {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        fn list(f: &mut Formatter<'_>, items: &[Ty]) -> std::fmt::Result {
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    f.write_str(", ")?;
+                }
+                write!(f, "{}", item)?;
+            }
+            Ok(())
+        }
+        match self {
+            Ty::Unknown => f.write_str("?"),
+            Ty::Item { name, args, .. } => {
+                f.write_str(name)?;
+                if !args.is_empty() {
+                    f.write_str("<")?;
+                    list(f, args)?;
+                    f.write_str(">")?;
+                }
+                Ok(())
+            }
+            Ty::Generic(name) | Ty::Primitive(name) => f.write_str(name),
+            Ty::Tuple(items) => {
+                f.write_str("(")?;
+                list(f, items)?;
+                f.write_str(")")
+            }
+            Ty::Function { params, ret } => {
+                f.write_str("(")?;
+                list(f, params)?;
+                write!(f, ") -> {}", ret)
+            }
+        }
+    }
+}
+
+impl Ty {
+    /// The unit type `()`.
+    pub fn unit() -> Ty {
+        Ty::Tuple(vec![])
+    }
+    fn primitive(name: &str) -> Ty {
+        Ty::Primitive(name.to_string())
+    }
+    /// The declaration of the type, if any.
+    pub fn item(&self) -> Option<ItemLoc> {
+        match self {
+            Ty::Item { item, .. } => Some(*item),
+            _ => None,
+        }
+    }
+    /// The element type of a generic container, its last generic argument.
+    pub fn element(&self) -> Ty {
+        match self {
+            Ty::Item { args, .. } => args.last().cloned().unwrap_or(Ty::Unknown),
+            _ => Ty::Unknown,
+        }
+    }
+    fn substitute(&self, substitution: &HashMap<String, Ty>) -> Ty {
+        match self {
+            Ty::Generic(name) => substitution.get(name).cloned().unwrap_or_else(|| self.clone()),
+            Ty::Item { item, name, args } => {
+                Ty::Item { item: *item, name: name.clone(), args: args.iter().map(|ty| ty.substitute(substitution)).collect() }
+            }
+            Ty::Tuple(items) => Ty::Tuple(items.iter().map(|ty| ty.substitute(substitution)).collect()),
+            Ty::Function { params, ret } => Ty::Function {
+                params: params.iter().map(|ty| ty.substitute(substitution)).collect(),
+                ret: Box::new(ret.substitute(substitution)),
+            },
+            Ty::Unknown | Ty::Primitive(_) => self.clone(),
+        }
+    }
+}
+
+/// The declarations a file can see, to resolve names and types from any file.
+struct Context {
+    file: FileId,
+    tree: Arc<ItemTree>,
+    scope: Arc<FileScope>,
+}
+
+/// Infer the types and resolve the names of a file.
+pub(crate) fn infer_file(db: &Snapshot, file: FileId) -> InferenceResult {
+    let parse = db.parse(file);
+    let sources = db.item_sources(file);
+    let items = sources.items.iter().enumerate().map(|(index, source)| (source.span.start, index as u32)).collect();
+    let mut infer = Infer {
+        db,
+        file,
+        map: db.def_map(),
+        context: Context { file, tree: db.item_tree(file), scope: db.file_scope(file) },
+        items,
+        namespace: vec![],
+        generics: vec![],
+        scopes: vec![],
+        result: InferenceResult::default(),
+    };
+    let root = parse.syntax();
+    for node in root.descendants() {
+        match node.kind() {
+            SyntaxKind::FunctionDecl => {
+                if let Some(function) = ast::FunctionDecl::cast(node) {
+                    infer.function(function);
+                }
+            }
+            SyntaxKind::PathType => {
+                let path = ast::PathType::cast(node.clone()).and_then(|ty| ty.path());
+                if let Some(path) = path {
+                    infer.enter(&node);
+                    infer.record_path(&path, |this, names| this.resolve_type_path(names));
+                }
+            }
+            SyntaxKind::UseTree => infer.use_tree(node),
+            SyntaxKind::NamespaceDecl => infer.namespace_decl(node),
+            _ => {}
+        }
+    }
+    infer.result
+}
+
+struct Infer<'a> {
+    db: &'a Snapshot,
+    file: FileId,
+    map: Arc<DefMap>,
+    context: Context,
+    /// The item index of each declaration, by the start offset of its node.
+    items: HashMap<usize, u32>,
+    namespace: Vec<String>,
+    generics: Vec<String>,
+    scopes: Vec<Vec<(String, u32)>>,
+    result: InferenceResult,
+}
+
+impl<'a> Infer<'a> {
+    /// The item tree index of the innermost declaration containing the node.
+    fn enclosing_item(&self, node: &SyntaxNode) -> Option<u32> {
+        node.ancestors().find_map(|ancestor| match is_item(ancestor.kind()) {
+            true => self.items.get(&ancestor.trimmed_span().start).copied(),
+            false => None,
+        })
+    }
+    /// Set the namespace and generics for the node.
+    fn enter(&mut self, node: &SyntaxNode) {
+        match self.enclosing_item(node) {
+            Some(index) => {
+                self.namespace = self.context.tree.item(index).namespace.clone();
+                self.generics = generics_in_scope(&self.context.tree, index);
+            }
+            None => {
+                self.namespace = self.context.tree.namespace.clone();
+                self.generics = vec![];
+            }
+        }
+    }
+    fn resolver<'r>(&'r self, context: &'r Context, namespace: &'r [String]) -> Resolver<'r> {
+        Resolver { map: &self.map, file: context.file, tree: &context.tree, scope: &context.scope, namespace }
+    }
+    fn resolve_path(&self, names: &[String]) -> Resolution {
+        self.resolver(&self.context, &self.namespace).resolve(names)
+    }
+    fn resolve_type_path(&self, names: &[String]) -> Resolution {
+        if names.len() == 1 && self.generics.contains(&names[0]) {
+            return Resolution::default();
+        }
+        self.resolve_path(names)
+    }
+    /// Record the resolution of every prefix of the path on its last segment.
+    fn record_path(&mut self, path: &ast::Path, resolve: impl Fn(&Self, &[String]) -> Resolution) -> Resolution {
+        let names = path.to_names();
+        let mut last = Resolution::default();
+        for (index, segment) in path.segments().enumerate() {
+            let resolution = resolve(self, &names[..=index]);
+            if let Some(token) = segment.name_ref().and_then(|name| name.token()) {
+                self.result.resolutions.insert(token.span().start, resolution.clone());
+            }
+            last = resolution;
+        }
+        last
+    }
+    fn use_tree(&mut self, node: SyntaxNode) {
+        let tree = match ast::UseTree::cast(node) {
+            Some(s) => s,
+            None => return,
+        };
+        let path = match tree.path() {
+            Some(s) => s,
+            None => return,
+        };
+        let mut prefix = vec![];
+        for ancestor in tree.syntax().ancestors().skip(1).filter_map(ast::UseTree::cast) {
+            if let Some(path) = ancestor.path() {
+                prefix.splice(0..0, path.to_names());
+            }
+        }
+        self.record_path(&path, |this, names| {
+            let mut absolute = prefix.clone();
+            absolute.extend(names.iter().cloned());
+            Resolution { definitions: this.map.resolve_absolute(&absolute, this.file), import: None }
+        });
+    }
+    fn namespace_decl(&mut self, node: SyntaxNode) {
+        let path = match ast::NamespaceDecl::cast(node.clone()).and_then(|namespace| namespace.path()) {
+            Some(s) => s,
+            None => return,
+        };
+        let mut prefix = vec![];
+        for ancestor in node.ancestors().skip(1).filter_map(ast::NamespaceDecl::cast) {
+            if let Some(path) = ancestor.path() {
+                prefix.splice(0..0, path.to_names());
+            }
+        }
+        self.record_path(&path, |_, names| {
+            let mut absolute = prefix.clone();
+            absolute.extend(names.iter().cloned());
+            Resolution { definitions: vec![Definition::Namespace(absolute)], import: None }
+        });
+    }
+    fn function(&mut self, function: ast::FunctionDecl) {
+        let index = match self.items.get(&function.syntax().trimmed_span().start) {
+            Some(s) => *s,
+            None => return,
+        };
+        self.enter(function.syntax());
+        let tree = self.context.tree.clone();
+        let item = tree.item(index);
+        let self_ty = match item.parent {
+            Some(parent) => self.self_ty(ItemLoc { file: self.file, index: parent }),
+            None => Ty::Unknown,
+        };
+        self.scopes = vec![vec![]];
+        for param in function.params() {
+            let ty = match param.ty() {
+                Some(ty) => self.lower(&TypeRef::lower(Some(ty))),
+                None if param.is_self() => self_ty.clone(),
+                None => Ty::Unknown,
+            };
+            match param.pattern() {
+                Some(pattern) => self.pattern(&pattern, ty),
+                None => {
+                    if let Some(name) = param.name() {
+                        self.bind(&name, ty, false);
+                    }
+                }
+            }
+        }
+        if let Some(body) = function.body() {
+            self.block(&body);
+        }
+        self.scopes.clear();
+    }
+    /// The type of `self` in the members of the declaration.
+    fn self_ty(&self, container: ItemLoc) -> Ty {
+        let tree = self.db.item_tree(container.file);
+        let item = tree.item(container.index);
+        match item.kind {
+            ItemKind::Imply => match &item.ty {
+                Some(ty) => self.lower_in(container, ty),
+                None => Ty::Unknown,
+            },
+            _ => Ty::Item {
+                item: container,
+                name: item.name.clone(),
+                args: item.generics.iter().map(|name| Ty::Generic(name.clone())).collect(),
+            },
+        }
+    }
+    fn bind(&mut self, name: &ast::Name, ty: Ty, mutable: bool) {
+        let token = match name.token() {
+            Some(s) => s,
+            None => return,
+        };
+        let id = self.result.locals.len() as u32;
+        self.result.locals.push(Local { name: name.text(), span: token.span(), ty: ty.clone(), mutable });
+        self.result.types.insert(token.span().start, ty);
+        self.result
+            .resolutions
+            .insert(token.span().start, Resolution { definitions: vec![Definition::Local(id)], import: None });
+        if let Some(scope) = self.scopes.last_mut() {
+            scope.push((name.text(), id));
+        }
+    }
+    fn lookup_local(&self, name: &str) -> Option<u32> {
+        self.scopes.iter().rev().flat_map(|scope| scope.iter().rev()).find(|(local, _)| local == name).map(|(_, id)| *id)
+    }
+    fn pattern(&mut self, pattern: &ast::Pat, ty: Ty) {
+        match pattern {
+            ast::Pat::Ident(pattern) => {
+                if let Some(name) = pattern.name() {
+                    self.bind(&name, ty, pattern.is_mut());
+                }
+            }
+            ast::Pat::Tuple(pattern) => {
+                let items = match ty {
+                    Ty::Tuple(items) => items,
+                    _ => vec![],
+                };
+                for (index, pattern) in pattern.pats().enumerate() {
+                    self.pattern(&pattern, items.get(index).cloned().unwrap_or(Ty::Unknown));
+                }
+            }
+            ast::Pat::Variant(pattern) => {
+                let resolution = match pattern.path() {
+                    Some(path) => self.record_path(&path, |this, names| this.resolve_path(names)),
+                    None => Resolution::default(),
+                };
+                let payload = self.variant_payload(&resolution, &ty);
+                for (index, pattern) in pattern.pats().iter().enumerate() {
+                    self.pattern(pattern, payload.get(index).cloned().unwrap_or(Ty::Unknown));
+                }
+            }
+            ast::Pat::Literal(_) => {}
+        }
+    }
+    /// The payload types of a matched union variant, with the generic arguments of the scrutinee.
+    fn variant_payload(&self, resolution: &Resolution, scrutinee: &Ty) -> Vec<Ty> {
+        let variant = resolution.definitions.iter().find_map(|definition| match definition {
+            Definition::Item(loc) => Some(*loc),
+            _ => None,
+        });
+        let variant = match variant {
+            Some(s) => s,
+            None => return vec![],
+        };
+        let tree = self.db.item_tree(variant.file);
+        let payload = match &tree.item(variant.index).ty {
+            Some(ty) => self.lower_in(variant, ty),
+            None => return vec![],
+        };
+        let payload = match tree.item(variant.index).parent {
+            Some(union) => payload.substitute(&substitution(&tree, union, scrutinee)),
+            None => payload,
+        };
+        match payload {
+            Ty::Tuple(items) => items,
+            ty => vec![ty],
+        }
+    }
+    fn block(&mut self, block: &ast::BlockExpr) -> Ty {
+        self.scopes.push(vec![]);
+        let mut ty = Ty::unit();
+        for statement in block.statements() {
+            ty = Ty::unit();
+            match statement {
+                ast::Stmt::Let(statement) => {
+                    let value = statement.initializer().map(|value| self.expr(&value)).unwrap_or(Ty::Unknown);
+                    let ty = match statement.ty() {
+                        Some(ty) => self.lower(&TypeRef::lower(Some(ty))),
+                        None => value,
+                    };
+                    if let Some(pattern) = statement.pattern() {
+                        self.pattern(&pattern, ty);
+                    }
+                }
+                ast::Stmt::Expr(statement) => {
+                    if let Some(expr) = statement.expr() {
+                        let value = self.expr(&expr);
+                        if !statement.has_semicolon() {
+                            ty = value;
+                        }
+                    }
+                }
+                ast::Stmt::Item(_) => {}
+            }
+        }
+        self.scopes.pop();
+        ty
+    }
+    fn expr(&mut self, expr: &ast::Expr) -> Ty {
+        match expr {
+            ast::Expr::Literal(literal) => match literal.token().map(|token| token.kind()) {
+                Some(SyntaxKind::Integer) => Ty::primitive("Integer"),
+                Some(SyntaxKind::Decimal) => Ty::primitive("Decimal"),
+                Some(SyntaxKind::String) => Ty::primitive("String"),
+                Some(SyntaxKind::TrueKw | SyntaxKind::FalseKw) => Ty::primitive("Boolean"),
+                _ => Ty::Unknown,
+            },
+            ast::Expr::Path(expr) => match expr.path() {
+                Some(path) => self.path_expr(&path),
+                None => Ty::Unknown,
+            },
+            ast::Expr::Paren(expr) => self.optional(expr.expr()),
+            ast::Expr::Tuple(expr) => Ty::Tuple(expr.exprs().map(|item| self.expr(&item)).collect()),
+            ast::Expr::Array(expr) => {
+                for item in expr.exprs() {
+                    self.expr(&item);
+                }
+                Ty::Unknown
+            }
+            ast::Expr::Block(block) => self.block(block),
+            ast::Expr::Prefix(expr) => {
+                let operand = self.optional(expr.expr());
+                match expr.op_token().map(|token| token.kind()) {
+                    Some(SyntaxKind::Bang | SyntaxKind::NotKw) => Ty::primitive("Boolean"),
+                    _ => operand,
+                }
+            }
+            ast::Expr::Binary(expr) => {
+                let lhs = self.optional(expr.lhs());
+                let rhs = self.optional(expr.rhs());
+                match expr.op_text().as_str() {
+                    "==" | "!=" | "<" | ">" | "<=" | ">=" | "&&" | "||" => Ty::primitive("Boolean"),
+                    "??" => rhs,
+                    "|>" => match rhs {
+                        Ty::Function { ret, .. } => *ret,
+                        _ => Ty::Unknown,
+                    },
+                    op if op.ends_with('=') => Ty::unit(),
+                    _ => lhs,
+                }
+            }
+            ast::Expr::Cast(expr) => {
+                self.optional(expr.expr());
+                match expr.is_test() {
+                    true => Ty::primitive("Boolean"),
+                    false => self.lower_optional(expr.ty()),
+                }
+            }
+            ast::Expr::Call(expr) => {
+                let callee = self.optional(expr.callee());
+                self.args(expr.arg_list());
+                match callee {
+                    Ty::Function { ret, .. } => *ret,
+                    ty @ Ty::Item { .. } => ty,
+                    _ => Ty::Unknown,
+                }
+            }
+            ast::Expr::Index(expr) => {
+                let base = self.optional(expr.base());
+                self.optional(expr.index());
+                base.element()
+            }
+            ast::Expr::Field(expr) => {
+                let receiver = self.optional(expr.receiver());
+                let name = match expr.name_ref() {
+                    Some(s) => s,
+                    None => return Ty::Unknown,
+                };
+                let token = match name.token() {
+                    Some(s) => s,
+                    None => return Ty::Unknown,
+                };
+                if let (Ty::Tuple(items), SyntaxKind::Integer) = (&receiver, token.kind()) {
+                    let ty =
+                        token.text().parse::<usize>().ok().and_then(|index| items.get(index).cloned()).unwrap_or(Ty::Unknown);
+                    self.result.types.insert(token.span().start, ty.clone());
+                    return ty;
+                }
+                let members = self.members(&receiver, &name.text());
+                let ty = members.first().map(|(_, ty)| ty.clone()).unwrap_or(Ty::Unknown);
+                let definitions = members.into_iter().map(|(loc, _)| Definition::Item(loc)).collect();
+                self.result.resolutions.insert(token.span().start, Resolution { definitions, import: None });
+                self.result.types.insert(token.span().start, ty.clone());
+                ty
+            }
+            ast::Expr::Try(expr) => match self.optional(expr.expr()) {
+                Ty::Item { args, .. } => args.first().cloned().unwrap_or(Ty::Unknown),
+                _ => Ty::Unknown,
+            },
+            ast::Expr::New(expr) => {
+                self.args(expr.arg_list());
+                self.lower_optional(expr.ty())
+            }
+            ast::Expr::If(expr) => {
+                self.optional(expr.condition());
+                let ty = match expr.then_branch() {
+                    Some(block) => self.block(&block),
+                    None => Ty::Unknown,
+                };
+                self.optional(expr.else_branch());
+                ty
+            }
+            ast::Expr::While(expr) => {
+                self.optional(expr.condition());
+                if let Some(body) = expr.body() {
+                    self.block(&body);
+                }
+                Ty::unit()
+            }
+            ast::Expr::Loop(expr) => {
+                if let Some(body) = expr.body() {
+                    self.block(&body);
+                }
+                Ty::Unknown
+            }
+            ast::Expr::For(expr) => {
+                let element = self.optional(expr.iterable()).element();
+                self.scopes.push(vec![]);
+                if let Some(pattern) = expr.pattern() {
+                    self.pattern(&pattern, element);
+                }
+                if let Some(body) = expr.body() {
+                    self.block(&body);
+                }
+                self.scopes.pop();
+                Ty::unit()
+            }
+            ast::Expr::Match(expr) => {
+                let scrutinee = self.optional(expr.scrutinee());
+                let mut ty = None;
+                for arm in expr.arms() {
+                    self.scopes.push(vec![]);
+                    if let Some(pattern) = arm.pattern() {
+                        self.pattern(&pattern, scrutinee.clone());
+                    }
+                    self.optional(arm.guard());
+                    let value = self.optional(arm.value());
+                    ty.get_or_insert(value);
+                    self.scopes.pop();
+                }
+                ty.unwrap_or(Ty::Unknown)
+            }
+            ast::Expr::Return(expr) => self.jump(expr.value()),
+            ast::Expr::Break(expr) => self.jump(expr.value()),
+            ast::Expr::Raise(expr) => self.jump(expr.value()),
+            ast::Expr::Yield(expr) => self.jump(expr.value()),
+            ast::Expr::Continue(_) => Ty::Unknown,
+            ast::Expr::TryCatch(expr) => {
+                let ty = match expr.body() {
+                    Some(body) => self.block(&body),
+                    None => Ty::Unknown,
+                };
+                for catch in expr.catches() {
+                    self.scopes.push(vec![]);
+                    if let Some(pattern) = catch.pattern() {
+                        self.pattern(&pattern, Ty::Unknown);
+                    }
+                    if let Some(body) = catch.body() {
+                        self.block(&body);
+                    }
+                    self.scopes.pop();
+                }
+                ty
+            }
+        }
+    }
+    fn optional(&mut self, expr: Option<ast::Expr>) -> Ty {
+        match expr {
+            Some(expr) => self.expr(&expr),
+            None => Ty::Unknown,
+        }
+    }
+    fn jump(&mut self, value: Option<ast::Expr>) -> Ty {
+        self.optional(value);
+        Ty::Unknown
+    }
+    fn args(&mut self, args: Option<ast::ArgList>) {
+        if let Some(args) = args {
+            for arg in args.args() {
+                self.expr(&arg);
+            }
+        }
+    }
+    fn path_expr(&mut self, path: &ast::Path) -> Ty {
+        let names = path.to_names();
+        let local = match names.as_slice() {
+            [name] => self.lookup_local(name),
+            _ => None,
+        };
+        let token = path.last_segment().and_then(|segment| segment.name_ref()).and_then(|name| name.token());
+        if let (Some(id), Some(token)) = (local, &token) {
+            let ty = self.result.locals[id as usize].ty.clone();
+            self.result
+                .resolutions
+                .insert(token.span().start, Resolution { definitions: vec![Definition::Local(id)], import: None });
+            self.result.types.insert(token.span().start, ty.clone());
+            return ty;
+        }
+        let resolution = self.record_path(path, |this, names| this.resolve_path(names));
+        let ty = resolution
+            .definitions
+            .iter()
+            .find_map(|definition| match definition {
+                Definition::Item(loc) => Some(self.item_value_ty(*loc)),
+                _ => None,
+            })
+            .unwrap_or(Ty::Unknown);
+        if let Some(token) = token {
+            self.result.types.insert(token.span().start, ty.clone());
+        }
+        ty
+    }
+    /// The type of a declaration used as a value.
+    fn item_value_ty(&self, loc: ItemLoc) -> Ty {
+        let tree = self.db.item_tree(loc.file);
+        let item = tree.item(loc.index);
+        match item.kind {
+            ItemKind::Function | ItemKind::Method => Ty::Function {
+                params: item.params.iter().map(|param| self.lower_optional_in(loc, param.ty.as_ref())).collect(),
+                ret: Box::new(self.lower_optional_in(loc, item.ty.as_ref())),
+            },
+            ItemKind::Field => self.lower_optional_in(loc, item.ty.as_ref()),
+            ItemKind::Variant => match item.parent {
+                Some(parent) => self.self_ty(ItemLoc { file: loc.file, index: parent }),
+                None => Ty::Unknown,
+            },
+            ItemKind::TypeAlias => self.lower_optional_in(loc, item.ty.as_ref()),
+            ItemKind::Class | ItemKind::Structure | ItemKind::Trait | ItemKind::Union => self.self_ty(loc),
+            ItemKind::Imply => Ty::Unknown,
+        }
+    }
+    /// The members with the name reachable from a type, with their types.
+    fn members(&self, ty: &Ty, name: &str) -> Vec<(ItemLoc, Ty)> {
+        let mut found = vec![];
+        let mut visited = BTreeSet::new();
+        let mut queue = vec![ty.clone()];
+        while let Some(ty) = queue.pop() {
+            let container = match &ty {
+                Ty::Item { item, .. } => *item,
+                _ => continue,
+            };
+            if !visited.insert(container) {
+                continue;
+            }
+            let tree = self.db.item_tree(container.file);
+            let item = tree.item(container.index);
+            if item.kind == ItemKind::TypeAlias {
+                if let Some(target) = &item.ty {
+                    queue.push(self.lower_in(container, target));
+                }
+                continue;
+            }
+            let substitution = substitution(&tree, container.index, &ty);
+            let mut blocks = vec![container];
+            blocks.extend(self.map.implementations.get(&container).into_iter().flatten().copied());
+            for block in blocks {
+                let block_tree = self.db.item_tree(block.file);
+                for member in block_tree.members(block.index) {
+                    if block_tree.item(member).name == name {
+                        let loc = ItemLoc { file: block.file, index: member };
+                        found.push((loc, self.item_value_ty(loc).substitute(&substitution)));
+                    }
+                }
+                for super_type in &block_tree.item(block.index).super_types {
+                    queue.push(self.lower_in(block, super_type).substitute(&substitution));
+                }
+            }
+        }
+        found
+    }
+    fn lower(&self, ty: &TypeRef) -> Ty {
+        lower_type(self, &self.context, &self.namespace, &self.generics, ty, 0)
+    }
+    fn lower_optional(&self, ty: Option<ast::Type>) -> Ty {
+        match ty {
+            Some(ty) => self.lower(&TypeRef::lower(Some(ty))),
+            None => Ty::Unknown,
+        }
+    }
+    /// Lower a type written in a declaration, possibly in another file.
+    fn lower_in(&self, loc: ItemLoc, ty: &TypeRef) -> Ty {
+        let context = Context { file: loc.file, tree: self.db.item_tree(loc.file), scope: self.db.file_scope(loc.file) };
+        let namespace = context.tree.item(loc.index).namespace.clone();
+        let generics = generics_in_scope(&context.tree, loc.index);
+        lower_type(self, &context, &namespace, &generics, ty, 0)
+    }
+    fn lower_optional_in(&self, loc: ItemLoc, ty: Option<&TypeRef>) -> Ty {
+        match ty {
+            Some(ty) => self.lower_in(loc, ty),
+            None => Ty::Unknown,
+        }
+    }
+}
+
+fn lower_type(infer: &Infer, context: &Context, namespace: &[String], generics: &[String], ty: &TypeRef, depth: usize) -> Ty {
+    match ty {
+        TypeRef::Path { path, args } => {
+            if let [name] = path.as_slice() {
+                if generics.contains(name) {
+                    return Ty::Generic(name.clone());
+                }
+            }
+            let args: Vec<Ty> = args.iter().map(|arg| lower_type(infer, context, namespace, generics, arg, depth)).collect();
+            let resolver = infer.resolver(context, namespace);
+            let target =
+                resolver.resolve_type(Some(ty)).into_iter().find(|loc| infer.db.item_tree(loc.file).item(loc.index).is_type());
+            match target {
+                Some(loc) => {
+                    let tree = infer.db.item_tree(loc.file);
+                    let item = tree.item(loc.index);
+                    match (item.kind, &item.ty) {
+                        (ItemKind::TypeAlias, Some(target)) if depth < 16 => {
+                            let context = Context { file: loc.file, tree: tree.clone(), scope: infer.db.file_scope(loc.file) };
+                            let generics = generics_in_scope(&tree, loc.index);
+                            let substitution = item.generics.iter().cloned().zip(args).collect();
+                            lower_type(infer, &context, &item.namespace, &generics, target, depth + 1).substitute(&substitution)
+                        }
+                        _ => Ty::Item { item: loc, name: item.name.clone(), args },
+                    }
+                }
+                None if path.len() == 1 => Ty::Primitive(path[0].clone()),
+                None => Ty::Unknown,
+            }
+        }
+        TypeRef::Tuple(items) => {
+            Ty::Tuple(items.iter().map(|item| lower_type(infer, context, namespace, generics, item, depth)).collect())
+        }
+        TypeRef::Function { params, ret } => Ty::Function {
+            params: params.iter().map(|param| lower_type(infer, context, namespace, generics, param, depth)).collect(),
+            ret: Box::new(lower_type(infer, context, namespace, generics, ret, depth)),
+        },
+        TypeRef::Missing => Ty::Unknown,
+    }
+}
+
+/// The generic arguments of the type bound to the generic parameters of the declaration.
+fn substitution(tree: &ItemTree, index: u32, ty: &Ty) -> HashMap<String, Ty> {
+    match ty {
+        Ty::Item { args, .. } => tree.item(index).generics.iter().cloned().zip(args.iter().cloned()).collect(),
+        _ => HashMap::new(),
+    }
+}
+
+/// The generic parameters of the declaration and all its containers.
+fn generics_in_scope(tree: &ItemTree, index: u32) -> Vec<String> {
+    let mut generics = vec![];
+    let mut current = Some(index);
+    while let Some(index) = current {
+        generics.extend(tree.item(index).generics.iter().cloned());
+        current = tree.item(index).parent;
+    }
+    generics
+}
+
+fn is_item(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::ClassDecl
+            | SyntaxKind::TraitDecl
+            | SyntaxKind::ImplyDecl
+            | SyntaxKind::UnionDecl
+            | SyntaxKind::VariantDecl
+            | SyntaxKind::FunctionDecl
+            | SyntaxKind::FieldDecl
+            | SyntaxKind::TypeAliasDecl
+    )
+}
@@ -0,0 +1,417 @@
+use std::ops::Range;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    Parse, SyntaxElement, SyntaxKind, SyntaxNode,
+    ast::{self, AstNode, HasDocComments, HasGenericParams, HasModifiers, HasName},
+};
+
+/// The kind of a declaration.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum ItemKind {
+    /// `class`
+    Class,
+    /// `structure`
+    Structure,
+    /// `trait`
+    Trait,
+    /// `imply` or `extends`
+    Imply,
+    /// `union`
+    Union,
+    /// A variant of a union.
+    Variant,
+    /// A free function.
+    Function,
+    /// A function declared in a class, trait, union or imply.
+    Method,
+    /// A field of a class.
+    Field,
+    /// `type`
+    TypeAlias,
+}
+
+/// The declarations of a file without positions, so edits inside bodies leave it unchanged.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ItemTree {
+    /// The namespace declared for the whole file.
+    pub namespace: Vec<String>,
+    /// All declarations in source order, members right after their container.
+    pub items: Vec<ItemData>,
+    /// All imported paths, nested using trees flattened.
+    pub imports: Vec<Import>,
+}
+
+/// A declaration in an [ItemTree].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ItemData {
+    /// The kind of declaration.
+    pub kind: ItemKind,
+    /// The declared name, the implemented type name for `imply`.
+    pub name: String,
+    /// The namespace the declaration is in.
+    pub namespace: Vec<String>,
+    /// The fully qualified name, namespace and containers included.
+    pub namepath: Vec<String>,
+    /// The index of the containing declaration.
+    pub parent: Option<u32>,
+    /// The modifier keywords.
+    pub modifiers: Vec<SyntaxKind>,
+    /// The doc comment.
+    pub docs: Option<String>,
+    /// The declaration header with whitespace normalized, without body.
+    pub signature: String,
+    /// The generic parameter names.
+    pub generics: Vec<String>,
+    /// The parameters of functions.
+    pub params: Vec<ParamData>,
+    /// The field type, return type, payload type, aliased type, or implemented type.
+    pub ty: Option<TypeRef>,
+    /// The inherited or implemented types.
+    pub super_types: Vec<TypeRef>,
+    /// Check if a function has a body, methods without one are abstract.
+    pub has_body: bool,
+}
+
+/// A parameter of a function in an [ItemTree].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ParamData {
+    /// The bound name, empty for destructuring patterns.
+    pub name: String,
+    /// The declared type.
+    pub ty: Option<TypeRef>,
+}
+
+/// A type as written in a declaration, unresolved.
+#[derive(Clone, Debug, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum TypeRef {
+    /// `a::B<C>`
+    Path {
+        /// The path segments.
+        path: Vec<String>,
+        /// The generic arguments of the last segment.
+        args: Vec<TypeRef>,
+    },
+    /// `(A, B)`
+    Tuple(Vec<TypeRef>),
+    /// `(A) -> B`
+    Function {
+        /// The parameter types.
+        params: Vec<TypeRef>,
+        /// The return type.
+        ret: Box<TypeRef>,
+    },
+    /// A type that could not be parsed.
+    Missing,
+}
+
+/// An imported path in an [ItemTree].
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct Import {
+    /// The full imported path.
+    pub path: Vec<String>,
+    /// The name the import is visible as, empty for glob imports.
+    pub name: String,
+    /// Check if all names under the path are imported, `using a::*`.
+    pub glob: bool,
+}
+
+/// The positions of the declarations in an [ItemTree], indexed the same way.
+#[derive(Clone, Debug, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ItemSourceMap {
+    /// The spans of the items.
+    pub items: Vec<ItemSource>,
+    /// The spans of the imports.
+    pub imports: Vec<ItemSource>,
+}
+
+/// The position of a declaration.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize, Deserialize)]
+pub struct ItemSource {
+    /// The span of the whole declaration, doc comments excluded.
+    pub span: Range<usize>,
+    /// The span of the name, or of the whole declaration if it has no name.
+    pub name_span: Range<usize>,
+}
+
+impl ItemTree {
+    /// Lower the declarations of a parsed file.
+    pub fn lower(parse: &Parse) -> (ItemTree, ItemSourceMap) {
+        let file = parse.tree();
+        let namespace = match file.namespace().and_then(|namespace| namespace.path()) {
+            Some(path) => path.to_names(),
+            None => vec![],
+        };
+        let mut lower =
+            Lower { tree: ItemTree { namespace: namespace.clone(), ..ItemTree::default() }, map: ItemSourceMap::default() };
+        lower.items(file.items(), &namespace, None);
+        (lower.tree, lower.map)
+    }
+    /// The item at the index.
+    pub fn item(&self, index: u32) -> &ItemData {
+        &self.items[index as usize]
+    }
+    /// The indices of the direct members of an item.
+    pub fn members(&self, parent: u32) -> impl Iterator<Item = u32> + '_ {
+        self.items.iter().enumerate().filter(move |(_, item)| item.parent == Some(parent)).map(|(index, _)| index as u32)
+    }
+}
+
+impl ItemData {
+    /// Check if the declaration defines a type.
+    pub fn is_type(&self) -> bool {
+        matches!(self.kind, ItemKind::Class | ItemKind::Structure | ItemKind::Trait | ItemKind::Union | ItemKind::TypeAlias)
+    }
+}
+
+impl TypeRef {
+    /// Lower a type written in source.
+    pub fn lower(ty: Option<ast::Type>) -> TypeRef {
+        match ty {
+            Some(ast::Type::Path(ty)) => match ty.path() {
+                Some(path) => {
+                    let args = match path.last_segment().and_then(|segment| segment.generic_args()) {
+                        Some(args) => args.types().map(|ty| TypeRef::lower(Some(ty))).collect(),
+                        None => vec![],
+                    };
+                    TypeRef::Path { path: path.to_names(), args }
+                }
+                None => TypeRef::Missing,
+            },
+            Some(ast::Type::Tuple(ty)) => TypeRef::Tuple(ty.types().map(|ty| TypeRef::lower(Some(ty))).collect()),
+            Some(ast::Type::Function(ty)) => TypeRef::Function {
+                params: match ty.params() {
+                    Some(params) => params.types().map(|ty| TypeRef::lower(Some(ty))).collect(),
+                    None => vec![],
+                },
+                ret: Box::new(TypeRef::lower(ty.ret_type())),
+            },
+            None => TypeRef::Missing,
+        }
+    }
+}
+
+struct Lower {
+    tree: ItemTree,
+    map: ItemSourceMap,
+}
+
+impl Lower {
+    fn items(&mut self, items: impl Iterator<Item = ast::Item>, namespace: &[String], parent: Option<u32>) {
+        for item in items {
+            self.item(item, namespace, parent);
+        }
+    }
+    fn item(&mut self, item: ast::Item, namespace: &[String], parent: Option<u32>) {
+        match item {
+            ast::Item::Namespace(node) => {
+                if let (Some(path), Some(list)) = (node.path(), node.item_list()) {
+                    let mut inner = namespace.to_vec();
+                    inner.extend(path.to_names());
+                    self.items(list.items(), &inner, parent);
+                }
+            }
+            ast::Item::Using(node) => {
+                if let Some(tree) = node.use_tree() {
+                    self.use_tree(tree, &[]);
+                }
+            }
+            ast::Item::Class(node) => {
+                let kind = if node.is_structure() { ItemKind::Structure } else { ItemKind::Class };
+                let index = self.push(&node, kind, namespace, parent, |data| {
+                    data.generics = generics(&node);
+                    data.super_types = super_types(node.super_types());
+                });
+                self.members(node.item_list(), namespace, index);
+            }
+            ast::Item::Trait(node) => {
+                let index = self.push(&node, ItemKind::Trait, namespace, parent, |data| {
+                    data.generics = generics(&node);
+                    data.super_types = super_types(node.super_types());
+                });
+                self.members(node.item_list(), namespace, index);
+            }
+            ast::Item::Union(node) => {
+                let index = self.push(&node, ItemKind::Union, namespace, parent, |data| {
+                    data.generics = generics(&node);
+                });
+                for variant in node.variants() {
+                    self.push(&variant, ItemKind::Variant, namespace, Some(index), |data| {
+                        data.ty = variant.payload().map(|ty| TypeRef::lower(Some(ty)));
+                    });
+                }
+                self.members(node.item_list(), namespace, index);
+            }
+            ast::Item::Imply(node) => {
+                let ty = TypeRef::lower(node.self_type());
+                let index = self.push(&node, ItemKind::Imply, namespace, parent, |data| {
+                    if let TypeRef::Path { path, .. } = &ty {
+                        data.name = path.last().cloned().unwrap_or_default();
+                    }
+                    data.generics = generics(&node);
+                    data.super_types = super_types(node.super_types());
+                    data.ty = Some(ty.clone());
+                });
+                self.members(node.item_list(), namespace, index);
+            }
+            ast::Item::Function(node) => {
+                let kind = if parent.is_some() { ItemKind::Method } else { ItemKind::Function };
+                self.push(&node, kind, namespace, parent, |data| {
+                    data.generics = generics(&node);
+                    data.params = node
+                        .params()
+                        .into_iter()
+                        .map(|param| ParamData {
+                            name: param.name().map(|name| name.text()).unwrap_or_default(),
+                            ty: param.ty().map(|ty| TypeRef::lower(Some(ty))),
+                        })
+                        .collect();
+                    data.ty = node.ret_type().map(|ty| TypeRef::lower(Some(ty)));
+                    data.has_body = node.body().is_some();
+                });
+            }
+            ast::Item::Field(node) => {
+                self.push(&node, ItemKind::Field, namespace, parent, |data| {
+                    data.ty = node.ty().map(|ty| TypeRef::lower(Some(ty)));
+                });
+            }
+            ast::Item::TypeAlias(node) => {
+                self.push(&node, ItemKind::TypeAlias, namespace, parent, |data| {
+                    data.generics = generics(&node);
+                    data.ty = Some(TypeRef::lower(node.ty()));
+                });
+            }
+        }
+    }
+    fn members(&mut self, list: Option<ast::ItemList>, namespace: &[String], parent: u32) {
+        if let Some(list) = list {
+            self.items(list.items(), namespace, Some(parent));
+        }
+    }
+    fn push<N>(
+        &mut self,
+        node: &N,
+        kind: ItemKind,
+        namespace: &[String],
+        parent: Option<u32>,
+        fill: impl FnOnce(&mut ItemData),
+    ) -> u32
+    where
+        N: HasDocComments + HasModifiers,
+    {
+        let name = name_of(node.syntax());
+        let mut data = ItemData {
+            kind,
+            name: name.as_ref().map(|name| name.text()).unwrap_or_default(),
+            namespace: namespace.to_vec(),
+            namepath: vec![],
+            parent,
+            modifiers: node.modifiers(),
+            docs: node.doc_comment(),
+            signature: signature(node.syntax()),
+            generics: vec![],
+            params: vec![],
+            ty: None,
+            super_types: vec![],
+            has_body: false,
+        };
+        fill(&mut data);
+        data.namepath = match parent {
+            Some(parent) => self.tree.item(parent).namepath.clone(),
+            None => namespace.to_vec(),
+        };
+        data.namepath.push(data.name.clone());
+        let span = node.syntax().trimmed_span();
+        let name_span = match name.and_then(|name| name.token()) {
+            Some(token) => token.span(),
+            None => span.clone(),
+        };
+        self.tree.items.push(data);
+        self.map.items.push(ItemSource { span, name_span });
+        self.tree.items.len() as u32 - 1
+    }
+    fn use_tree(&mut self, tree: ast::UseTree, prefix: &[String]) {
+        let mut path = prefix.to_vec();
+        if let Some(names) = tree.path() {
+            path.extend(names.to_names());
+        }
+        if let Some(list) = tree.use_tree_list() {
+            for tree in list.use_trees() {
+                self.use_tree(tree, &path);
+            }
+            return;
+        }
+        let glob = tree.is_glob();
+        let alias = tree.alias();
+        let name = match (&alias, glob) {
+            (_, true) => String::new(),
+            (Some(alias), _) => alias.text(),
+            (None, _) => path.last().cloned().unwrap_or_default(),
+        };
+        let span = tree.syntax().trimmed_span();
+        let name_span = match alias.and_then(|alias| alias.token()) {
+            Some(token) => token.span(),
+            None => match tree.path().and_then(|path| path.last_segment()) {
+                Some(segment) => segment.syntax().trimmed_span(),
+                None => span.clone(),
+            },
+        };
+        self.tree.imports.push(Import { path, name, glob });
+        self.map.imports.push(ItemSource { span, name_span });
+    }
+}
+
+fn name_of(node: &SyntaxNode) -> Option<ast::Name> {
+    node.children().find_map(ast::Name::cast)
+}
+
+fn generics(node: &impl HasGenericParams) -> Vec<String> {
+    match node.generic_params() {
+        Some(list) => list.params().map(|param| param.name().map(|name| name.text()).unwrap_or_default()).collect(),
+        None => vec![],
+    }
+}
+
+fn super_types(list: Option<ast::SuperTypeList>) -> Vec<TypeRef> {
+    match list {
+        Some(list) => list.types().map(|ty| TypeRef::lower(Some(ty))).collect(),
+        None => vec![],
+    }
+}
+
+/// The declaration tokens before the body, attributes and trailing separators dropped, whitespace runs collapsed.
+fn signature(node: &SyntaxNode) -> String {
+    let mut out = String::new();
+    let mut space = false;
+    collect_signature(node, &mut out, &mut space);
+    out.trim_end_matches([',', ';']).trim().to_string()
+}
+
+fn collect_signature(node: &SyntaxNode, out: &mut String, space: &mut bool) -> bool {
+    for child in node.children_with_tokens() {
+        match child {
+            SyntaxElement::Node(child) => match child.kind() {
+                SyntaxKind::Attribute => {}
+                SyntaxKind::ItemList | SyntaxKind::BlockExpr => return false,
+                _ => {
+                    if !collect_signature(&child, out, space) {
+                        return false;
+                    }
+                }
+            },
+            SyntaxElement::Token(token) => match token.kind() {
+                SyntaxKind::Whitespace | SyntaxKind::LineComment | SyntaxKind::BlockComment | SyntaxKind::DocComment => {
+                    *space = !out.is_empty();
+                }
+                _ => {
+                    if std::mem::take(space) {
+                        out.push(' ');
+                    }
+                    out.push_str(token.text());
+                }
+            },
+        }
+    }
+    true
+}
@@ -0,0 +1,10 @@
+pub(crate) use self::infer::infer_file;
+pub use self::{
+    infer::{InferenceResult, Local, Ty},
+    item_tree::{Import, ItemData, ItemKind, ItemSource, ItemSourceMap, ItemTree, ParamData, TypeRef},
+    resolve::{DefMap, Definition, FileScope, ItemLoc, Resolution, Resolver},
+};
+
+mod infer;
+mod item_tree;
+mod resolve;
@@ -0,0 +1,211 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    sync::Arc,
+};
+
+use super::{ItemKind, ItemTree, TypeRef};
+use crate::{FileId, PackageGraph, PackageId};
+
+/// A declaration anywhere in the workspace, the file and the index in its [ItemTree].
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct ItemLoc {
+    /// The declaring file.
+    pub file: FileId,
+    /// The index in the item tree of the file.
+    pub index: u32,
+}
+
+/// What a name refers to.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum Definition {
+    /// A local binding, the index in the [InferenceResult](super::InferenceResult) of the file.
+    Local(u32),
+    /// A declaration.
+    Item(ItemLoc),
+    /// A namespace, by its full path.
+    Namespace(Vec<String>),
+}
+
+/// The result of resolving a name, a name may have several definitions.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct Resolution {
+    /// All definitions the name refers to.
+    pub definitions: Vec<Definition>,
+    /// The import the name was found through, the index in the item tree of the file.
+    pub import: Option<u32>,
+}
+
+/// The names of all declarations in the workspace and the relations between them.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct DefMap {
+    /// Declarations by fully qualified name.
+    pub items: BTreeMap<Vec<String>, Vec<ItemLoc>>,
+    /// All namespaces, parents included, and the files declaring them.
+    pub namespaces: BTreeMap<Vec<String>, BTreeSet<FileId>>,
+    /// The `imply` blocks of each type.
+    pub implementations: BTreeMap<ItemLoc, Vec<ItemLoc>>,
+    /// The declarations listing each type as a super type, `imply` blocks included.
+    pub subtypes: BTreeMap<ItemLoc, Vec<ItemLoc>>,
+    /// The package of each file in a package.
+    pub packages: BTreeMap<FileId, PackageId>,
+    /// The packages of the workspace.
+    pub graph: Arc<PackageGraph>,
+}
+
+/// The imports of a file resolved against the [DefMap].
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct FileScope {
+    /// The resolved definitions of each import of the item tree, indexed the same way.
+    pub imports: Vec<Vec<Definition>>,
+}
+
+impl DefMap {
+    /// Collect the declarations of all files, each with the package it belongs to.
+    pub fn build(trees: &[(FileId, &ItemTree, Option<PackageId>)], graph: Arc<PackageGraph>) -> DefMap {
+        let mut map = DefMap { graph, ..DefMap::default() };
+        for (file, tree, package) in trees {
+            if let Some(package) = package {
+                map.packages.insert(*file, *package);
+            }
+            map.add_namespace(&tree.namespace, *file);
+            for (index, item) in tree.items.iter().enumerate() {
+                map.add_namespace(&item.namespace, *file);
+                if item.kind != ItemKind::Imply {
+                    let loc = ItemLoc { file: *file, index: index as u32 };
+                    map.items.entry(item.namepath.clone()).or_default().push(loc);
+                }
+            }
+        }
+        let mut implementations = vec![];
+        let mut subtypes = vec![];
+        for (file, tree, _) in trees {
+            let scope = FileScope::build(tree, &map, *file);
+            for (index, item) in tree.items.iter().enumerate() {
+                let loc = ItemLoc { file: *file, index: index as u32 };
+                let resolver = Resolver { map: &map, file: *file, tree, scope: &scope, namespace: &item.namespace };
+                if item.kind == ItemKind::Imply {
+                    implementations.extend(resolver.resolve_type(item.ty.as_ref()).into_iter().map(|target| (target, loc)));
+                }
+                for ty in &item.super_types {
+                    subtypes.extend(resolver.resolve_type(Some(ty)).into_iter().map(|target| (target, loc)));
+                }
+            }
+        }
+        for (target, loc) in implementations {
+            map.implementations.entry(target).or_default().push(loc);
+        }
+        for (target, loc) in subtypes {
+            map.subtypes.entry(target).or_default().push(loc);
+        }
+        map
+    }
+    fn add_namespace(&mut self, namespace: &[String], file: FileId) {
+        for end in 1..=namespace.len() {
+            self.namespaces.entry(namespace[..end].to_vec()).or_default().insert(file);
+        }
+    }
+    /// The package of a file, none for files outside of all packages.
+    pub fn package_of(&self, file: FileId) -> Option<PackageId> {
+        self.packages.get(&file).copied()
+    }
+    /// Check if a file may use the declarations of another file.
+    pub fn can_see(&self, from: FileId, to: FileId) -> bool {
+        self.graph.can_see(self.package_of(from), self.package_of(to))
+    }
+    /// The declarations with the fully qualified name.
+    pub fn get(&self, namepath: &[String]) -> &[ItemLoc] {
+        match self.items.get(namepath) {
+            Some(s) => s,
+            None => &[],
+        }
+    }
+    /// Resolve a fully qualified path to the declarations or the namespace visible from the file.
+    pub fn resolve_absolute(&self, path: &[String], from: FileId) -> Vec<Definition> {
+        let items: Vec<Definition> =
+            self.get(path).iter().filter(|loc| self.can_see(from, loc.file)).map(|loc| Definition::Item(*loc)).collect();
+        if !items.is_empty() {
+            return items;
+        }
+        match self.namespaces.get(path) {
+            Some(files) if files.iter().any(|file| self.can_see(from, *file)) => vec![Definition::Namespace(path.to_vec())],
+            _ => vec![],
+        }
+    }
+}
+
+impl FileScope {
+    /// Resolve the imports of a file.
+    pub fn build(tree: &ItemTree, map: &DefMap, file: FileId) -> FileScope {
+        FileScope { imports: tree.imports.iter().map(|import| map.resolve_absolute(&import.path, file)).collect() }
+    }
+}
+
+/// Resolves names as seen from a namespace in a file.
+#[derive(Copy, Clone, Debug)]
+pub struct Resolver<'a> {
+    /// The workspace declarations.
+    pub map: &'a DefMap,
+    /// The file the name is used in.
+    pub file: FileId,
+    /// The declarations of the file.
+    pub tree: &'a ItemTree,
+    /// The resolved imports of the file.
+    pub scope: &'a FileScope,
+    /// The namespace the name is used in.
+    pub namespace: &'a [String],
+}
+
+impl<'a> Resolver<'a> {
+    /// Resolve a path, the first segment is looked up in the enclosing namespaces, the imports, then from the root.
+    pub fn resolve(&self, path: &[String]) -> Resolution {
+        let (first, rest) = match path.split_first() {
+            Some(s) => s,
+            None => return Resolution::default(),
+        };
+        for end in (1..=self.namespace.len()).rev() {
+            let mut absolute = self.namespace[..end].to_vec();
+            absolute.extend(path.iter().cloned());
+            let definitions = self.map.resolve_absolute(&absolute, self.file);
+            if !definitions.is_empty() {
+                return Resolution { definitions, import: None };
+            }
+        }
+        for (index, import) in self.tree.imports.iter().enumerate() {
+            let definitions = match import.glob {
+                true => {
+                    let mut absolute = import.path.clone();
+                    absolute.extend(path.iter().cloned());
+                    self.map.resolve_absolute(&absolute, self.file)
+                }
+                false if &import.name == first => match rest.is_empty() {
+                    true => self.scope.imports[index].clone(),
+                    false => {
+                        let mut absolute = import.path.clone();
+                        absolute.extend(rest.iter().cloned());
+                        self.map.resolve_absolute(&absolute, self.file)
+                    }
+                },
+                false => continue,
+            };
+            if !definitions.is_empty() {
+                return Resolution { definitions, import: Some(index as u32) };
+            }
+        }
+        Resolution { definitions: self.map.resolve_absolute(path, self.file), import: None }
+    }
+    /// Resolve a written type to the declarations it names.
+    pub fn resolve_type(&self, ty: Option<&TypeRef>) -> Vec<ItemLoc> {
+        match ty {
+            Some(TypeRef::Path { path, .. }) => self
+                .resolve(path)
+                .definitions
+                .into_iter()
+                .filter_map(|definition| match definition {
+                    Definition::Item(loc) => Some(loc),
+                    _ => None,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+}
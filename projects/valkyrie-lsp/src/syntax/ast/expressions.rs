@@ -0,0 +1,585 @@
+use super::{AstNode, Item, Name, NameRef, ast_enum, ast_node, child, children};
+use crate::{SyntaxKind, SyntaxToken};
+
+ast_node! {
+    /// `a::b::c`
+    Path,
+    /// One segment of a path with its generic arguments.
+    PathSegment,
+    /// `<A, B>`
+    GenericArgList,
+    /// `a::B<C>` as a type.
+    PathType,
+    /// `(A, B)`
+    TupleType,
+    /// `(A, B) -> C`
+    FunctionType,
+    /// `let pattern: Type = value;`
+    LetStmt,
+    /// `expression;`
+    ExprStmt,
+    /// `1`, `"text"`, `true` or `null`
+    Literal,
+    /// `a::b`
+    PathExpr,
+    /// `(a)`
+    ParenExpr,
+    /// `(a, b)`
+    TupleExpr,
+    /// `[a, b]`
+    ArrayExpr,
+    /// `{ statements }`
+    BlockExpr,
+    /// `-a`, `!a`, `not a`
+    PrefixExpr,
+    /// `a + b`
+    BinaryExpr,
+    /// `a as T` or `a is T`
+    CastExpr,
+    /// `f(a, b)`
+    CallExpr,
+    /// `(a, b)` of a call.
+    ArgList,
+    /// `a[b]`
+    IndexExpr,
+    /// `a.b` or `a?.b`
+    FieldExpr,
+    /// `a?`
+    TryExpr,
+    /// `new T(a)`
+    NewExpr,
+    /// `if a { } else { }`
+    IfExpr,
+    /// `while a { }`
+    WhileExpr,
+    /// `loop { }`
+    LoopExpr,
+    /// `for a in b { }`
+    ForExpr,
+    /// `match a { case ... }`
+    MatchExpr,
+    /// `case pattern if guard => value`
+    MatchArm,
+    /// `return a`
+    ReturnExpr,
+    /// `break a`
+    BreakExpr,
+    /// `continue`
+    ContinueExpr,
+    /// `raise a`
+    RaiseExpr,
+    /// `yield a`
+    YieldExpr,
+    /// `try { } catch e { }`
+    TryCatchExpr,
+    /// `catch e { }`
+    CatchClause,
+    /// `a` or `mut a`
+    IdentPat,
+    /// `(a, b)`
+    TuplePat,
+    /// `1` as a pattern.
+    LiteralPat,
+    /// `Some(a)`
+    VariantPat,
+}
+
+ast_enum! {
+    /// Any type reference.
+    Type {
+        /// `a::B<C>`
+        Path(PathType),
+        /// `(A, B)`
+        Tuple(TupleType),
+        /// `(A) -> B`
+        Function(FunctionType),
+    }
+}
+
+ast_enum! {
+    /// Any statement in a block or at the top level.
+    Stmt {
+        /// `let`
+        Let(LetStmt),
+        /// An expression.
+        Expr(ExprStmt),
+        /// A local declaration.
+        Item(Item),
+    }
+}
+
+ast_enum! {
+    /// Any expression.
+    Expr {
+        /// `1`
+        Literal(Literal),
+        /// `a::b`
+        Path(PathExpr),
+        /// `(a)`
+        Paren(ParenExpr),
+        /// `(a, b)`
+        Tuple(TupleExpr),
+        /// `[a, b]`
+        Array(ArrayExpr),
+        /// `{ }`
+        Block(BlockExpr),
+        /// `-a`
+        Prefix(PrefixExpr),
+        /// `a + b`
+        Binary(BinaryExpr),
+        /// `a as T`
+        Cast(CastExpr),
+        /// `f(a)`
+        Call(CallExpr),
+        /// `a[b]`
+        Index(IndexExpr),
+        /// `a.b`
+        Field(FieldExpr),
+        /// `a?`
+        Try(TryExpr),
+        /// `new T()`
+        New(NewExpr),
+        /// `if`
+        If(IfExpr),
+        /// `while`
+        While(WhileExpr),
+        /// `loop`
+        Loop(LoopExpr),
+        /// `for`
+        For(ForExpr),
+        /// `match`
+        Match(MatchExpr),
+        /// `return`
+        Return(ReturnExpr),
+        /// `break`
+        Break(BreakExpr),
+        /// `continue`
+        Continue(ContinueExpr),
+        /// `raise`
+        Raise(RaiseExpr),
+        /// `yield`
+        Yield(YieldExpr),
+        /// `try catch`
+        TryCatch(TryCatchExpr),
+    }
+}
+
+ast_enum! {
+    /// Any binding pattern.
+    Pat {
+        /// `a`
+        Ident(IdentPat),
+        /// `(a, b)`
+        Tuple(TuplePat),
+        /// `1`
+        Literal(LiteralPat),
+        /// `Some(a)`
+        Variant(VariantPat),
+    }
+}
+
+/// The nth expression child.
+fn nth_expr(node: &impl AstNode, n: usize) -> Option<Expr> {
+    children(node.syntax()).nth(n)
+}
+
+/// The first operator token or keyword directly in the node.
+fn operator(node: &impl AstNode) -> Option<SyntaxToken> {
+    node.syntax().child_tokens().find(|t| !t.kind().is_trivia())
+}
+
+impl Path {
+    /// The segments in order.
+    pub fn segments(&self) -> impl Iterator<Item = PathSegment> + '_ {
+        children(&self.syntax)
+    }
+    /// The last segment, the name the path refers to.
+    pub fn last_segment(&self) -> Option<PathSegment> {
+        self.segments().last()
+    }
+    /// The segment names, generic arguments dropped.
+    pub fn to_names(&self) -> Vec<String> {
+        self.segments().map(|segment| segment.name_ref().map(|name| name.text()).unwrap_or_default()).collect()
+    }
+}
+
+impl PathSegment {
+    /// The segment name.
+    pub fn name_ref(&self) -> Option<NameRef> {
+        child(&self.syntax)
+    }
+    /// The generic arguments of a type path.
+    pub fn generic_args(&self) -> Option<GenericArgList> {
+        child(&self.syntax)
+    }
+}
+
+impl GenericArgList {
+    /// The type arguments.
+    pub fn types(&self) -> impl Iterator<Item = Type> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl PathType {
+    /// The type path.
+    pub fn path(&self) -> Option<Path> {
+        child(&self.syntax)
+    }
+}
+
+impl TupleType {
+    /// The element types.
+    pub fn types(&self) -> impl Iterator<Item = Type> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl FunctionType {
+    /// The parameter types.
+    pub fn params(&self) -> Option<TupleType> {
+        child(&self.syntax)
+    }
+    /// The return type.
+    pub fn ret_type(&self) -> Option<Type> {
+        children(&self.syntax).nth(1)
+    }
+}
+
+impl LetStmt {
+    /// The binding pattern.
+    pub fn pattern(&self) -> Option<Pat> {
+        child(&self.syntax)
+    }
+    /// The declared type.
+    pub fn ty(&self) -> Option<Type> {
+        child(&self.syntax)
+    }
+    /// The value after `=`.
+    pub fn initializer(&self) -> Option<Expr> {
+        child(&self.syntax)
+    }
+}
+
+impl ExprStmt {
+    /// The expression.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.syntax)
+    }
+    /// Check if the statement ends with `;`.
+    pub fn has_semicolon(&self) -> bool {
+        self.syntax.child_token(SyntaxKind::Semicolon).is_some()
+    }
+}
+
+impl Literal {
+    /// The literal token.
+    pub fn token(&self) -> Option<SyntaxToken> {
+        operator(self)
+    }
+}
+
+impl PathExpr {
+    /// The path.
+    pub fn path(&self) -> Option<Path> {
+        child(&self.syntax)
+    }
+}
+
+impl ParenExpr {
+    /// The inner expression.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.syntax)
+    }
+}
+
+impl TupleExpr {
+    /// The elements.
+    pub fn exprs(&self) -> impl Iterator<Item = Expr> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl ArrayExpr {
+    /// The elements.
+    pub fn exprs(&self) -> impl Iterator<Item = Expr> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl BlockExpr {
+    /// The statements, including the tail expression.
+    pub fn statements(&self) -> impl Iterator<Item = Stmt> + '_ {
+        children(&self.syntax)
+    }
+    /// The last expression statement if it has no `;`, the value of the block.
+    pub fn tail_expr(&self) -> Option<Expr> {
+        match self.statements().last()? {
+            Stmt::Expr(stmt) if !stmt.has_semicolon() => stmt.expr(),
+            _ => None,
+        }
+    }
+}
+
+impl PrefixExpr {
+    /// The operator token.
+    pub fn op_token(&self) -> Option<SyntaxToken> {
+        operator(self)
+    }
+    /// The operand.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.syntax)
+    }
+}
+
+impl BinaryExpr {
+    /// The left operand.
+    pub fn lhs(&self) -> Option<Expr> {
+        nth_expr(self, 0)
+    }
+    /// The right operand.
+    pub fn rhs(&self) -> Option<Expr> {
+        nth_expr(self, 1)
+    }
+    /// The operator text, `>>` and `>>=` are glued from several tokens.
+    pub fn op_text(&self) -> String {
+        self.syntax.child_tokens().filter(|t| !t.kind().is_trivia()).map(|t| t.text().to_string()).collect()
+    }
+    /// The first operator token.
+    pub fn op_token(&self) -> Option<SyntaxToken> {
+        operator(self)
+    }
+}
+
+impl CastExpr {
+    /// The value.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.syntax)
+    }
+    /// The target type.
+    pub fn ty(&self) -> Option<Type> {
+        child(&self.syntax)
+    }
+    /// Check if this is a type test with `is`.
+    pub fn is_test(&self) -> bool {
+        self.syntax.child_token(SyntaxKind::IsKw).is_some()
+    }
+}
+
+impl CallExpr {
+    /// The called expression.
+    pub fn callee(&self) -> Option<Expr> {
+        child(&self.syntax)
+    }
+    /// The argument list.
+    pub fn arg_list(&self) -> Option<ArgList> {
+        child(&self.syntax)
+    }
+}
+
+impl ArgList {
+    /// The arguments.
+    pub fn args(&self) -> impl Iterator<Item = Expr> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl IndexExpr {
+    /// The indexed value.
+    pub fn base(&self) -> Option<Expr> {
+        nth_expr(self, 0)
+    }
+    /// The index.
+    pub fn index(&self) -> Option<Expr> {
+        nth_expr(self, 1)
+    }
+}
+
+impl FieldExpr {
+    /// The value whose member is accessed.
+    pub fn receiver(&self) -> Option<Expr> {
+        child(&self.syntax)
+    }
+    /// The member name.
+    pub fn name_ref(&self) -> Option<NameRef> {
+        child(&self.syntax)
+    }
+    /// Check if the access is null safe, `?.`.
+    pub fn is_optional(&self) -> bool {
+        self.syntax.child_token(SyntaxKind::QuestionDot).is_some()
+    }
+}
+
+impl TryExpr {
+    /// The value.
+    pub fn expr(&self) -> Option<Expr> {
+        child(&self.syntax)
+    }
+}
+
+impl NewExpr {
+    /// The constructed type.
+    pub fn ty(&self) -> Option<Type> {
+        child(&self.syntax)
+    }
+    /// The constructor arguments.
+    pub fn arg_list(&self) -> Option<ArgList> {
+        child(&self.syntax)
+    }
+}
+
+impl IfExpr {
+    /// The condition.
+    pub fn condition(&self) -> Option<Expr> {
+        nth_expr(self, 0)
+    }
+    /// The block run when the condition holds.
+    pub fn then_branch(&self) -> Option<BlockExpr> {
+        child(&self.syntax)
+    }
+    /// The `else` block or `else if` expression.
+    pub fn else_branch(&self) -> Option<Expr> {
+        nth_expr(self, 2)
+    }
+}
+
+impl WhileExpr {
+    /// The condition.
+    pub fn condition(&self) -> Option<Expr> {
+        nth_expr(self, 0)
+    }
+    /// The loop body.
+    pub fn body(&self) -> Option<BlockExpr> {
+        children(&self.syntax).last()
+    }
+}
+
+impl LoopExpr {
+    /// The loop body.
+    pub fn body(&self) -> Option<BlockExpr> {
+        child(&self.syntax)
+    }
+}
+
+impl ForExpr {
+    /// The binding pattern.
+    pub fn pattern(&self) -> Option<Pat> {
+        child(&self.syntax)
+    }
+    /// The iterated value.
+    pub fn iterable(&self) -> Option<Expr> {
+        nth_expr(self, 0)
+    }
+    /// The loop body.
+    pub fn body(&self) -> Option<BlockExpr> {
+        children(&self.syntax).last()
+    }
+}
+
+impl MatchExpr {
+    /// The matched value.
+    pub fn scrutinee(&self) -> Option<Expr> {
+        child(&self.syntax)
+    }
+    /// The arms in order.
+    pub fn arms(&self) -> impl Iterator<Item = MatchArm> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl MatchArm {
+    /// The pattern, none for the `else` arm.
+    pub fn pattern(&self) -> Option<Pat> {
+        child(&self.syntax)
+    }
+    /// The guard after `if`.
+    pub fn guard(&self) -> Option<Expr> {
+        match self.syntax.child_token(SyntaxKind::IfKw) {
+            Some(_) => nth_expr(self, 0),
+            None => None,
+        }
+    }
+    /// The value of the arm.
+    pub fn value(&self) -> Option<Expr> {
+        match self.syntax.child_token(SyntaxKind::IfKw) {
+            Some(_) => nth_expr(self, 1),
+            None => nth_expr(self, 0),
+        }
+    }
+}
+
+macro_rules! jump_value {
+    ($($name:ident),*) => {
+        $(
+            impl $name {
+                /// The value, if any.
+                pub fn value(&self) -> Option<Expr> {
+                    child(&self.syntax)
+                }
+            }
+        )*
+    };
+}
+
+jump_value!(ReturnExpr, BreakExpr, RaiseExpr, YieldExpr);
+
+impl TryCatchExpr {
+    /// The guarded block.
+    pub fn body(&self) -> Option<BlockExpr> {
+        child(&self.syntax)
+    }
+    /// The catch clauses.
+    pub fn catches(&self) -> impl Iterator<Item = CatchClause> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl CatchClause {
+    /// The binding pattern of the error.
+    pub fn pattern(&self) -> Option<Pat> {
+        child(&self.syntax)
+    }
+    /// The handler.
+    pub fn body(&self) -> Option<BlockExpr> {
+        child(&self.syntax)
+    }
+}
+
+impl IdentPat {
+    /// The bound name.
+    pub fn name(&self) -> Option<Name> {
+        child(&self.syntax)
+    }
+    /// Check if the binding is mutable.
+    pub fn is_mut(&self) -> bool {
+        self.syntax.child_token(SyntaxKind::MutKw).is_some()
+    }
+}
+
+impl TuplePat {
+    /// The element patterns.
+    pub fn pats(&self) -> impl Iterator<Item = Pat> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl LiteralPat {
+    /// The literal.
+    pub fn literal(&self) -> Option<Literal> {
+        child(&self.syntax)
+    }
+}
+
+impl VariantPat {
+    /// The variant path.
+    pub fn path(&self) -> Option<Path> {
+        child(&self.syntax)
+    }
+    /// The payload patterns.
+    pub fn pats(&self) -> Vec<Pat> {
+        match child::<TuplePat>(&self.syntax) {
+            Some(tuple) => tuple.pats().collect(),
+            None => vec![],
+        }
+    }
+}
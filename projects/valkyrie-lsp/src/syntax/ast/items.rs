@@ -0,0 +1,345 @@
+use super::{
+    AstNode, BlockExpr, Expr, HasAttributes, HasDocComments, HasGenericParams, HasModifiers, HasName, Name, Pat, Type,
+    ast_enum, ast_node, child, children,
+};
+use crate::{SyntaxKind, SyntaxToken};
+
+ast_node! {
+    /// `namespace a.b;` or `namespace a.b { items }`
+    NamespaceDecl,
+    /// `using a::b::{c, d as e};`
+    UsingDecl,
+    /// One branch of a using declaration.
+    UseTree,
+    /// `{a, b::c}`
+    UseTreeList,
+    /// `as name`
+    UseAlias,
+    /// `class A<T>: Base { members }` or `structure A { members }`
+    ClassDecl,
+    /// `trait A: Base { members }`
+    TraitDecl,
+    /// `imply A: Trait { members }` or `extends A { members }`
+    ImplyDecl,
+    /// `union A { Variant(T), ... }`
+    UnionDecl,
+    /// `Variant(T)` in a union.
+    VariantDecl,
+    /// `micro name<T>(params) -> Return { body }`, methods may omit `micro`.
+    FunctionDecl,
+    /// `name: Type = default` in a class.
+    FieldDecl,
+    /// `type Name<T> = Type;`
+    TypeAliasDecl,
+    /// `{ items }`
+    ItemList,
+    /// `: A, B` or `: A + B`
+    SuperTypeList,
+    /// `(params)`
+    ParamList,
+    /// `pattern: Type = default`, `self` or `...rest`
+    Param,
+    /// `<T, U: Bound>`
+    GenericParamList,
+    /// `T: Bound`
+    GenericParam,
+}
+
+ast_enum! {
+    /// Any declaration.
+    Item {
+        /// `namespace`
+        Namespace(NamespaceDecl),
+        /// `using`
+        Using(UsingDecl),
+        /// `class` or `structure`
+        Class(ClassDecl),
+        /// `trait`
+        Trait(TraitDecl),
+        /// `imply` or `extends`
+        Imply(ImplyDecl),
+        /// `union`
+        Union(UnionDecl),
+        /// `micro`
+        Function(FunctionDecl),
+        /// A field in a class.
+        Field(FieldDecl),
+        /// `type`
+        TypeAlias(TypeAliasDecl),
+    }
+}
+
+macro_rules! impl_traits {
+    ($trait:ident for $($name:ident),*) => {
+        $(impl $trait for $name {})*
+    };
+}
+
+impl_traits!(HasName for ClassDecl, TraitDecl, UnionDecl, VariantDecl, FunctionDecl, FieldDecl, TypeAliasDecl, GenericParam);
+impl_traits!(HasModifiers for ClassDecl, TraitDecl, ImplyDecl, UnionDecl, VariantDecl, FunctionDecl, FieldDecl, TypeAliasDecl);
+impl_traits!(HasAttributes for ClassDecl, TraitDecl, ImplyDecl, UnionDecl, FunctionDecl, FieldDecl, TypeAliasDecl);
+impl_traits!(HasDocComments for NamespaceDecl, ClassDecl, TraitDecl, ImplyDecl, UnionDecl, VariantDecl, FunctionDecl, FieldDecl, TypeAliasDecl);
+impl_traits!(HasGenericParams for ClassDecl, TraitDecl, ImplyDecl, UnionDecl, FunctionDecl, TypeAliasDecl);
+
+impl Item {
+    /// The declared name, namespaces, usings and implementations have none.
+    pub fn name(&self) -> Option<Name> {
+        match self {
+            Item::Class(node) => node.name(),
+            Item::Trait(node) => node.name(),
+            Item::Union(node) => node.name(),
+            Item::Function(node) => node.name(),
+            Item::Field(node) => node.name(),
+            Item::TypeAlias(node) => node.name(),
+            Item::Namespace(_) | Item::Using(_) | Item::Imply(_) => None,
+        }
+    }
+}
+
+impl NamespaceDecl {
+    /// The namespace path.
+    pub fn path(&self) -> Option<super::Path> {
+        child(&self.syntax)
+    }
+    /// The braced body, none for a file level namespace.
+    pub fn item_list(&self) -> Option<ItemList> {
+        child(&self.syntax)
+    }
+}
+
+impl UsingDecl {
+    /// The imported tree.
+    pub fn use_tree(&self) -> Option<UseTree> {
+        child(&self.syntax)
+    }
+}
+
+impl UseTree {
+    /// The path before the alias, `*` or `{`.
+    pub fn path(&self) -> Option<super::Path> {
+        child(&self.syntax)
+    }
+    /// The name introduced by `as`.
+    pub fn alias(&self) -> Option<Name> {
+        child::<UseAlias>(&self.syntax).and_then(|alias| child(alias.syntax()))
+    }
+    /// Check if the tree imports everything with `*`.
+    pub fn is_glob(&self) -> bool {
+        self.syntax.child_token(SyntaxKind::Star).is_some()
+    }
+    /// The nested trees in `{a, b}`.
+    pub fn use_tree_list(&self) -> Option<UseTreeList> {
+        child(&self.syntax)
+    }
+}
+
+impl UseTreeList {
+    /// The nested trees.
+    pub fn use_trees(&self) -> impl Iterator<Item = UseTree> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl ClassDecl {
+    /// Check if declared with `structure`.
+    pub fn is_structure(&self) -> bool {
+        self.syntax.child_token(SyntaxKind::StructureKw).is_some()
+    }
+    /// The inherited types.
+    pub fn super_types(&self) -> Option<SuperTypeList> {
+        child(&self.syntax)
+    }
+    /// The braced body.
+    pub fn item_list(&self) -> Option<ItemList> {
+        child(&self.syntax)
+    }
+    /// The fields declared in the body.
+    pub fn fields(&self) -> Vec<FieldDecl> {
+        members(self.item_list())
+    }
+    /// The methods declared in the body.
+    pub fn methods(&self) -> Vec<FunctionDecl> {
+        members(self.item_list())
+    }
+}
+
+impl TraitDecl {
+    /// The inherited traits.
+    pub fn super_types(&self) -> Option<SuperTypeList> {
+        child(&self.syntax)
+    }
+    /// The braced body.
+    pub fn item_list(&self) -> Option<ItemList> {
+        child(&self.syntax)
+    }
+    /// The methods declared in the body, abstract or not.
+    pub fn methods(&self) -> Vec<FunctionDecl> {
+        members(self.item_list())
+    }
+}
+
+impl ImplyDecl {
+    /// Check if declared with `extends`.
+    pub fn is_extends(&self) -> bool {
+        self.syntax.child_token(SyntaxKind::ExtendsKw).is_some()
+    }
+    /// The type being implemented.
+    pub fn self_type(&self) -> Option<Type> {
+        child(&self.syntax)
+    }
+    /// The implemented traits.
+    pub fn super_types(&self) -> Option<SuperTypeList> {
+        child(&self.syntax)
+    }
+    /// The braced body.
+    pub fn item_list(&self) -> Option<ItemList> {
+        child(&self.syntax)
+    }
+    /// The methods declared in the body.
+    pub fn methods(&self) -> Vec<FunctionDecl> {
+        members(self.item_list())
+    }
+}
+
+impl UnionDecl {
+    /// The braced body.
+    pub fn item_list(&self) -> Option<ItemList> {
+        child(&self.syntax)
+    }
+    /// The variants in declaration order.
+    pub fn variants(&self) -> Vec<VariantDecl> {
+        members(self.item_list())
+    }
+    /// The methods declared in the body.
+    pub fn methods(&self) -> Vec<FunctionDecl> {
+        members(self.item_list())
+    }
+}
+
+impl VariantDecl {
+    /// The payload, a tuple type.
+    pub fn payload(&self) -> Option<Type> {
+        child(&self.syntax)
+    }
+}
+
+impl FunctionDecl {
+    /// The parameter list.
+    pub fn param_list(&self) -> Option<ParamList> {
+        child(&self.syntax)
+    }
+    /// The parameters.
+    pub fn params(&self) -> Vec<Param> {
+        match self.param_list() {
+            Some(list) => list.params().collect(),
+            None => vec![],
+        }
+    }
+    /// The declared return type.
+    pub fn ret_type(&self) -> Option<Type> {
+        self.syntax.children().find(|node| node.kind() == SyntaxKind::ReturnType).and_then(|node| child(&node))
+    }
+    /// The body, none for an abstract method.
+    pub fn body(&self) -> Option<BlockExpr> {
+        child(&self.syntax)
+    }
+}
+
+impl FieldDecl {
+    /// The declared type.
+    pub fn ty(&self) -> Option<Type> {
+        child(&self.syntax)
+    }
+    /// The default value.
+    pub fn default_value(&self) -> Option<Expr> {
+        child(&self.syntax)
+    }
+}
+
+impl TypeAliasDecl {
+    /// The aliased type.
+    pub fn ty(&self) -> Option<Type> {
+        child(&self.syntax)
+    }
+}
+
+impl ItemList {
+    /// The declarations in the body.
+    pub fn items(&self) -> impl Iterator<Item = Item> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl SuperTypeList {
+    /// The listed types.
+    pub fn types(&self) -> impl Iterator<Item = Type> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl ParamList {
+    /// The parameters.
+    pub fn params(&self) -> impl Iterator<Item = Param> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl Param {
+    /// Check if this is the `self` parameter.
+    pub fn is_self(&self) -> bool {
+        child::<Name>(&self.syntax).and_then(|name| name.token()).is_some_and(|token| token.kind() == SyntaxKind::SelfKw)
+    }
+    /// Check if this is a `...rest` parameter.
+    pub fn is_variadic(&self) -> bool {
+        self.syntax.child_token(SyntaxKind::DotDotDot).is_some()
+    }
+    /// The binding pattern, none for `self`.
+    pub fn pattern(&self) -> Option<Pat> {
+        child(&self.syntax)
+    }
+    /// The bound name, for `self` and simple bindings.
+    pub fn name(&self) -> Option<Name> {
+        match self.pattern() {
+            Some(Pat::Ident(pat)) => pat.name(),
+            Some(_) => None,
+            None => child(&self.syntax),
+        }
+    }
+    /// The declared type.
+    pub fn ty(&self) -> Option<Type> {
+        child(&self.syntax)
+    }
+    /// The default value.
+    pub fn default_value(&self) -> Option<Expr> {
+        child(&self.syntax)
+    }
+}
+
+impl GenericParamList {
+    /// The parameters.
+    pub fn params(&self) -> impl Iterator<Item = GenericParam> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl GenericParam {
+    /// The bounds after `:`.
+    pub fn bounds(&self) -> impl Iterator<Item = Type> + '_ {
+        children(&self.syntax)
+    }
+}
+
+impl UseAlias {
+    /// The `as` keyword.
+    pub fn as_token(&self) -> Option<SyntaxToken> {
+        self.syntax.child_token(SyntaxKind::AsKw)
+    }
+}
+
+/// The members of a kind in a declaration body.
+fn members<N: AstNode>(list: Option<ItemList>) -> Vec<N> {
+    match list {
+        Some(list) => list.syntax().children().filter_map(N::cast).collect(),
+        None => vec![],
+    }
+}
@@ -0,0 +1,236 @@
+//! Typed views over the untyped [SyntaxNode]s, accessors return none for parts missing in broken code.
+
+pub use self::{expressions::*, items::*};
+use super::{SyntaxNode, SyntaxToken};
+use crate::SyntaxKind;
+
+mod expressions;
+mod items;
+
+/// A typed view of a syntax node.
+pub trait AstNode: Sized {
+    /// Check if a node of the kind can be viewed as this type.
+    fn can_cast(kind: SyntaxKind) -> bool;
+    /// View the node as this type.
+    fn cast(syntax: SyntaxNode) -> Option<Self>;
+    /// The underlying node.
+    fn syntax(&self) -> &SyntaxNode;
+}
+
+macro_rules! ast_node {
+    ($($(#[$meta:meta])* $name:ident),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+            pub struct $name {
+                syntax: $crate::SyntaxNode,
+            }
+
+            impl $crate::ast::AstNode for $name {
+                fn can_cast(kind: $crate::SyntaxKind) -> bool {
+                    kind == $crate::SyntaxKind::$name
+                }
+                fn cast(syntax: $crate::SyntaxNode) -> Option<Self> {
+                    match Self::can_cast(syntax.kind()) {
+                        true => Some(Self { syntax }),
+                        false => None,
+                    }
+                }
+                fn syntax(&self) -> &$crate::SyntaxNode {
+                    &self.syntax
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! ast_enum {
+    ($(#[$meta:meta])* $name:ident { $($(#[$variant_meta:meta])* $variant:ident($node:ident)),* $(,)? }) => {
+        $(#[$meta])*
+        #[derive(Clone, Debug, Eq, PartialEq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant($node),)*
+        }
+
+        impl $crate::ast::AstNode for $name {
+            fn can_cast(kind: $crate::SyntaxKind) -> bool {
+                $(<$node as $crate::ast::AstNode>::can_cast(kind))||*
+            }
+            fn cast(syntax: $crate::SyntaxNode) -> Option<Self> {
+                $(
+                    if <$node as $crate::ast::AstNode>::can_cast(syntax.kind()) {
+                        return <$node as $crate::ast::AstNode>::cast(syntax).map($name::$variant);
+                    }
+                )*
+                None
+            }
+            fn syntax(&self) -> &$crate::SyntaxNode {
+                match self {
+                    $($name::$variant(node) => $crate::ast::AstNode::syntax(node),)*
+                }
+            }
+        }
+    };
+}
+
+pub(crate) use ast_enum;
+pub(crate) use ast_node;
+
+/// The first child that can be viewed as the type.
+pub(crate) fn child<N: AstNode>(parent: &SyntaxNode) -> Option<N> {
+    parent.children().find_map(N::cast)
+}
+
+/// All children that can be viewed as the type.
+pub(crate) fn children<N: AstNode + 'static>(parent: &SyntaxNode) -> impl Iterator<Item = N> + '_ {
+    parent.children().filter_map(N::cast)
+}
+
+/// A declaration that introduces a name.
+pub trait HasName: AstNode {
+    /// The declared name.
+    fn name(&self) -> Option<Name> {
+        child(self.syntax())
+    }
+}
+
+/// A declaration with modifiers.
+pub trait HasModifiers: AstNode {
+    /// The modifier keywords in source order.
+    fn modifiers(&self) -> Vec<SyntaxKind> {
+        match child::<ModifierList>(self.syntax()) {
+            Some(list) => list.syntax().child_tokens().map(|t| t.kind()).filter(|k| k.is_modifier()).collect(),
+            None => vec![],
+        }
+    }
+    /// Check if the modifier is present.
+    fn has_modifier(&self, modifier: SyntaxKind) -> bool {
+        self.modifiers().contains(&modifier)
+    }
+}
+
+/// A declaration with attributes.
+pub trait HasAttributes: AstNode {
+    /// The attributes in source order.
+    fn attributes(&self) -> Vec<Attribute> {
+        children(self.syntax()).collect()
+    }
+}
+
+/// A declaration with generic parameters.
+pub trait HasGenericParams: AstNode {
+    /// The generic parameter list.
+    fn generic_params(&self) -> Option<GenericParamList> {
+        child(self.syntax())
+    }
+}
+
+/// A declaration that can be documented by the doc comments right before it.
+pub trait HasDocComments: AstNode {
+    /// The doc comment text with comment markers removed, lines joined by `\n`.
+    ///
+    /// A blank line between the comment and the declaration detaches the comment.
+    fn doc_comment(&self) -> Option<String> {
+        let mut lines: Vec<String> = vec![];
+        let mut element = self.syntax().prev_sibling_or_token();
+        while let Some(current) = element {
+            let token = match current.into_token() {
+                Some(s) => s,
+                None => break,
+            };
+            match token.kind() {
+                SyntaxKind::Whitespace if token.text().matches('\n').count() > 1 => break,
+                SyntaxKind::Whitespace => {}
+                SyntaxKind::DocComment => lines.extend(doc_comment_lines(token.text()).into_iter().rev()),
+                _ => break,
+            }
+            element = token.prev_sibling_or_token();
+        }
+        if lines.is_empty() {
+            return None;
+        }
+        lines.reverse();
+        Some(lines.join("\n"))
+    }
+}
+
+/// Strip the comment markers of a doc comment.
+pub fn doc_comment_lines(text: &str) -> Vec<String> {
+    if let Some(line) = text.strip_prefix("///") {
+        return vec![line.strip_prefix(' ').unwrap_or(line).to_string()];
+    }
+    let body = text.trim_start_matches("/**").trim_end_matches("*/");
+    let lines: Vec<String> = body
+        .lines()
+        .map(|line| {
+            let line = line.trim_start();
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line).trim_end().to_string()
+        })
+        .collect();
+    let start = lines.iter().position(|line| !line.is_empty()).unwrap_or(lines.len());
+    let end = lines.iter().rposition(|line| !line.is_empty()).map(|i| i + 1).unwrap_or(start);
+    lines[start..end].to_vec()
+}
+
+ast_node! {
+    /// The root of a file.
+    SourceFile,
+    /// The name a declaration introduces.
+    Name,
+    /// A name referring to a declaration.
+    NameRef,
+    /// `#[name(...)]` or `@name(...)`
+    Attribute,
+    /// `public static`
+    ModifierList,
+}
+
+impl SourceFile {
+    /// The declarations at the top level, the ones inside namespace blocks are not included.
+    pub fn items(&self) -> impl Iterator<Item = Item> + '_ {
+        children(&self.syntax)
+    }
+    /// The top level statements.
+    pub fn statements(&self) -> impl Iterator<Item = Stmt> + '_ {
+        children(&self.syntax)
+    }
+    /// The namespace declared for the whole file, `namespace a::b;` without a block.
+    pub fn namespace(&self) -> Option<NamespaceDecl> {
+        children::<NamespaceDecl>(&self.syntax).find(|namespace| namespace.item_list().is_none())
+    }
+}
+
+impl Name {
+    /// The identifier token.
+    pub fn token(&self) -> Option<SyntaxToken> {
+        self.syntax.child_tokens().find(|t| matches!(t.kind(), SyntaxKind::Ident | SyntaxKind::SelfKw))
+    }
+    /// The name text, quotes of a quoted identifier removed.
+    pub fn text(&self) -> String {
+        self.token().map(|t| unquote(t.text()).to_string()).unwrap_or_default()
+    }
+}
+
+impl NameRef {
+    /// The identifier, `self`, `Self` or tuple index token.
+    pub fn token(&self) -> Option<SyntaxToken> {
+        self.syntax.child_tokens().find(|t| !t.kind().is_trivia())
+    }
+    /// The name text, quotes of a quoted identifier removed.
+    pub fn text(&self) -> String {
+        self.token().map(|t| unquote(t.text()).to_string()).unwrap_or_default()
+    }
+}
+
+impl Attribute {
+    /// The attribute name path.
+    pub fn path(&self) -> Option<Path> {
+        child(&self.syntax)
+    }
+}
+
+/// Remove the backticks of a quoted identifier.
+pub fn unquote(text: &str) -> &str {
+    text.strip_prefix('`').map(|s| s.strip_suffix('`').unwrap_or(s)).unwrap_or(text)
+}
@@ -0,0 +1,169 @@
+use std::{
+    fmt::{Debug, Formatter},
+    sync::Arc,
+};
+
+use crate::SyntaxKind;
+
+/// An immutable, position independent node of the syntax tree, cheap to clone and share between trees.
+#[derive(Clone, Eq, PartialEq)]
+pub struct GreenNode(Arc<GreenNodeData>);
+
+#[derive(Eq, PartialEq)]
+struct GreenNodeData {
+    kind: SyntaxKind,
+    text_length: usize,
+    children: Vec<GreenElement>,
+}
+
+/// An immutable token of the syntax tree, owns its text.
+#[derive(Clone, Eq, PartialEq)]
+pub struct GreenToken(Arc<GreenTokenData>);
+
+#[derive(Eq, PartialEq)]
+struct GreenTokenData {
+    kind: SyntaxKind,
+    text: Box<str>,
+}
+
+/// A child of a [GreenNode].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum GreenElement {
+    /// A nested node.
+    Node(GreenNode),
+    /// A leaf token.
+    Token(GreenToken),
+}
+
+/// Builds a [GreenNode] from a flat sequence of start, token and finish calls.
+#[derive(Debug, Default)]
+pub struct GreenBuilder {
+    parents: Vec<(SyntaxKind, usize)>,
+    children: Vec<GreenElement>,
+}
+
+/// A position in the [GreenBuilder] a node can be retroactively started at.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub struct Checkpoint(usize);
+
+impl GreenNode {
+    /// Create a node from its children.
+    pub fn new(kind: SyntaxKind, children: Vec<GreenElement>) -> Self {
+        let text_length = children.iter().map(|child| child.text_length()).sum();
+        GreenNode(Arc::new(GreenNodeData { kind, text_length, children }))
+    }
+    /// The kind of the node.
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.kind
+    }
+    /// The byte length of all text covered by the node.
+    pub fn text_length(&self) -> usize {
+        self.0.text_length
+    }
+    /// The children of the node.
+    pub fn children(&self) -> &[GreenElement] {
+        &self.0.children
+    }
+    /// Create a copy of the node with the child at the index replaced.
+    pub fn replace_child(&self, index: usize, child: GreenElement) -> GreenNode {
+        let mut children = self.0.children.clone();
+        children[index] = child;
+        GreenNode::new(self.kind(), children)
+    }
+    /// Check if two nodes are the same allocation, shared subtrees compare equal without walking them.
+    pub fn ptr_eq(&self, other: &GreenNode) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+    /// Write the covered text into the buffer.
+    pub fn write_text(&self, buffer: &mut String) {
+        for child in self.children() {
+            match child {
+                GreenElement::Node(node) => node.write_text(buffer),
+                GreenElement::Token(token) => buffer.push_str(token.text()),
+            }
+        }
+    }
+}
+
+impl GreenToken {
+    /// Create a token.
+    pub fn new(kind: SyntaxKind, text: &str) -> Self {
+        GreenToken(Arc::new(GreenTokenData { kind, text: text.into() }))
+    }
+    /// The kind of the token.
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.kind
+    }
+    /// The text of the token.
+    pub fn text(&self) -> &str {
+        &self.0.text
+    }
+}
+
+impl GreenElement {
+    /// The kind of the element.
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            GreenElement::Node(node) => node.kind(),
+            GreenElement::Token(token) => token.kind(),
+        }
+    }
+    /// The byte length of the element text.
+    pub fn text_length(&self) -> usize {
+        match self {
+            GreenElement::Node(node) => node.text_length(),
+            GreenElement::Token(token) => token.text().len(),
+        }
+    }
+}
+
+impl Debug for GreenNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("GreenNode").field("kind", &self.kind()).field("children", &self.children()).finish()
+    }
+}
+
+impl Debug for GreenToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}@{:?}", self.kind(), self.text())
+    }
+}
+
+impl GreenBuilder {
+    /// Start a new node, all following elements become its children until [GreenBuilder::finish_node].
+    pub fn start_node(&mut self, kind: SyntaxKind) {
+        self.parents.push((kind, self.children.len()));
+    }
+    /// Add a token to the current node.
+    pub fn token(&mut self, kind: SyntaxKind, text: &str) {
+        self.children.push(GreenElement::Token(GreenToken::new(kind, text)));
+    }
+    /// Add a prebuilt node to the current node.
+    pub fn node(&mut self, node: GreenNode) {
+        self.children.push(GreenElement::Node(node));
+    }
+    /// Finish the current node.
+    pub fn finish_node(&mut self) {
+        let (kind, first) = self.parents.pop().expect("unbalanced finish_node");
+        let children = self.children.split_off(first);
+        self.children.push(GreenElement::Node(GreenNode::new(kind, children)));
+    }
+    /// Remember the current position, to wrap the following elements in a node later.
+    pub fn checkpoint(&self) -> Checkpoint {
+        Checkpoint(self.children.len())
+    }
+    /// Start a node that wraps all elements added since the checkpoint.
+    pub fn start_node_at(&mut self, checkpoint: Checkpoint, kind: SyntaxKind) {
+        let first = self.parents.last().map(|(_, first)| *first).unwrap_or(0);
+        assert!(checkpoint.0 >= first, "checkpoint is outside of the current node");
+        self.parents.push((kind, checkpoint.0));
+    }
+    /// Finish building, exactly one root node must be built.
+    pub fn finish(mut self) -> GreenNode {
+        assert!(self.parents.is_empty(), "unfinished nodes");
+        match self.children.pop() {
+            Some(GreenElement::Node(node)) if self.children.is_empty() => node,
+            _ => panic!("the builder must produce exactly one root node"),
+        }
+    }
+}
@@ -0,0 +1,71 @@
+use std::{
+    fmt::{Display, Formatter},
+    ops::Range,
+};
+
+pub use self::{
+    green::{Checkpoint, GreenBuilder, GreenElement, GreenNode, GreenToken},
+    node::{SyntaxElement, SyntaxNode, SyntaxToken, TokenAtOffset},
+};
+use crate::{
+    lex,
+    syntax::{ast::AstNode, parser::Fragment},
+};
+
+pub mod ast;
+mod green;
+mod node;
+mod parser;
+mod reparse;
+
+/// The result of parsing a file, always a complete tree plus all problems found on the way.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Parse {
+    green: GreenNode,
+    errors: Vec<SyntaxError>,
+}
+
+/// A problem found while lexing or parsing.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub struct SyntaxError {
+    /// The byte span of the problem, empty for missing tokens.
+    pub span: Range<usize>,
+    /// The message to report.
+    pub message: String,
+}
+
+impl Display for SyntaxError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Parse {
+    /// Parse a whole file.
+    pub fn new(text: &str) -> Self {
+        let lexed = lex(text);
+        let (green, errors) =
+            parser::parse_fragment(text, &lexed.tokens, Fragment::SourceFile).expect("a file can always be parsed");
+        let mut all: Vec<SyntaxError> =
+            lexed.errors.into_iter().map(|e| SyntaxError { span: e.span, message: e.message }).collect();
+        all.extend(errors);
+        all.sort_by_key(|e| e.span.start);
+        Parse { green, errors: all }
+    }
+    /// The green root of the tree.
+    pub fn green(&self) -> &GreenNode {
+        &self.green
+    }
+    /// The untyped root of the tree.
+    pub fn syntax(&self) -> SyntaxNode {
+        SyntaxNode::new_root(self.green.clone())
+    }
+    /// The typed root of the tree.
+    pub fn tree(&self) -> ast::SourceFile {
+        ast::SourceFile::cast(self.syntax()).expect("the root is always a source file")
+    }
+    /// All syntax errors, sorted by position.
+    pub fn errors(&self) -> &[SyntaxError] {
+        &self.errors
+    }
+}
@@ -0,0 +1,395 @@
+use std::{
+    fmt::{Debug, Display, Formatter},
+    hash::{Hash, Hasher},
+    ops::Range,
+    sync::Arc,
+};
+
+use super::green::{GreenElement, GreenNode, GreenToken};
+use crate::SyntaxKind;
+
+/// A node of the syntax tree that knows its parent and absolute offset, created lazily from a [GreenNode].
+#[derive(Clone)]
+pub struct SyntaxNode(Arc<NodeData>);
+
+struct NodeData {
+    green: GreenNode,
+    parent: Option<SyntaxNode>,
+    /// The index of the node in the children of the parent.
+    index: usize,
+    offset: usize,
+}
+
+/// A token of the syntax tree, with its parent node and absolute offset.
+#[derive(Clone)]
+pub struct SyntaxToken {
+    green: GreenToken,
+    parent: SyntaxNode,
+    index: usize,
+    offset: usize,
+}
+
+/// A node or a token.
+#[derive(Clone, Debug, Eq, PartialEq, Hash)]
+pub enum SyntaxElement {
+    /// A node.
+    Node(SyntaxNode),
+    /// A token.
+    Token(SyntaxToken),
+}
+
+/// The tokens at an offset, an offset between two tokens touches both of them.
+#[derive(Clone, Debug)]
+pub enum TokenAtOffset {
+    /// The offset is outside of the tree.
+    None,
+    /// The offset is inside a token.
+    Single(SyntaxToken),
+    /// The offset is the boundary of two tokens, left then right.
+    Between(SyntaxToken, SyntaxToken),
+}
+
+impl SyntaxNode {
+    /// Create the root of a syntax tree.
+    pub fn new_root(green: GreenNode) -> Self {
+        SyntaxNode(Arc::new(NodeData { green, parent: None, index: 0, offset: 0 }))
+    }
+    fn new_child(green: GreenNode, parent: SyntaxNode, index: usize, offset: usize) -> Self {
+        SyntaxNode(Arc::new(NodeData { green, parent: Some(parent), index, offset }))
+    }
+    /// The kind of the node.
+    pub fn kind(&self) -> SyntaxKind {
+        self.0.green.kind()
+    }
+    /// The green node backing this node.
+    pub fn green(&self) -> &GreenNode {
+        &self.0.green
+    }
+    /// The byte span covered by the node, trivia inside the node included.
+    pub fn span(&self) -> Range<usize> {
+        self.0.offset..self.0.offset + self.0.green.text_length()
+    }
+    /// The byte span without leading and trailing trivia.
+    pub fn trimmed_span(&self) -> Range<usize> {
+        let mut tokens = self.descendant_tokens().filter(|token| !token.kind().is_trivia());
+        match tokens.next() {
+            Some(first) => {
+                let end = tokens.last().unwrap_or_else(|| first.clone()).span().end;
+                first.span().start..end
+            }
+            None => self.span(),
+        }
+    }
+    /// The text covered by the node.
+    pub fn text(&self) -> String {
+        let mut buffer = String::with_capacity(self.0.green.text_length());
+        self.0.green.write_text(&mut buffer);
+        buffer
+    }
+    /// The parent node, none for the root.
+    pub fn parent(&self) -> Option<SyntaxNode> {
+        self.0.parent.clone()
+    }
+    /// The index of this node in the children of its parent.
+    pub fn index(&self) -> usize {
+        self.0.index
+    }
+    /// The node itself, then all its parents up to the root.
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+        std::iter::successors(Some(self.clone()), |node| node.parent())
+    }
+    /// All child nodes and tokens.
+    pub fn children_with_tokens(&self) -> impl Iterator<Item = SyntaxElement> + '_ {
+        let mut offset = self.0.offset;
+        self.0.green.children().iter().enumerate().map(move |(index, child)| {
+            let start = offset;
+            offset += child.text_length();
+            match child {
+                GreenElement::Node(green) => {
+                    SyntaxElement::Node(SyntaxNode::new_child(green.clone(), self.clone(), index, start))
+                }
+                GreenElement::Token(green) => {
+                    SyntaxElement::Token(SyntaxToken { green: green.clone(), parent: self.clone(), index, offset: start })
+                }
+            }
+        })
+    }
+    /// All child nodes.
+    pub fn children(&self) -> impl Iterator<Item = SyntaxNode> + '_ {
+        self.children_with_tokens().filter_map(|element| element.into_node())
+    }
+    /// All child tokens.
+    pub fn child_tokens(&self) -> impl Iterator<Item = SyntaxToken> + '_ {
+        self.children_with_tokens().filter_map(|element| element.into_token())
+    }
+    /// The first child token of the kind.
+    pub fn child_token(&self, kind: SyntaxKind) -> Option<SyntaxToken> {
+        self.child_tokens().find(|token| token.kind() == kind)
+    }
+    /// The node itself and all nested nodes in preorder.
+    pub fn descendants(&self) -> impl Iterator<Item = SyntaxNode> {
+        let mut stack = vec![self.clone()];
+        std::iter::from_fn(move || {
+            let node = stack.pop()?;
+            let children: Vec<SyntaxNode> = node.children().collect();
+            stack.extend(children.into_iter().rev());
+            Some(node)
+        })
+    }
+    /// All tokens in the node in source order.
+    pub fn descendant_tokens(&self) -> impl Iterator<Item = SyntaxToken> {
+        let mut stack = vec![SyntaxElement::Node(self.clone())];
+        std::iter::from_fn(move || {
+            while let Some(element) = stack.pop() {
+                match element {
+                    SyntaxElement::Token(token) => return Some(token),
+                    SyntaxElement::Node(node) => {
+                        let children: Vec<SyntaxElement> = node.children_with_tokens().collect();
+                        stack.extend(children.into_iter().rev());
+                    }
+                }
+            }
+            None
+        })
+    }
+    /// The sibling element before this node.
+    pub fn prev_sibling_or_token(&self) -> Option<SyntaxElement> {
+        let index = self.index().checked_sub(1)?;
+        self.parent()?.children_with_tokens().nth(index)
+    }
+    /// The sibling element after this node.
+    pub fn next_sibling_or_token(&self) -> Option<SyntaxElement> {
+        self.parent()?.children_with_tokens().nth(self.index() + 1)
+    }
+    /// The first token of the node, trivia included.
+    pub fn first_token(&self) -> Option<SyntaxToken> {
+        self.descendant_tokens().next()
+    }
+    /// The last token of the node, trivia included.
+    pub fn last_token(&self) -> Option<SyntaxToken> {
+        self.descendant_tokens().last()
+    }
+    /// Find the tokens touching the offset.
+    pub fn token_at_offset(&self, offset: usize) -> TokenAtOffset {
+        let right = self.token_covering(offset, false);
+        let left = self.token_covering(offset, true);
+        match (left, right) {
+            (Some(left), Some(right)) if left != right => TokenAtOffset::Between(left, right),
+            (_, Some(token)) | (Some(token), None) => TokenAtOffset::Single(token),
+            (None, None) => TokenAtOffset::None,
+        }
+    }
+    /// Find the token containing the offset, or ending at the offset if `end_inclusive` is set.
+    fn token_covering(&self, offset: usize, end_inclusive: bool) -> Option<SyntaxToken> {
+        let mut node = self.clone();
+        loop {
+            let child = node.children_with_tokens().find(|child| {
+                let span = child.span();
+                match end_inclusive {
+                    true => span.start < offset && offset <= span.end,
+                    false => span.start <= offset && offset < span.end,
+                }
+            })?;
+            match child {
+                SyntaxElement::Node(child) => node = child,
+                SyntaxElement::Token(token) => return Some(token),
+            }
+        }
+    }
+    /// Build the green root of a new tree with this node replaced, all other subtrees are shared with this tree.
+    pub fn replace_with(&self, replacement: GreenNode) -> GreenNode {
+        match self.parent() {
+            Some(parent) => parent.replace_with(parent.green().replace_child(self.index(), GreenElement::Node(replacement))),
+            None => replacement,
+        }
+    }
+    /// The smallest node that fully covers the span.
+    pub fn covering_node(&self, span: Range<usize>) -> SyntaxNode {
+        let mut node = self.clone();
+        loop {
+            let inner = node.children().find(|child| {
+                let child_span = child.span();
+                child_span.start <= span.start && span.end <= child_span.end
+            });
+            match inner {
+                Some(inner) => node = inner,
+                None => return node,
+            }
+        }
+    }
+}
+
+impl SyntaxToken {
+    /// The kind of the token.
+    pub fn kind(&self) -> SyntaxKind {
+        self.green.kind()
+    }
+    /// The text of the token.
+    pub fn text(&self) -> &str {
+        self.green.text()
+    }
+    /// The byte span of the token.
+    pub fn span(&self) -> Range<usize> {
+        self.offset..self.offset + self.green.text().len()
+    }
+    /// The node containing the token.
+    pub fn parent(&self) -> SyntaxNode {
+        self.parent.clone()
+    }
+    /// All nodes containing the token, innermost first.
+    pub fn ancestors(&self) -> impl Iterator<Item = SyntaxNode> {
+        self.parent.ancestors()
+    }
+    /// The sibling element before this token.
+    pub fn prev_sibling_or_token(&self) -> Option<SyntaxElement> {
+        let index = self.index.checked_sub(1)?;
+        self.parent.children_with_tokens().nth(index)
+    }
+    /// The sibling element after this token.
+    pub fn next_sibling_or_token(&self) -> Option<SyntaxElement> {
+        self.parent.children_with_tokens().nth(self.index + 1)
+    }
+    /// The token before this one in the whole tree, trivia included.
+    pub fn prev_token(&self) -> Option<SyntaxToken> {
+        let mut node = self.parent();
+        let mut element = self.prev_sibling_or_token();
+        loop {
+            match element {
+                Some(SyntaxElement::Token(token)) => return Some(token),
+                Some(SyntaxElement::Node(sibling)) => match sibling.last_token() {
+                    Some(token) => return Some(token),
+                    None => element = sibling.prev_sibling_or_token(),
+                },
+                None => {
+                    element = node.prev_sibling_or_token();
+                    node = node.parent()?;
+                }
+            }
+        }
+    }
+    /// The token after this one in the whole tree, trivia included.
+    pub fn next_token(&self) -> Option<SyntaxToken> {
+        let mut node = self.parent();
+        let mut element = self.next_sibling_or_token();
+        loop {
+            match element {
+                Some(SyntaxElement::Token(token)) => return Some(token),
+                Some(SyntaxElement::Node(sibling)) => match sibling.first_token() {
+                    Some(token) => return Some(token),
+                    None => element = sibling.next_sibling_or_token(),
+                },
+                None => {
+                    element = node.next_sibling_or_token();
+                    node = node.parent()?;
+                }
+            }
+        }
+    }
+    /// Build the green root of a new tree with this token replaced, all other subtrees are shared with this tree.
+    pub fn replace_with(&self, replacement: GreenToken) -> GreenNode {
+        self.parent.replace_with(self.parent.green().replace_child(self.index, GreenElement::Token(replacement)))
+    }
+}
+
+impl TokenAtOffset {
+    /// Pick one token, preferring the one the predicate ranks higher, the right one on ties.
+    pub fn pick_best(self, rank: impl Fn(SyntaxKind) -> usize) -> Option<SyntaxToken> {
+        match self {
+            TokenAtOffset::None => None,
+            TokenAtOffset::Single(token) => Some(token),
+            TokenAtOffset::Between(left, right) => {
+                if rank(left.kind()) > rank(right.kind()) {
+                    Some(left)
+                }
+                else {
+                    Some(right)
+                }
+            }
+        }
+    }
+}
+
+impl SyntaxElement {
+    /// The kind of the element.
+    pub fn kind(&self) -> SyntaxKind {
+        match self {
+            SyntaxElement::Node(node) => node.kind(),
+            SyntaxElement::Token(token) => token.kind(),
+        }
+    }
+    /// The byte span of the element.
+    pub fn span(&self) -> Range<usize> {
+        match self {
+            SyntaxElement::Node(node) => node.span(),
+            SyntaxElement::Token(token) => token.span(),
+        }
+    }
+    /// Get the node, if it is one.
+    pub fn into_node(self) -> Option<SyntaxNode> {
+        match self {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        }
+    }
+    /// Get the token, if it is one.
+    pub fn into_token(self) -> Option<SyntaxToken> {
+        match self {
+            SyntaxElement::Node(_) => None,
+            SyntaxElement::Token(token) => Some(token),
+        }
+    }
+}
+
+impl PartialEq for SyntaxNode {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.green.ptr_eq(&other.0.green) && self.0.offset == other.0.offset
+    }
+}
+
+impl Eq for SyntaxNode {}
+
+impl Hash for SyntaxNode {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind().hash(state);
+        self.span().hash(state);
+    }
+}
+
+impl PartialEq for SyntaxToken {
+    fn eq(&self, other: &Self) -> bool {
+        self.parent == other.parent && self.index == other.index
+    }
+}
+
+impl Eq for SyntaxToken {}
+
+impl Hash for SyntaxToken {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.kind().hash(state);
+        self.span().hash(state);
+    }
+}
+
+impl Debug for SyntaxNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}@{:?}", self.kind(), self.span())
+    }
+}
+
+impl Debug for SyntaxToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?}@{:?} {:?}", self.kind(), self.span(), self.text())
+    }
+}
+
+impl Display for SyntaxNode {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.text())
+    }
+}
+
+impl Display for SyntaxToken {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.text())
+    }
+}
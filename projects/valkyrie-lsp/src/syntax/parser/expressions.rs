@@ -0,0 +1,450 @@
+use super::{
+    Parser,
+    items::{self, Context, ITEM_START},
+    types::{path, pattern, type_ref},
+};
+use crate::SyntaxKind;
+
+/// Check if the current token can start an expression.
+pub(super) fn at_expr_start(p: &Parser) -> bool {
+    let kind = p.current();
+    kind.is_literal()
+        || matches!(
+            kind,
+            SyntaxKind::Ident
+                | SyntaxKind::SelfKw
+                | SyntaxKind::SelfTypeKw
+                | SyntaxKind::LParen
+                | SyntaxKind::LBracket
+                | SyntaxKind::LBrace
+                | SyntaxKind::Minus
+                | SyntaxKind::Bang
+                | SyntaxKind::Tilde
+                | SyntaxKind::NotKw
+                | SyntaxKind::IfKw
+                | SyntaxKind::WhileKw
+                | SyntaxKind::LoopKw
+                | SyntaxKind::ForKw
+                | SyntaxKind::MatchKw
+                | SyntaxKind::TryKw
+                | SyntaxKind::ReturnKw
+                | SyntaxKind::BreakKw
+                | SyntaxKind::ContinueKw
+                | SyntaxKind::RaiseKw
+                | SyntaxKind::YieldKw
+                | SyntaxKind::NewKw
+        )
+}
+
+/// `expression;`, the semicolon is optional.
+///
+/// Block like expressions end the statement, `if a { } -b` is two statements.
+pub(super) fn expr_stmt(p: &mut Parser) {
+    let checkpoint = p.checkpoint();
+    let block_like = [
+        SyntaxKind::LBrace,
+        SyntaxKind::IfKw,
+        SyntaxKind::WhileKw,
+        SyntaxKind::LoopKw,
+        SyntaxKind::ForKw,
+        SyntaxKind::MatchKw,
+        SyntaxKind::TryKw,
+    ];
+    if p.at_any(&block_like) {
+        primary_expr(p);
+    }
+    else {
+        expr(p);
+    }
+    p.start_at(checkpoint, SyntaxKind::ExprStmt);
+    p.eat(SyntaxKind::Semicolon);
+    p.finish_node();
+}
+
+/// `let pattern: Type = value;`, the node must already be started.
+pub(super) fn let_stmt(p: &mut Parser) {
+    p.bump();
+    pattern(p);
+    if p.eat(SyntaxKind::Colon) {
+        type_ref(p);
+    }
+    if p.eat(SyntaxKind::Eq) && !expr(p) {
+        p.error(format!("expected an expression, found {}", p.describe_current()));
+    }
+    p.eat(SyntaxKind::Semicolon);
+}
+
+/// Parse an expression, returns false without consuming anything if there is none.
+pub(super) fn expr(p: &mut Parser) -> bool {
+    expr_bp(p, 0)
+}
+
+/// The binding power of an infix operator as left and right power, and the node it builds.
+fn infix_power(p: &Parser) -> Option<(u8, u8, SyntaxKind, usize)> {
+    let binary = SyntaxKind::BinaryExpr;
+    let power = match p.current() {
+        SyntaxKind::Eq
+        | SyntaxKind::PlusEq
+        | SyntaxKind::MinusEq
+        | SyntaxKind::StarEq
+        | SyntaxKind::SlashEq
+        | SyntaxKind::PercentEq
+        | SyntaxKind::CaretEq
+        | SyntaxKind::AmpEq
+        | SyntaxKind::PipeEq
+        | SyntaxKind::ShlEq => (2, 1, binary, 1),
+        // `>>=` is lexed as `>` and `>=`
+        SyntaxKind::Gt if p.at_glued_gt(SyntaxKind::GtEq) => (2, 1, binary, 2),
+        SyntaxKind::PipeGt => (3, 4, binary, 1),
+        SyntaxKind::DotDot | SyntaxKind::DotDotLt => (5, 6, binary, 1),
+        SyntaxKind::QuestionQuestion => (8, 7, binary, 1),
+        SyntaxKind::PipePipe => (9, 10, binary, 1),
+        SyntaxKind::AmpAmp => (11, 12, binary, 1),
+        // `>>` is lexed as two `>`
+        SyntaxKind::Gt if p.at_glued_gt(SyntaxKind::Gt) => (21, 22, binary, 2),
+        SyntaxKind::EqEq | SyntaxKind::BangEq | SyntaxKind::Lt | SyntaxKind::Gt | SyntaxKind::LtEq | SyntaxKind::GtEq => {
+            (13, 14, binary, 1)
+        }
+        SyntaxKind::IsKw => (13, 14, SyntaxKind::CastExpr, 1),
+        SyntaxKind::Pipe => (15, 16, binary, 1),
+        SyntaxKind::Caret => (17, 18, binary, 1),
+        SyntaxKind::Amp => (19, 20, binary, 1),
+        SyntaxKind::Shl => (21, 22, binary, 1),
+        SyntaxKind::Plus | SyntaxKind::Minus => (23, 24, binary, 1),
+        SyntaxKind::Star | SyntaxKind::Slash | SyntaxKind::Percent => (25, 26, binary, 1),
+        SyntaxKind::StarStar => (28, 27, binary, 1),
+        SyntaxKind::AsKw => (29, 30, SyntaxKind::CastExpr, 1),
+        _ => return None,
+    };
+    Some(power)
+}
+
+/// The binding power of prefix operators, binds tighter than `*` but looser than `**`.
+const PREFIX_POWER: u8 = 27;
+
+fn expr_bp(p: &mut Parser, min_power: u8) -> bool {
+    let checkpoint = p.checkpoint();
+    if !prefix_expr(p) {
+        return false;
+    }
+    while let Some((left, right, kind, tokens)) = infix_power(p) {
+        if left < min_power {
+            break;
+        }
+        p.start_at(checkpoint, kind);
+        for _ in 0..tokens {
+            p.bump();
+        }
+        if kind == SyntaxKind::CastExpr {
+            type_ref(p);
+        }
+        else if !expr_bp(p, right) {
+            p.error(format!("expected an expression, found {}", p.describe_current()));
+        }
+        p.finish_node();
+    }
+    true
+}
+
+fn prefix_expr(p: &mut Parser) -> bool {
+    match p.current() {
+        SyntaxKind::Minus | SyntaxKind::Bang | SyntaxKind::Tilde | SyntaxKind::NotKw => {
+            p.start(SyntaxKind::PrefixExpr);
+            p.bump();
+            if !expr_bp(p, PREFIX_POWER) {
+                p.error(format!("expected an expression, found {}", p.describe_current()));
+            }
+            p.finish_node();
+            true
+        }
+        _ => postfix_expr(p),
+    }
+}
+
+fn postfix_expr(p: &mut Parser) -> bool {
+    let checkpoint = p.checkpoint();
+    if !primary_expr(p) {
+        return false;
+    }
+    loop {
+        match p.current() {
+            // a call or index on the next line is a new statement
+            SyntaxKind::LParen if !p.newline_before() => {
+                p.start_at(checkpoint, SyntaxKind::CallExpr);
+                arg_list(p);
+                p.finish_node();
+            }
+            SyntaxKind::LBracket if !p.newline_before() => {
+                p.start_at(checkpoint, SyntaxKind::IndexExpr);
+                p.bump();
+                if !expr(p) {
+                    p.error(format!("expected an index, found {}", p.describe_current()));
+                }
+                p.expect(SyntaxKind::RBracket);
+                p.finish_node();
+            }
+            SyntaxKind::Dot | SyntaxKind::QuestionDot => {
+                p.start_at(checkpoint, SyntaxKind::FieldExpr);
+                p.bump();
+                if p.at(SyntaxKind::Ident) || p.at(SyntaxKind::Integer) {
+                    p.start(SyntaxKind::NameRef);
+                    p.bump();
+                    p.finish_node();
+                }
+                else {
+                    p.error(format!("expected a member name, found {}", p.describe_current()));
+                }
+                p.finish_node();
+            }
+            SyntaxKind::Question => {
+                p.start_at(checkpoint, SyntaxKind::TryExpr);
+                p.bump();
+                p.finish_node();
+            }
+            _ => return true,
+        }
+    }
+}
+
+pub(super) fn literal(p: &mut Parser) {
+    p.start(SyntaxKind::Literal);
+    p.bump();
+    p.finish_node();
+}
+
+fn primary_expr(p: &mut Parser) -> bool {
+    match p.current() {
+        kind if kind.is_literal() => literal(p),
+        SyntaxKind::Ident | SyntaxKind::SelfKw | SyntaxKind::SelfTypeKw => {
+            p.start(SyntaxKind::PathExpr);
+            path(p, false);
+            p.finish_node();
+        }
+        SyntaxKind::LParen => paren_expr(p),
+        SyntaxKind::LBracket => {
+            p.start(SyntaxKind::ArrayExpr);
+            p.bump();
+            expr_list(p, SyntaxKind::RBracket);
+            p.finish_node();
+        }
+        SyntaxKind::LBrace => block_expr(p),
+        SyntaxKind::IfKw => if_expr(p),
+        SyntaxKind::WhileKw => {
+            p.start(SyntaxKind::WhileExpr);
+            p.bump();
+            condition(p);
+            body(p);
+            p.finish_node();
+        }
+        SyntaxKind::LoopKw => {
+            p.start(SyntaxKind::LoopExpr);
+            p.bump();
+            body(p);
+            p.finish_node();
+        }
+        SyntaxKind::ForKw => {
+            p.start(SyntaxKind::ForExpr);
+            p.bump();
+            pattern(p);
+            if p.expect(SyntaxKind::InKw) {
+                condition(p);
+            }
+            body(p);
+            p.finish_node();
+        }
+        SyntaxKind::MatchKw => match_expr(p),
+        SyntaxKind::TryKw => {
+            p.start(SyntaxKind::TryCatchExpr);
+            p.bump();
+            body(p);
+            while p.at(SyntaxKind::CatchKw) {
+                p.start(SyntaxKind::CatchClause);
+                p.bump();
+                if !p.at(SyntaxKind::LBrace) {
+                    pattern(p);
+                }
+                body(p);
+                p.finish_node();
+            }
+            p.finish_node();
+        }
+        SyntaxKind::ReturnKw => jump_expr(p, SyntaxKind::ReturnExpr),
+        SyntaxKind::RaiseKw => jump_expr(p, SyntaxKind::RaiseExpr),
+        SyntaxKind::YieldKw => jump_expr(p, SyntaxKind::YieldExpr),
+        SyntaxKind::BreakKw => jump_expr(p, SyntaxKind::BreakExpr),
+        SyntaxKind::ContinueKw => {
+            p.start(SyntaxKind::ContinueExpr);
+            p.bump();
+            p.finish_node();
+        }
+        SyntaxKind::NewKw => {
+            p.start(SyntaxKind::NewExpr);
+            p.bump();
+            type_ref(p);
+            if p.at(SyntaxKind::LParen) {
+                arg_list(p);
+            }
+            p.finish_node();
+        }
+        _ => return false,
+    }
+    true
+}
+
+fn paren_expr(p: &mut Parser) {
+    let checkpoint = p.checkpoint();
+    p.bump();
+    if p.eat(SyntaxKind::RParen) {
+        p.start_at(checkpoint, SyntaxKind::TupleExpr);
+        p.finish_node();
+        return;
+    }
+    if !expr(p) {
+        p.error(format!("expected an expression, found {}", p.describe_current()));
+    }
+    if p.at(SyntaxKind::Comma) {
+        p.start_at(checkpoint, SyntaxKind::TupleExpr);
+        p.bump();
+        expr_list(p, SyntaxKind::RParen);
+    }
+    else {
+        p.start_at(checkpoint, SyntaxKind::ParenExpr);
+        p.expect(SyntaxKind::RParen);
+    }
+    p.finish_node();
+}
+
+/// Comma separated expressions up to and including the closing token.
+fn expr_list(p: &mut Parser, close: SyntaxKind) {
+    while !p.at(close) && !p.at_eof() {
+        if expr(p) {
+            if !p.at(close) {
+                p.expect(SyntaxKind::Comma);
+            }
+        }
+        else if p.at_any(&[SyntaxKind::LBrace, SyntaxKind::RBrace, SyntaxKind::Semicolon]) || p.at_any(ITEM_START) {
+            break;
+        }
+        else {
+            p.error_and_bump(format!("expected an expression, found {}", p.describe_current()));
+        }
+    }
+    p.expect(close);
+}
+
+fn arg_list(p: &mut Parser) {
+    p.start(SyntaxKind::ArgList);
+    p.bump();
+    expr_list(p, SyntaxKind::RParen);
+    p.finish_node();
+}
+
+/// A condition or iterable before a body.
+fn condition(p: &mut Parser) {
+    if p.at(SyntaxKind::LBrace) || !expr(p) {
+        p.error(format!("expected a condition, found {}", p.describe_current()));
+    }
+}
+
+/// The braced body of a control flow expression.
+fn body(p: &mut Parser) {
+    if p.at(SyntaxKind::LBrace) {
+        block_expr(p);
+    }
+    else {
+        p.error(format!("expected `{{`, found {}", p.describe_current()));
+    }
+}
+
+fn if_expr(p: &mut Parser) {
+    p.start(SyntaxKind::IfExpr);
+    p.bump();
+    condition(p);
+    body(p);
+    if p.eat(SyntaxKind::ElseKw) {
+        if p.at(SyntaxKind::IfKw) {
+            if_expr(p);
+        }
+        else {
+            body(p);
+        }
+    }
+    p.finish_node();
+}
+
+fn match_expr(p: &mut Parser) {
+    p.start(SyntaxKind::MatchExpr);
+    p.bump();
+    condition(p);
+    if p.eat(SyntaxKind::LBrace) {
+        while !p.at(SyntaxKind::RBrace) && !p.at_eof() {
+            if p.at(SyntaxKind::CaseKw) || p.at(SyntaxKind::ElseKw) {
+                match_arm(p);
+            }
+            else if p.at_any(ITEM_START) {
+                break;
+            }
+            else {
+                p.error_and_bump(format!("expected `case`, found {}", p.describe_current()));
+            }
+        }
+        p.expect(SyntaxKind::RBrace);
+    }
+    else {
+        p.error(format!("expected `{{`, found {}", p.describe_current()));
+    }
+    p.finish_node();
+}
+
+/// `case pattern if guard => value` or `else => value`
+fn match_arm(p: &mut Parser) {
+    p.start(SyntaxKind::MatchArm);
+    if p.eat(SyntaxKind::CaseKw) {
+        pattern(p);
+        if p.eat(SyntaxKind::IfKw) {
+            condition(p);
+        }
+    }
+    else {
+        p.bump();
+    }
+    if !p.eat(SyntaxKind::FatArrow) && !p.eat(SyntaxKind::Colon) {
+        p.error(format!("expected `=>`, found {}", p.describe_current()));
+    }
+    if !expr(p) {
+        p.error(format!("expected an expression, found {}", p.describe_current()));
+    }
+    if !p.eat(SyntaxKind::Comma) {
+        p.eat(SyntaxKind::Semicolon);
+    }
+    p.finish_node();
+}
+
+/// `return`, `raise`, `yield` and `break` with an optional value on the same line.
+fn jump_expr(p: &mut Parser, kind: SyntaxKind) {
+    p.start(kind);
+    p.bump();
+    if !p.newline_before() && at_expr_start(p) && !p.at(SyntaxKind::LBrace) {
+        expr(p);
+    }
+    p.finish_node();
+}
+
+/// `{ statements }`
+pub(super) fn block_expr(p: &mut Parser) {
+    p.start(SyntaxKind::BlockExpr);
+    block_body(p);
+    p.finish_node();
+}
+
+/// Parse `{ statements }`, the node must already be started.
+pub(super) fn block_body(p: &mut Parser) {
+    p.expect(SyntaxKind::LBrace);
+    while !p.at(SyntaxKind::RBrace) && !p.at_eof() {
+        if p.eat(SyntaxKind::Semicolon) {
+            continue;
+        }
+        items::item_or_statement(p, Context::Block);
+    }
+    p.expect(SyntaxKind::RBrace);
+}
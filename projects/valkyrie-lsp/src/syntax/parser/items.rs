@@ -0,0 +1,413 @@
+use super::{
+    Parser,
+    expressions::{self, block_expr, expr},
+    types::{generic_params, name, path, pattern, type_ref},
+};
+use crate::SyntaxKind;
+
+/// Where an item is parsed, decides which items and statements are allowed.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(super) enum Context {
+    /// Top level of a file or a namespace block, statements are allowed.
+    File,
+    /// Body of a class, trait, imply, methods and fields are allowed.
+    Declaration,
+    /// Inside a block expression.
+    Block,
+}
+
+/// Tokens that start a declaration, used to stop error recovery.
+pub(super) const ITEM_START: &[SyntaxKind] = &[
+    SyntaxKind::NamespaceKw,
+    SyntaxKind::UsingKw,
+    SyntaxKind::ClassKw,
+    SyntaxKind::StructureKw,
+    SyntaxKind::TraitKw,
+    SyntaxKind::ImplyKw,
+    SyntaxKind::ExtendsKw,
+    SyntaxKind::UnionKw,
+    SyntaxKind::MicroKw,
+    SyntaxKind::TypeKw,
+    SyntaxKind::LetKw,
+    SyntaxKind::Hash,
+    SyntaxKind::At,
+    SyntaxKind::PublicKw,
+    SyntaxKind::PrivateKw,
+    SyntaxKind::ProtectedKw,
+    SyntaxKind::StaticKw,
+    SyntaxKind::FinalKw,
+];
+
+pub(super) fn source_file(p: &mut Parser) {
+    p.start_root(SyntaxKind::SourceFile);
+    while !p.at_eof() {
+        if p.at(SyntaxKind::RBrace) {
+            p.error_and_bump("unmatched `}`");
+            continue;
+        }
+        item_or_statement(p, Context::File);
+    }
+    p.finish_root();
+}
+
+/// Parse `{ items }`, the node must already be started.
+pub(super) fn item_list_body(p: &mut Parser, context: Context) {
+    p.expect(SyntaxKind::LBrace);
+    while !p.at(SyntaxKind::RBrace) && !p.at_eof() {
+        item_or_statement(p, context);
+    }
+    p.expect(SyntaxKind::RBrace);
+}
+
+fn item_list(p: &mut Parser, context: Context) {
+    if !p.at(SyntaxKind::LBrace) {
+        p.error(format!("expected `{{`, found {}", p.describe_current()));
+        return;
+    }
+    p.start(SyntaxKind::ItemList);
+    item_list_body(p, context);
+    p.finish_node();
+}
+
+/// Parse one item, or a statement where statements are allowed, always consumes at least one token.
+pub(super) fn item_or_statement(p: &mut Parser, context: Context) {
+    let start = p.position();
+    let checkpoint = p.checkpoint();
+    let mut prefixed = false;
+    while p.at(SyntaxKind::Hash) || p.at(SyntaxKind::At) {
+        attribute(p);
+        prefixed = true;
+    }
+    if p.current().is_modifier() && p.current() != SyntaxKind::MutKw {
+        p.start(SyntaxKind::ModifierList);
+        while p.current().is_modifier() {
+            p.bump();
+        }
+        p.finish_node();
+        prefixed = true;
+    }
+    let kind = match p.current() {
+        SyntaxKind::NamespaceKw => SyntaxKind::NamespaceDecl,
+        SyntaxKind::UsingKw => SyntaxKind::UsingDecl,
+        SyntaxKind::ClassKw | SyntaxKind::StructureKw => SyntaxKind::ClassDecl,
+        SyntaxKind::TraitKw => SyntaxKind::TraitDecl,
+        SyntaxKind::ImplyKw | SyntaxKind::ExtendsKw => SyntaxKind::ImplyDecl,
+        SyntaxKind::UnionKw => SyntaxKind::UnionDecl,
+        SyntaxKind::MicroKw => SyntaxKind::FunctionDecl,
+        SyntaxKind::TypeKw => SyntaxKind::TypeAliasDecl,
+        SyntaxKind::LetKw if context != Context::Declaration => SyntaxKind::LetStmt,
+        SyntaxKind::Ident | SyntaxKind::SelfKw if context == Context::Declaration => match p.nth(1) {
+            SyntaxKind::LParen | SyntaxKind::Lt => SyntaxKind::FunctionDecl,
+            _ => SyntaxKind::FieldDecl,
+        },
+        _ => SyntaxKind::Error,
+    };
+    if kind == SyntaxKind::Error && !prefixed {
+        if context != Context::Declaration && expressions::at_expr_start(p) {
+            expressions::expr_stmt(p);
+            return;
+        }
+        let message = match context {
+            Context::Declaration => format!("expected a member declaration, found {}", p.describe_current()),
+            _ => format!("expected a declaration or statement, found {}", p.describe_current()),
+        };
+        p.error_and_bump(message);
+        return;
+    }
+    p.start_at(checkpoint, kind);
+    match kind {
+        SyntaxKind::NamespaceDecl => namespace_decl(p),
+        SyntaxKind::UsingDecl => using_decl(p),
+        SyntaxKind::ClassDecl | SyntaxKind::TraitDecl => class_decl(p),
+        SyntaxKind::ImplyDecl => imply_decl(p),
+        SyntaxKind::UnionDecl => union_decl(p),
+        SyntaxKind::FunctionDecl => function_decl(p),
+        SyntaxKind::TypeAliasDecl => type_alias_decl(p),
+        SyntaxKind::LetStmt => expressions::let_stmt(p),
+        SyntaxKind::FieldDecl => field_decl(p),
+        _ => p.error(format!("expected a declaration, found {}", p.describe_current())),
+    }
+    p.finish_node();
+    // the callers loop until a closing token, they must move on even if the declaration was empty
+    if p.position() == start && !p.at_eof() {
+        p.error_and_bump(format!("expected a declaration, found {}", p.describe_current()));
+    }
+}
+
+fn namespace_decl(p: &mut Parser) {
+    p.bump();
+    path(p, true);
+    if p.at(SyntaxKind::LBrace) {
+        item_list(p, Context::File);
+    }
+    else {
+        p.eat(SyntaxKind::Semicolon);
+    }
+}
+
+fn using_decl(p: &mut Parser) {
+    p.bump();
+    use_tree(p);
+    p.eat(SyntaxKind::Semicolon);
+}
+
+fn use_tree(p: &mut Parser) {
+    p.start(SyntaxKind::UseTree);
+    if p.at(SyntaxKind::LBrace) {
+        use_tree_list(p);
+    }
+    else if p.at(SyntaxKind::Star) {
+        p.bump();
+    }
+    else {
+        path(p, true);
+        if p.at(SyntaxKind::ColonColon) || p.at(SyntaxKind::Dot) {
+            p.bump();
+            match p.current() {
+                SyntaxKind::LBrace => use_tree_list(p),
+                SyntaxKind::Star => p.bump(),
+                _ => p.error(format!("expected a name, `*` or `{{`, found {}", p.describe_current())),
+            }
+        }
+        else if p.at(SyntaxKind::AsKw) {
+            p.start(SyntaxKind::UseAlias);
+            p.bump();
+            name(p);
+            p.finish_node();
+        }
+    }
+    p.finish_node();
+}
+
+fn use_tree_list(p: &mut Parser) {
+    p.start(SyntaxKind::UseTreeList);
+    p.bump();
+    while !p.at(SyntaxKind::RBrace) && !p.at_eof() {
+        if p.at(SyntaxKind::Ident) || p.at(SyntaxKind::LBrace) || p.at(SyntaxKind::Star) {
+            use_tree(p);
+            if !p.at(SyntaxKind::RBrace) {
+                p.expect(SyntaxKind::Comma);
+            }
+        }
+        else if p.at_any(ITEM_START) || p.at(SyntaxKind::Semicolon) {
+            break;
+        }
+        else {
+            p.error_and_bump(format!("expected a name, found {}", p.describe_current()));
+        }
+    }
+    p.expect(SyntaxKind::RBrace);
+    p.finish_node();
+}
+
+fn class_decl(p: &mut Parser) {
+    p.bump();
+    name(p);
+    if p.at(SyntaxKind::Lt) {
+        generic_params(p);
+    }
+    if p.at(SyntaxKind::Colon) {
+        super_types(p);
+    }
+    item_list(p, Context::Declaration);
+}
+
+fn imply_decl(p: &mut Parser) {
+    p.bump();
+    if p.at(SyntaxKind::Lt) {
+        generic_params(p);
+    }
+    type_ref(p);
+    if p.at(SyntaxKind::Colon) {
+        super_types(p);
+    }
+    item_list(p, Context::Declaration);
+}
+
+fn super_types(p: &mut Parser) {
+    p.start(SyntaxKind::SuperTypeList);
+    p.bump();
+    type_ref(p);
+    while p.at(SyntaxKind::Comma) || p.at(SyntaxKind::Plus) {
+        p.bump();
+        type_ref(p);
+    }
+    p.finish_node();
+}
+
+fn union_decl(p: &mut Parser) {
+    p.bump();
+    name(p);
+    if p.at(SyntaxKind::Lt) {
+        generic_params(p);
+    }
+    if !p.at(SyntaxKind::LBrace) {
+        p.error(format!("expected `{{`, found {}", p.describe_current()));
+        return;
+    }
+    p.start(SyntaxKind::ItemList);
+    p.bump();
+    while !p.at(SyntaxKind::RBrace) && !p.at_eof() {
+        if p.at(SyntaxKind::Ident) {
+            p.start(SyntaxKind::VariantDecl);
+            name(p);
+            if p.at(SyntaxKind::LParen) {
+                type_ref(p);
+            }
+            p.finish_node();
+            if !p.eat(SyntaxKind::Comma) {
+                p.eat(SyntaxKind::Semicolon);
+            }
+        }
+        else if p.at(SyntaxKind::MicroKw) || p.at(SyntaxKind::Hash) || p.at(SyntaxKind::At) || p.current().is_modifier() {
+            item_or_statement(p, Context::Declaration);
+        }
+        else {
+            p.error_and_bump(format!("expected a variant, found {}", p.describe_current()));
+        }
+    }
+    p.expect(SyntaxKind::RBrace);
+    p.finish_node();
+}
+
+fn function_decl(p: &mut Parser) {
+    p.eat(SyntaxKind::MicroKw);
+    member_name(p);
+    if p.at(SyntaxKind::Lt) {
+        generic_params(p);
+    }
+    if p.at(SyntaxKind::LParen) {
+        param_list(p);
+    }
+    else {
+        p.error(format!("expected `(`, found {}", p.describe_current()));
+    }
+    if p.at(SyntaxKind::Arrow) {
+        p.start(SyntaxKind::ReturnType);
+        p.bump();
+        type_ref(p);
+        p.finish_node();
+    }
+    if p.at(SyntaxKind::LBrace) {
+        block_expr(p);
+    }
+    else {
+        // declaration without body, such as a trait method
+        p.eat(SyntaxKind::Semicolon);
+    }
+}
+
+fn param_list(p: &mut Parser) {
+    p.start(SyntaxKind::ParamList);
+    p.bump();
+    while !p.at(SyntaxKind::RParen) && !p.at_eof() {
+        if p.at_any(&[SyntaxKind::Ident, SyntaxKind::SelfKw, SyntaxKind::MutKw, SyntaxKind::DotDotDot]) {
+            param(p);
+            if !p.at(SyntaxKind::RParen) {
+                p.expect(SyntaxKind::Comma);
+            }
+        }
+        else if p.at(SyntaxKind::LBrace) || p.at(SyntaxKind::Arrow) || p.at_any(ITEM_START) {
+            break;
+        }
+        else {
+            p.error_and_bump(format!("expected a parameter, found {}", p.describe_current()));
+        }
+    }
+    p.expect(SyntaxKind::RParen);
+    p.finish_node();
+}
+
+fn param(p: &mut Parser) {
+    p.start(SyntaxKind::Param);
+    p.eat(SyntaxKind::DotDotDot);
+    if p.at(SyntaxKind::SelfKw) {
+        p.start(SyntaxKind::Name);
+        p.bump();
+        p.finish_node();
+    }
+    else {
+        pattern(p);
+    }
+    if p.eat(SyntaxKind::Colon) {
+        type_ref(p);
+    }
+    if p.eat(SyntaxKind::Eq) && !expr(p) {
+        p.error("expected a default value");
+    }
+    p.finish_node();
+}
+
+fn field_decl(p: &mut Parser) {
+    member_name(p);
+    if p.eat(SyntaxKind::Colon) {
+        type_ref(p);
+    }
+    if p.eat(SyntaxKind::Eq) && !expr(p) {
+        p.error("expected a default value");
+    }
+    if !p.eat(SyntaxKind::Comma) {
+        p.eat(SyntaxKind::Semicolon);
+    }
+}
+
+/// The name of a function or field, `self` is reserved for the receiver and skipped as an error.
+fn member_name(p: &mut Parser) {
+    match p.at(SyntaxKind::SelfKw) {
+        true => p.error_and_bump("expected a member name, found `self`"),
+        false => name(p),
+    }
+}
+
+fn type_alias_decl(p: &mut Parser) {
+    p.bump();
+    name(p);
+    if p.at(SyntaxKind::Lt) {
+        generic_params(p);
+    }
+    if p.expect(SyntaxKind::Eq) {
+        type_ref(p);
+    }
+    p.eat(SyntaxKind::Semicolon);
+}
+
+/// `#[path(tokens)]` or `@path(tokens)`
+fn attribute(p: &mut Parser) {
+    p.start(SyntaxKind::Attribute);
+    if p.eat(SyntaxKind::Hash) {
+        p.expect(SyntaxKind::LBracket);
+        path(p, false);
+        if p.at(SyntaxKind::LParen) {
+            token_tree(p);
+        }
+        p.expect(SyntaxKind::RBracket);
+    }
+    else {
+        p.bump();
+        path(p, false);
+        if p.at(SyntaxKind::LParen) && !p.newline_before() {
+            token_tree(p);
+        }
+    }
+    p.finish_node();
+}
+
+/// Balanced delimiters with arbitrary tokens inside.
+fn token_tree(p: &mut Parser) {
+    p.start(SyntaxKind::TokenTree);
+    let close = match p.current() {
+        SyntaxKind::LParen => SyntaxKind::RParen,
+        SyntaxKind::LBracket => SyntaxKind::RBracket,
+        _ => SyntaxKind::RBrace,
+    };
+    p.bump();
+    while !p.at(close) && !p.at_eof() {
+        match p.current() {
+            SyntaxKind::LParen | SyntaxKind::LBracket | SyntaxKind::LBrace => token_tree(p),
+            SyntaxKind::RParen | SyntaxKind::RBracket | SyntaxKind::RBrace => break,
+            _ => p.bump(),
+        }
+    }
+    p.expect(close);
+    p.finish_node();
+}
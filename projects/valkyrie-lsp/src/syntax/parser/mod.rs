@@ -0,0 +1,241 @@
+use std::ops::Range;
+
+use super::{
+    SyntaxError,
+    green::{Checkpoint, GreenBuilder, GreenElement, GreenNode},
+};
+use crate::{SyntaxKind, Token};
+
+mod expressions;
+mod items;
+mod types;
+
+/// A recursive descent parser building a green tree, trivia is attached to the innermost open node.
+pub(super) struct Parser<'i> {
+    text: &'i str,
+    tokens: &'i [Token],
+    /// Indices of the non-trivia tokens.
+    significant: Vec<usize>,
+    /// The index in `significant` of the current token.
+    cursor: usize,
+    /// The number of tokens, trivia included, already added to the tree.
+    emitted: usize,
+    builder: GreenBuilder,
+    errors: Vec<SyntaxError>,
+}
+
+/// The node a fragment of text can be parsed as on its own.
+#[derive(Copy, Clone, Debug, Eq, PartialEq)]
+pub(super) enum Fragment {
+    /// A whole file.
+    SourceFile,
+    /// A braced block of statements.
+    BlockExpr,
+    /// The braced body of a class, trait or imply.
+    ItemList,
+    /// The braced body of a namespace.
+    NamespaceBody,
+}
+
+/// Parse tokens as the fragment, returns the tree and the errors relative to the text.
+///
+/// A braced fragment must be closed by its last token, otherwise it would not be parsed as one node in a whole file,
+/// none is returned in that case.
+pub(super) fn parse_fragment(text: &str, tokens: &[Token], fragment: Fragment) -> Option<(GreenNode, Vec<SyntaxError>)> {
+    let mut p = Parser::new(text, tokens);
+    let kind = match fragment {
+        Fragment::SourceFile => {
+            items::source_file(&mut p);
+            return Some(p.finish());
+        }
+        Fragment::BlockExpr => SyntaxKind::BlockExpr,
+        Fragment::ItemList | Fragment::NamespaceBody => SyntaxKind::ItemList,
+    };
+    if tokens.first().map(|token| token.kind) != Some(SyntaxKind::LBrace) {
+        return None;
+    }
+    p.start_root(kind);
+    match fragment {
+        Fragment::BlockExpr => expressions::block_body(&mut p),
+        Fragment::NamespaceBody => items::item_list_body(&mut p, items::Context::File),
+        _ => items::item_list_body(&mut p, items::Context::Declaration),
+    }
+    if !p.at_eof() || p.emitted != tokens.len() {
+        return None;
+    }
+    p.finish_root();
+    let (green, errors) = p.finish();
+    match green.children().last() {
+        Some(GreenElement::Token(token)) if token.kind() == SyntaxKind::RBrace => Some((green, errors)),
+        _ => None,
+    }
+}
+
+impl<'i> Parser<'i> {
+    fn new(text: &'i str, tokens: &'i [Token]) -> Self {
+        let significant = tokens.iter().enumerate().filter(|(_, t)| !t.kind.is_trivia()).map(|(i, _)| i).collect();
+        Parser { text, tokens, significant, cursor: 0, emitted: 0, builder: GreenBuilder::default(), errors: vec![] }
+    }
+    fn finish(self) -> (GreenNode, Vec<SyntaxError>) {
+        (self.builder.finish(), self.errors)
+    }
+    /// The kind of the current token.
+    fn current(&self) -> SyntaxKind {
+        self.nth(0)
+    }
+    /// The kind of the token n steps ahead, trivia skipped.
+    fn nth(&self, n: usize) -> SyntaxKind {
+        match self.significant.get(self.cursor + n) {
+            Some(index) => self.tokens[*index].kind,
+            None => SyntaxKind::Eof,
+        }
+    }
+    /// The text of the current token.
+    fn current_text(&self) -> &'i str {
+        match self.significant.get(self.cursor) {
+            Some(index) => &self.text[self.tokens[*index].span.clone()],
+            None => "",
+        }
+    }
+    fn at(&self, kind: SyntaxKind) -> bool {
+        self.current() == kind
+    }
+    fn at_any(&self, kinds: &[SyntaxKind]) -> bool {
+        kinds.contains(&self.current())
+    }
+    fn at_eof(&self) -> bool {
+        self.at(SyntaxKind::Eof)
+    }
+    /// The number of significant tokens consumed so far.
+    fn position(&self) -> usize {
+        self.cursor
+    }
+    /// Check if the current token is `>` immediately followed by the kind, without trivia in between.
+    fn at_glued_gt(&self, next: SyntaxKind) -> bool {
+        if !self.at(SyntaxKind::Gt) || self.nth(1) != next {
+            return false;
+        }
+        let first = self.significant[self.cursor];
+        let second = self.significant[self.cursor + 1];
+        self.tokens[first].span.end == self.tokens[second].span.start
+    }
+    /// Check if a line break separates the previous and the current token.
+    fn newline_before(&self) -> bool {
+        let end = match self.significant.get(self.cursor) {
+            Some(s) => *s,
+            None => self.tokens.len(),
+        };
+        let start = match self.cursor.checked_sub(1) {
+            Some(previous) => self.significant[previous] + 1,
+            None => 0,
+        };
+        self.tokens[start..end].iter().any(|token| {
+            let text = &self.text[token.span.clone()];
+            token.kind == SyntaxKind::Whitespace && (text.contains('\n') || text.contains('\r'))
+        })
+    }
+    /// Add pending trivia to the current node.
+    fn skip_trivia(&mut self) {
+        let next = self.significant.get(self.cursor).copied().unwrap_or(self.tokens.len());
+        while self.emitted < next {
+            let token = &self.tokens[self.emitted];
+            self.builder.token(token.kind, &self.text[token.span.clone()]);
+            self.emitted += 1;
+        }
+    }
+    /// Add the current token to the current node.
+    fn bump(&mut self) {
+        if self.at_eof() {
+            return;
+        }
+        self.skip_trivia();
+        let token = &self.tokens[self.emitted];
+        self.builder.token(token.kind, &self.text[token.span.clone()]);
+        self.emitted += 1;
+        self.cursor += 1;
+    }
+    fn eat(&mut self, kind: SyntaxKind) -> bool {
+        let eaten = self.at(kind);
+        if eaten {
+            self.bump();
+        }
+        eaten
+    }
+    /// Eat the token, or report it missing.
+    fn expect(&mut self, kind: SyntaxKind) -> bool {
+        if self.eat(kind) {
+            return true;
+        }
+        let expected = match kind.as_str() {
+            Some(s) => format!("`{}`", s),
+            None => format!("{:?}", kind).to_lowercase(),
+        };
+        self.error(format!("expected {}", expected));
+        false
+    }
+    fn start(&mut self, kind: SyntaxKind) {
+        self.skip_trivia();
+        self.builder.start_node(kind);
+    }
+    fn start_root(&mut self, kind: SyntaxKind) {
+        self.builder.start_node(kind);
+    }
+    fn finish_node(&mut self) {
+        self.builder.finish_node();
+    }
+    /// Finish the root node, trailing trivia included.
+    fn finish_root(&mut self) {
+        self.skip_trivia();
+        self.builder.finish_node();
+    }
+    fn checkpoint(&mut self) -> Checkpoint {
+        self.skip_trivia();
+        self.builder.checkpoint()
+    }
+    fn start_at(&mut self, checkpoint: Checkpoint, kind: SyntaxKind) {
+        self.builder.start_node_at(checkpoint, kind);
+    }
+    /// The span errors at the current token are reported at.
+    ///
+    /// Missing tokens are reported right after the previous token, which is where the user types next.
+    fn error_span(&self) -> Range<usize> {
+        match self.cursor.checked_sub(1).and_then(|previous| self.significant.get(previous)) {
+            Some(previous) => {
+                let end = self.tokens[*previous].span.end;
+                end..end
+            }
+            None => 0..0,
+        }
+    }
+    fn error(&mut self, message: impl Into<String>) {
+        let span = self.error_span();
+        self.errors.push(SyntaxError { span, message: message.into() });
+    }
+    /// Report the current token as unexpected and wrap it in an error node.
+    fn error_and_bump(&mut self, message: impl Into<String>) {
+        let span = match self.significant.get(self.cursor) {
+            Some(index) => self.tokens[*index].span.clone(),
+            None => self.error_span(),
+        };
+        self.errors.push(SyntaxError { span, message: message.into() });
+        self.start(SyntaxKind::Error);
+        self.bump();
+        self.finish_node();
+    }
+    /// Report the current token as unexpected, it is only consumed if it does not belong to the recovery set.
+    fn error_recover(&mut self, message: impl Into<String>, recovery: &[SyntaxKind]) {
+        if self.at_any(recovery) || self.at_any(&[SyntaxKind::LBrace, SyntaxKind::RBrace]) || self.at_eof() {
+            self.error(message);
+        }
+        else {
+            self.error_and_bump(message);
+        }
+    }
+    /// Describe the current token for error messages.
+    fn describe_current(&self) -> String {
+        match self.current() {
+            SyntaxKind::Eof => "end of file".to_string(),
+            _ => format!("`{}`", self.current_text()),
+        }
+    }
+}
@@ -0,0 +1,206 @@
+use super::{Parser, expressions::literal};
+use crate::SyntaxKind;
+
+/// The name of a declaration.
+pub(super) fn name(p: &mut Parser) {
+    if p.at(SyntaxKind::Ident) {
+        p.start(SyntaxKind::Name);
+        p.bump();
+        p.finish_node();
+    }
+    else {
+        p.error(format!("expected a name, found {}", p.describe_current()));
+    }
+}
+
+fn name_ref(p: &mut Parser) {
+    p.start(SyntaxKind::NameRef);
+    p.bump();
+    p.finish_node();
+}
+
+/// Check if the current token can be a path segment.
+fn at_segment(p: &Parser) -> bool {
+    p.at_any(&[SyntaxKind::Ident, SyntaxKind::SelfKw, SyntaxKind::SelfTypeKw])
+}
+
+/// `a::b::c`, with `allow_dot` the namespace style `a.b.c` is accepted too.
+///
+/// A trailing separator not followed by a segment is left for the caller, `using a::{b}` needs it.
+pub(super) fn path(p: &mut Parser, allow_dot: bool) {
+    p.start(SyntaxKind::Path);
+    segment(p, false);
+    while (p.at(SyntaxKind::ColonColon) || (allow_dot && p.at(SyntaxKind::Dot))) && p.nth(1) == SyntaxKind::Ident {
+        p.bump();
+        segment(p, false);
+    }
+    p.finish_node();
+}
+
+fn segment(p: &mut Parser, generic: bool) {
+    p.start(SyntaxKind::PathSegment);
+    if at_segment(p) {
+        name_ref(p);
+    }
+    else {
+        p.error(format!("expected a name, found {}", p.describe_current()));
+    }
+    if generic && p.at(SyntaxKind::Lt) {
+        generic_args(p);
+    }
+    p.finish_node();
+}
+
+/// `<T, U: Bound + Other>`
+pub(super) fn generic_params(p: &mut Parser) {
+    p.start(SyntaxKind::GenericParamList);
+    p.bump();
+    while !p.at(SyntaxKind::Gt) && !p.at_eof() {
+        if p.at(SyntaxKind::Ident) {
+            p.start(SyntaxKind::GenericParam);
+            name(p);
+            if p.eat(SyntaxKind::Colon) {
+                type_ref(p);
+                while p.eat(SyntaxKind::Plus) {
+                    type_ref(p);
+                }
+            }
+            p.finish_node();
+            if !p.at(SyntaxKind::Gt) {
+                p.expect(SyntaxKind::Comma);
+            }
+        }
+        else {
+            p.error_recover(
+                format!("expected a generic parameter, found {}", p.describe_current()),
+                &[SyntaxKind::LParen, SyntaxKind::Colon],
+            );
+            if p.at(SyntaxKind::LParen) || p.at(SyntaxKind::Colon) || p.at(SyntaxKind::LBrace) || p.at(SyntaxKind::RBrace) {
+                break;
+            }
+        }
+    }
+    p.expect(SyntaxKind::Gt);
+    p.finish_node();
+}
+
+fn generic_args(p: &mut Parser) {
+    p.start(SyntaxKind::GenericArgList);
+    p.bump();
+    while !p.at(SyntaxKind::Gt) && !p.at_eof() {
+        if at_type_start(p) {
+            type_ref(p);
+            if !p.at(SyntaxKind::Gt) {
+                p.expect(SyntaxKind::Comma);
+            }
+        }
+        else {
+            break;
+        }
+    }
+    p.expect(SyntaxKind::Gt);
+    p.finish_node();
+}
+
+fn at_type_start(p: &Parser) -> bool {
+    at_segment(p) || p.at(SyntaxKind::LParen)
+}
+
+/// A type reference, `a::B<C>`, `(A, B)` or `(A) -> B`.
+pub(super) fn type_ref(p: &mut Parser) {
+    if at_segment(p) {
+        p.start(SyntaxKind::PathType);
+        p.start(SyntaxKind::Path);
+        segment(p, true);
+        while (p.at(SyntaxKind::ColonColon) || p.at(SyntaxKind::Dot)) && p.nth(1) == SyntaxKind::Ident {
+            p.bump();
+            segment(p, true);
+        }
+        p.finish_node();
+        p.finish_node();
+    }
+    else if p.at(SyntaxKind::LParen) {
+        let checkpoint = p.checkpoint();
+        p.start(SyntaxKind::TupleType);
+        p.bump();
+        while !p.at(SyntaxKind::RParen) && !p.at_eof() && at_type_start(p) {
+            type_ref(p);
+            if !p.at(SyntaxKind::RParen) {
+                p.expect(SyntaxKind::Comma);
+            }
+        }
+        p.expect(SyntaxKind::RParen);
+        p.finish_node();
+        if p.at(SyntaxKind::Arrow) {
+            p.start_at(checkpoint, SyntaxKind::FunctionType);
+            p.bump();
+            type_ref(p);
+            p.finish_node();
+        }
+    }
+    else {
+        p.error(format!("expected a type, found {}", p.describe_current()));
+    }
+}
+
+/// A binding pattern, `a`, `mut a`, `(a, b)`, `Some(a)` or a literal.
+pub(super) fn pattern(p: &mut Parser) {
+    match p.current() {
+        SyntaxKind::MutKw => {
+            p.start(SyntaxKind::IdentPat);
+            p.bump();
+            name(p);
+            p.finish_node();
+        }
+        SyntaxKind::Ident if matches!(p.nth(1), SyntaxKind::LParen | SyntaxKind::ColonColon) => {
+            p.start(SyntaxKind::VariantPat);
+            path(p, false);
+            if p.at(SyntaxKind::LParen) {
+                tuple_pattern(p);
+            }
+            p.finish_node();
+        }
+        SyntaxKind::Ident => {
+            p.start(SyntaxKind::IdentPat);
+            name(p);
+            p.finish_node();
+        }
+        SyntaxKind::LParen => tuple_pattern(p),
+        SyntaxKind::Minus if matches!(p.nth(1), SyntaxKind::Integer | SyntaxKind::Decimal) => {
+            p.start(SyntaxKind::LiteralPat);
+            p.bump();
+            literal(p);
+            p.finish_node();
+        }
+        kind if kind.is_literal() => {
+            p.start(SyntaxKind::LiteralPat);
+            literal(p);
+            p.finish_node();
+        }
+        _ => p.error(format!("expected a pattern, found {}", p.describe_current())),
+    }
+}
+
+fn tuple_pattern(p: &mut Parser) {
+    p.start(SyntaxKind::TuplePat);
+    p.bump();
+    while !p.at(SyntaxKind::RParen) && !p.at_eof() {
+        let before = p.cursor;
+        pattern(p);
+        if p.cursor == before {
+            // the missing pattern is already reported
+            if p.at_any(&[SyntaxKind::Eq, SyntaxKind::InKw, SyntaxKind::FatArrow, SyntaxKind::LBrace, SyntaxKind::RBrace]) {
+                break;
+            }
+            p.start(SyntaxKind::Error);
+            p.bump();
+            p.finish_node();
+            continue;
+        }
+        if !p.at(SyntaxKind::RParen) {
+            p.expect(SyntaxKind::Comma);
+        }
+    }
+    p.expect(SyntaxKind::RParen);
+    p.finish_node();
+}
@@ -0,0 +1,141 @@
+use std::ops::Range;
+
+use super::{
+    GreenToken, Parse, SyntaxError, SyntaxNode, TokenAtOffset,
+    parser::{Fragment, parse_fragment},
+};
+use crate::{SyntaxKind, lex};
+
+impl Parse {
+    /// Update the tree after `delete` of the old text was replaced by `insert`, `text` is the whole new text.
+    ///
+    /// Only the edited token, or else the innermost braced block or body around the edit, is parsed again, the rest of
+    /// the tree is shared with the old one. The whole text is parsed again if neither applies.
+    pub fn reparse(&self, delete: Range<usize>, insert: &str, text: &str) -> Parse {
+        let root = self.syntax();
+        reparse_token(self, &root, delete.clone(), insert)
+            .or_else(|| reparse_block(self, &root, delete, insert, text))
+            .unwrap_or_else(|| Parse::new(text))
+    }
+}
+
+/// Replace a single token if the edit keeps its kind and its boundaries with the neighbours.
+fn reparse_token(old: &Parse, root: &SyntaxNode, delete: Range<usize>, insert: &str) -> Option<Parse> {
+    let token = match root.token_at_offset(delete.start) {
+        TokenAtOffset::None => return None,
+        TokenAtOffset::Single(token) => token,
+        TokenAtOffset::Between(left, right) => match delete.is_empty() && is_relexable(left.kind()) {
+            true => left,
+            false => right,
+        },
+    };
+    let span = token.span();
+    if !is_relexable(token.kind()) || delete.end > span.end {
+        return None;
+    }
+    let mut new_text = token.text().to_string();
+    new_text.replace_range(delete.start - span.start..delete.end - span.start, insert);
+    if token.kind() == SyntaxKind::Whitespace && has_newline(token.text()) != has_newline(&new_text) {
+        return None;
+    }
+    // the neighbours must still end and start at the same places
+    let prev = token.prev_token();
+    let next = token.next_token();
+    let mut context = String::new();
+    let mut expected = vec![];
+    for (part, kind) in [
+        (prev.as_ref().map(|t| t.text()), prev.as_ref().map(|t| t.kind())),
+        (Some(new_text.as_str()), Some(token.kind())),
+        (next.as_ref().map(|t| t.text()), next.as_ref().map(|t| t.kind())),
+    ] {
+        if let (Some(part), Some(kind)) = (part, kind) {
+            context.push_str(part);
+            expected.push((kind, context.len()));
+        }
+    }
+    let lexed = lex(&context);
+    if !lexed.errors.is_empty() || lexed.tokens.iter().map(|t| (t.kind, t.span.end)).ne(expected) {
+        return None;
+    }
+    // errors may quote the token, they are reported from the end of the previous significant token
+    let mut guard = 0;
+    let mut previous = prev;
+    while let Some(token) = previous {
+        if !token.kind().is_trivia() {
+            guard = token.span().end;
+            break;
+        }
+        previous = token.prev_token();
+    }
+    if old.errors.iter().any(|e| e.span.end >= guard && e.span.start <= span.end) {
+        return None;
+    }
+    let green = token.replace_with(GreenToken::new(token.kind(), &new_text));
+    let errors = shift_errors(&old.errors, span.end, new_text.len() as isize - span.len() as isize);
+    Some(Parse { green, errors })
+}
+
+/// Parse the innermost braced block or declaration body that contains the whole edit.
+fn reparse_block(old: &Parse, root: &SyntaxNode, delete: Range<usize>, insert: &str, text: &str) -> Option<Parse> {
+    let (node, fragment) = root.covering_node(delete.clone()).ancestors().find_map(|node| {
+        let span = node.span();
+        if span.start >= delete.start || delete.end >= span.end {
+            return None;
+        }
+        let fragment = match node.kind() {
+            SyntaxKind::BlockExpr => Fragment::BlockExpr,
+            SyntaxKind::ItemList => match node.parent().map(|parent| parent.kind()) {
+                Some(SyntaxKind::NamespaceDecl) => Fragment::NamespaceBody,
+                Some(SyntaxKind::ClassDecl | SyntaxKind::TraitDecl | SyntaxKind::ImplyDecl) => Fragment::ItemList,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some((node, fragment))
+    })?;
+    let span = node.span();
+    // an error touching the boundary of the block may change with it, only a full parse can tell
+    let touches = |e: &SyntaxError| e.span.start <= span.end && e.span.end >= span.start;
+    let inside = |e: &SyntaxError| e.span.start > span.start && e.span.end < span.end;
+    if old.errors.iter().any(|e| touches(e) && !inside(e)) {
+        return None;
+    }
+    let delta = insert.len() as isize - delete.len() as isize;
+    let new_span = span.start..(span.end as isize + delta) as usize;
+    let fragment_text = text.get(new_span.clone())?;
+    let lexed = lex(fragment_text);
+    let (green, parse_errors) = parse_fragment(fragment_text, &lexed.tokens, fragment)?;
+    let mut inner: Vec<SyntaxError> =
+        lexed.errors.into_iter().map(|e| SyntaxError { span: e.span, message: e.message }).collect();
+    inner.extend(parse_errors);
+    inner.sort_by_key(|e| e.span.start);
+    let mut errors: Vec<SyntaxError> = old.errors.iter().filter(|e| e.span.end <= span.start).cloned().collect();
+    errors.extend(inner.into_iter().map(|e| SyntaxError { span: e.span.start + span.start..e.span.end + span.start, ..e }));
+    let after: Vec<SyntaxError> = old.errors.iter().filter(|e| e.span.start >= span.end).cloned().collect();
+    errors.extend(shift_errors(&after, span.end, delta));
+    Some(Parse { green: node.replace_with(green), errors })
+}
+
+/// Tokens whose content can change without changing the structure around them.
+fn is_relexable(kind: SyntaxKind) -> bool {
+    matches!(
+        kind,
+        SyntaxKind::Ident | SyntaxKind::Whitespace | SyntaxKind::LineComment | SyntaxKind::DocComment | SyntaxKind::String
+    )
+}
+
+fn has_newline(text: &str) -> bool {
+    text.contains('\n') || text.contains('\r')
+}
+
+/// Move the errors starting at or after the offset.
+fn shift_errors(errors: &[SyntaxError], offset: usize, delta: isize) -> Vec<SyntaxError> {
+    let shift = |position: usize| (position as isize + delta) as usize;
+    errors
+        .iter()
+        .map(|e| match e.span.start >= offset {
+            true => SyntaxError { span: shift(e.span.start)..shift(e.span.end), message: e.message.clone() },
+            false => e.clone(),
+        })
+        .collect()
+}
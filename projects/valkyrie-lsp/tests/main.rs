@@ -1,4 +1,1062 @@
+use std::{
+    collections::HashMap,
+    future::Future,
+    sync::{Arc, Mutex},
+};
+
+use futures::{SinkExt, StreamExt};
+use serde_json::{Value, json};
+use tower::{Service, ServiceExt};
+use tower_lsp::{
+    LspService,
+    jsonrpc::{self, ErrorCode, Request, Response},
+    lsp_types::{Position, Range, TextDocumentContentChangeEvent, Url},
+};
+use valkyrie_lsp::{
+    Associativity, Color, ColorSchema, Database, Definition, DocumentFormat, DocumentStore, Fixity, KeywordDoc, LineIndex,
+    OPERATOR_DOCS, OperatorDoc, Parse, PositionEncoding, Settings, SymbolIndex, SyntaxKind, ValkyrieErrorKind,
+    ValkyrieLanguageServer,
+    ast::{self, AstNode, HasDocComments, HasModifiers, HasName},
+    lex,
+};
+
 #[test]
 fn ready() {
     println!("it works!")
 }
+
+/// A server driven through json-rpc messages, everything it sends to the client is recorded.
+struct TestServer {
+    service: LspService<ValkyrieLanguageServer>,
+    sent: Arc<Mutex<Vec<Request>>>,
+    /// The results of requests of the server by method, null for others.
+    responses: Arc<Mutex<HashMap<String, Value>>>,
+    id: i64,
+}
+
+impl TestServer {
+    /// Start a server and initialize it with the client capabilities.
+    async fn initialize(capabilities: Value) -> (TestServer, Value) {
+        Self::initialize_with(json!({ "capabilities": capabilities })).await
+    }
+    /// Start a server and initialize it with the params.
+    async fn initialize_with(params: Value) -> (TestServer, Value) {
+        let (service, mut socket) = ValkyrieLanguageServer::launch();
+        let sent = Arc::new(Mutex::new(vec![]));
+        let responses: Arc<Mutex<HashMap<String, Value>>> = Arc::default();
+        let record = sent.clone();
+        let results = responses.clone();
+        tokio::spawn(async move {
+            // requests of the server such as progress creation succeed with null
+            while let Some(request) = socket.next().await {
+                if let Some(id) = request.id().cloned() {
+                    let result = results.lock().unwrap().get(request.method()).cloned().unwrap_or_default();
+                    socket.send(Response::from_ok(id, result)).await.unwrap();
+                }
+                record.lock().unwrap().push(request);
+            }
+        });
+        let mut server = TestServer { service, sent, responses, id: 0 };
+        let result = server.request("initialize", params).await.unwrap();
+        server.notify("initialized", json!({})).await;
+        (server, result)
+    }
+    async fn request(&mut self, method: &'static str, params: Value) -> jsonrpc::Result<Value> {
+        self.start(method, params).await.await
+    }
+    /// Send a request without waiting for the response, its id is the current `id`.
+    async fn start(&mut self, method: &'static str, params: Value) -> impl Future<Output = jsonrpc::Result<Value>> + use<> {
+        self.id += 1;
+        let request = Request::build(method).params(params).id(self.id).finish();
+        let response = self.service.ready().await.unwrap().call(request);
+        async move { response.await.unwrap().expect("a request has a response").into_parts().1 }
+    }
+    async fn notify(&mut self, method: &'static str, params: Value) {
+        let request = Request::build(method).params(params).finish();
+        self.service.ready().await.unwrap().call(request).await.unwrap();
+    }
+    async fn open(&mut self, uri: &str, text: &str) {
+        let document = json!({ "uri": uri, "languageId": "valkyrie", "version": 1, "text": text });
+        self.notify("textDocument/didOpen", json!({ "textDocument": document })).await;
+    }
+    /// Wait until the server finished indexing the number of folders, for clients with progress support.
+    async fn indexed(&self, folders: usize) {
+        for _ in 0..500 {
+            let ends = self.sent("$/progress").iter().filter(|progress| progress["value"]["kind"] == "end").count();
+            if ends >= folders {
+                return;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("indexing did not finish");
+    }
+    /// Answer the requests of the server with the method with a result.
+    fn respond(&self, method: &str, result: Value) {
+        self.responses.lock().unwrap().insert(method.to_string(), result);
+    }
+    /// Wait until the server sent the number of messages with the method, returns their params.
+    async fn wait_sent(&self, method: &str, count: usize) -> Vec<Value> {
+        for _ in 0..500 {
+            let sent = self.sent(method);
+            if sent.len() >= count {
+                return sent;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+        panic!("the server did not send {} `{}`", count, method);
+    }
+    /// The params of all messages with the method the server sent so far.
+    fn sent(&self, method: &str) -> Vec<Value> {
+        let sent = self.sent.lock().unwrap();
+        sent.iter()
+            .filter(|request| request.method() == method)
+            .map(|request| request.params().cloned().unwrap_or_default())
+            .collect()
+    }
+}
+
+/// Create a fresh directory with the files, paths are relative to it.
+fn temp_tree(name: &str, files: &[(&str, &str)]) -> std::path::PathBuf {
+    let root = std::env::temp_dir().join(format!("valkyrie-lsp-{}-{name}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    for (path, text) in files {
+        let path = root.join(path);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, text).unwrap();
+    }
+    root
+}
+
+fn change(start: (u32, u32), end: (u32, u32), text: &str) -> TextDocumentContentChangeEvent {
+    TextDocumentContentChangeEvent {
+        range: Some(Range { start: Position::new(start.0, start.1), end: Position::new(end.0, end.1) }),
+        range_length: None,
+        text: text.to_string(),
+    }
+}
+
+#[test]
+fn incremental_sync() {
+    let store = DocumentStore::default();
+    let uri = Url::parse("file:///main.vk").unwrap();
+    store.open(uri.clone(), "valkyrie".to_string(), 1, "let a = 1;\nlet b = 2;\n".to_string());
+    let changes = vec![change((0, 4), (0, 5), "alpha"), change((1, 8), (1, 9), "a + 1")];
+    store.change(&uri, 2, changes).unwrap();
+    assert_eq!(store.get(&uri).unwrap().text, "let alpha = 1;\nlet b = a + 1;\n");
+    let error = store.change(&uri, 2, vec![change((0, 0), (0, 0), "stale")]).unwrap_err();
+    assert!(matches!(error.kind(), ValkyrieErrorKind::StaleVersion { current: 2, received: 2, .. }), "{:?}", error);
+    assert_eq!(jsonrpc::Error::from(error).code, jsonrpc::ErrorCode::ContentModified);
+    assert_eq!(store.version(&uri), Some(2));
+    store.close(&uri);
+    assert!(!store.contains(&uri));
+    let error = store.change(&uri, 3, vec![]).unwrap_err();
+    assert_eq!(jsonrpc::Error::from(error).code, jsonrpc::ErrorCode::InvalidParams);
+}
+
+#[test]
+fn line_index_encodings() {
+    let text = "let 名前 = \"😀!\";\r\nlet b = 名前;";
+    let emoji = text.find('!').unwrap();
+    let utf8 = LineIndex::new(text, PositionEncoding::Utf8);
+    let utf16 = LineIndex::new(text, PositionEncoding::Utf16);
+    let utf32 = LineIndex::new(text, PositionEncoding::Utf32);
+    assert_eq!(utf8.position(emoji), Position::new(0, 18));
+    assert_eq!(utf16.position(emoji), Position::new(0, 12));
+    assert_eq!(utf32.position(emoji), Position::new(0, 11));
+    for index in [&utf8, &utf16, &utf32] {
+        assert_eq!(index.offset(index.position(emoji)), emoji);
+        let second = text.rfind("名前").unwrap();
+        assert_eq!(index.position(second).line, 1);
+        assert_eq!(index.offset(index.position(second)), second);
+    }
+    // a position inside the surrogate pair snaps to the start of the emoji
+    assert_eq!(utf16.offset(Position::new(0, 11)), emoji - '😀'.len_utf8());
+    // past the line end clamps before the terminator
+    assert_eq!(utf16.offset(Position::new(0, 99)), text.find('\r').unwrap());
+}
+
+#[test]
+fn lexer_is_lossless() {
+    let text = "/// 文档\nmicro add(a: i32, b) -> i32 { a ** 2 + 0x1F_u8 ..< 1.5e-3 } /* open /* nested */ */ `q x` \"s\\\"\" ¤ 'unterminated";
+    let lexed = lex(text);
+    let mut rebuilt = String::new();
+    for token in &lexed.tokens {
+        rebuilt.push_str(&text[token.span.clone()]);
+    }
+    assert_eq!(rebuilt, text);
+    let kinds: Vec<SyntaxKind> = lexed.tokens.iter().map(|t| t.kind).filter(|k| !k.is_trivia()).collect();
+    assert_eq!(&kinds[..3], &[SyntaxKind::MicroKw, SyntaxKind::Ident, SyntaxKind::LParen]);
+    assert!(kinds.contains(&SyntaxKind::StarStar));
+    assert!(kinds.contains(&SyntaxKind::DotDotLt));
+    assert!(kinds.contains(&SyntaxKind::Decimal));
+    assert_eq!(lexed.tokens[0].kind, SyntaxKind::DocComment);
+    assert_eq!(kinds.iter().filter(|k| **k == SyntaxKind::Error).count(), 1);
+    assert_eq!(lexed.errors.len(), 2);
+}
+
+const SAMPLE: &str = r#"namespace demo.shapes;
+
+using std::math::{sqrt, PI as pi};
+
+/// A point in the plane.
+public class Point<T>: Shape {
+    x: T = 0,
+    y: T,
+    /// The distance to the origin.
+    length(self) -> f64 {
+        sqrt(self.x ** 2 + self.y ** 2)
+    }
+}
+
+trait Shape {
+    area(self) -> f64;
+}
+
+imply Point<f64>: Shape {
+    area(self) -> f64 { 0.0 }
+}
+
+micro main() {
+    let p = new Point(1, 2);
+    if p.length() > pi { print("far") } else { print("near") }
+}
+"#;
+
+#[test]
+fn parse_declarations() {
+    let parse = Parse::new(SAMPLE);
+    assert_eq!(parse.syntax().text(), SAMPLE);
+    assert!(parse.errors().is_empty(), "{:?}", parse.errors());
+    let file = parse.tree();
+    let names: Vec<String> = file.namespace().and_then(|n| n.path()).unwrap().to_names();
+    assert_eq!(names, ["demo", "shapes"]);
+    let items: Vec<ast::Item> = file.items().collect();
+    assert_eq!(items.len(), 6);
+    let ast::Item::Class(point) = &items[2]
+    else {
+        panic!("expected a class, found {:?}", items[2])
+    };
+    assert_eq!(point.name().unwrap().text(), "Point");
+    assert!(point.has_modifier(SyntaxKind::PublicKw));
+    assert_eq!(point.doc_comment().as_deref(), Some("A point in the plane."));
+    assert_eq!(point.fields().len(), 2);
+    let length = &point.methods()[0];
+    assert_eq!(length.doc_comment().as_deref(), Some("The distance to the origin."));
+    assert!(length.params()[0].is_self());
+    let tail = length.body().and_then(|body| body.tail_expr()).unwrap();
+    assert!(matches!(tail, ast::Expr::Call(_)));
+    let ast::Item::Imply(imply) = &items[4]
+    else {
+        panic!("expected an imply, found {:?}", items[4])
+    };
+    assert_eq!(imply.self_type().unwrap().syntax().text(), "Point<f64>");
+    let ast::Item::Trait(shape) = &items[3]
+    else {
+        panic!("expected a trait, found {:?}", items[3])
+    };
+    assert!(shape.methods()[0].body().is_none());
+}
+
+#[test]
+fn parse_recovers_from_broken_code() {
+    let text = "micro f(a: \nclass X {\n    y: i32\n}\nmicro g() { let = 1; }\n";
+    let parse = Parse::new(text);
+    assert_eq!(parse.syntax().text(), text);
+    assert!(!parse.errors().is_empty());
+    let names: Vec<String> = parse.tree().items().filter_map(|item| item.name()).map(|name| name.text()).collect();
+    assert_eq!(names, ["f", "X", "g"]);
+}
+
+#[test]
+fn self_member_recovery() {
+    // `self` can not name a member, it is skipped instead of stopping the member list
+    for text in ["class{self", "class X {\n    self\n}", "class X { self(x) }", "class X { self }"] {
+        let parse = Parse::new(text);
+        assert_eq!(parse.syntax().text(), text);
+        assert!(parse.errors().iter().any(|e| e.message == "expected a member name, found `self`"), "{:?}", parse.errors());
+    }
+    // typing it in a class body goes through the incremental path
+    let mut text = "class X {  }".to_string();
+    let mut parse = Parse::new(&text);
+    let mut offset = text.find("  }").unwrap() + 1;
+    for c in "self".chars() {
+        text.insert(offset, c);
+        parse = parse.reparse(offset..offset, &c.to_string(), &text);
+        assert_eq!(parse, Parse::new(&text), "incremental reparse differs from a full parse");
+        offset += c.len_utf8();
+    }
+    assert_eq!(parse.syntax().text(), "class X { self }");
+}
+
+#[test]
+fn incremental_reparse() {
+    let class = |parse: &Parse| parse.tree().items().nth(2).unwrap().syntax().green().clone();
+    let mut text = SAMPLE.to_string();
+    let mut parse = Parse::new(&text);
+    let before = class(&parse);
+    // typing inside the body of `main` leaves the class untouched, every step is checked against a full parse
+    let mut offset = text.find("    if p.length()").unwrap();
+    for c in "let q = p.length(\n".chars() {
+        text.insert(offset, c);
+        parse = parse.reparse(offset..offset, &c.to_string(), &text);
+        assert_eq!(parse, Parse::new(&text), "incremental reparse differs from a full parse");
+        offset += c.len_utf8();
+    }
+    assert!(class(&parse).ptr_eq(&before));
+    assert!(!parse.errors().is_empty());
+    // comments and strings opened before they are closed swallow the text after them for a while
+    let mut at = text.find("print(\"far").unwrap();
+    for c in "/* \"x\" */".chars() {
+        text.insert(at, c);
+        parse = parse.reparse(at..at, &c.to_string(), &text);
+        assert_eq!(parse, Parse::new(&text), "incremental reparse differs from a full parse");
+        at += c.len_utf8();
+    }
+    let start = text.find("/* ").unwrap();
+    text.replace_range(start..at, "");
+    parse = parse.reparse(start..at, "", &text);
+    // deleting it again restores the original tree
+    let start = text.find("let q").unwrap();
+    text.replace_range(start..offset, "");
+    parse = parse.reparse(start..offset, "", &text);
+    assert_eq!(parse, Parse::new(SAMPLE));
+    // an unclosed block at the end reports its missing `}` where the text ends, wherever that moves to
+    for (before, edit) in [("micro f() {\n    g() {}", "g() {"), ("class X {\n    micro f() {}", "f() {")] {
+        let mut text = before.to_string();
+        let mut parse = Parse::new(&text);
+        let mut at = text.find(edit).unwrap() + edit.len();
+        for c in " h(); ".chars() {
+            text.insert(at, c);
+            parse = parse.reparse(at..at, &c.to_string(), &text);
+            assert_eq!(parse, Parse::new(&text), "incremental reparse differs from a full parse");
+            at += c.len_utf8();
+        }
+    }
+    // renaming a class changes only the name token
+    let start = text.find("Point<T>").unwrap();
+    text.replace_range(start..start + 5, "Vector");
+    parse = parse.reparse(start..start + 5, "Vector", &text);
+    assert_eq!(parse.syntax().text(), text);
+}
+
+#[test]
+fn query_database() {
+    let shapes = Url::parse("file:///shapes.vk").unwrap();
+    let app = Url::parse("file:///app.vk").unwrap();
+    let app_text = "namespace demo.app;\nusing demo::shapes::Point;\nmicro run() {\n    let p = new Point(1, 2);\n    let n = p.length();\n}\n";
+    let mut db = Database::default();
+    let shapes_id = db.set_file_text(shapes.clone(), SAMPLE.to_string(), None);
+    let app_id = db.set_file_text(app.clone(), app_text.to_string(), None);
+    let snapshot = db.snapshot();
+    let infer = snapshot.infer(app_id);
+    let point = app_text.find("Point(").unwrap();
+    let definitions = &infer.resolutions[&point].definitions;
+    let Some(Definition::Item(loc)) = definitions.first()
+    else {
+        panic!("`Point` is not resolved: {:?}", definitions)
+    };
+    assert_eq!(loc.file, shapes_id);
+    assert_eq!(snapshot.item_tree(shapes_id).item(loc.index).namepath, ["demo", "shapes", "Point"]);
+    let n = infer.locals.iter().find(|local| local.name == "n").unwrap();
+    assert_eq!(n.ty.to_string(), "f64");
+    // an edit inside a function body keeps the declarations, nothing depending on them is recomputed
+    let def_map = snapshot.def_map();
+    db.set_file_text(shapes.clone(), SAMPLE.replace("\"far\"", "\"farther\""), None);
+    let edited = db.snapshot();
+    assert!(edited.revision() > snapshot.revision());
+    assert!(Arc::ptr_eq(&edited.def_map(), &def_map));
+    assert!(Arc::ptr_eq(&edited.infer(app_id), &infer));
+    // renaming the class is seen by the other file, the old snapshot is unchanged
+    db.set_file_text(shapes, SAMPLE.replace("class Point", "class Vector"), None);
+    assert!(db.snapshot().infer(app_id).resolutions[&point].definitions.is_empty());
+    assert!(!snapshot.infer(app_id).resolutions[&point].definitions.is_empty());
+}
+
+#[test]
+fn file_diagnostics() {
+    let mut db = Database::default();
+    let shapes = db.set_file_text(Url::parse("file:///shapes.vk").unwrap(), SAMPLE.to_string(), None);
+    let text = "namespace demo.app;\nusing demo::shapes::Pont;\nclass Point {}\nclass Point {}\nmicro run( {}\n";
+    let app = db.set_file_text(Url::parse("file:///app.vk").unwrap(), text.to_string(), None);
+    let snapshot = db.snapshot();
+    // external packages such as `std` are not reported
+    assert!(snapshot.diagnostics(shapes).is_empty(), "{:?}", snapshot.diagnostics(shapes));
+    let diagnostics = snapshot.diagnostics(app);
+    let messages: Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.message.as_str()).collect();
+    assert_eq!(
+        messages[..3],
+        [
+            "cannot find `Pont` in namespace `demo::shapes`",
+            "type `demo::app::Point` is defined 2 times",
+            "type `demo::app::Point` is defined 2 times"
+        ]
+    );
+    assert_eq!(diagnostics[0].span, text.find("Pont").unwrap()..text.find("Pont").unwrap() + 4);
+    let codes: Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.code.code).collect();
+    assert_eq!(codes, ["V0002", "V0003", "V0003", "V0001"]);
+    assert!(diagnostics.len() > 3);
+}
+
+#[test]
+fn project_model() {
+    let mut db = Database::default();
+    let url = |path: &str| Url::parse(&format!("file:///ws/{path}")).unwrap();
+    let manifest = "{\n    name: 'app',\n    licence: 'MIT',\n    dependencies: {\n        utils: { path: '../utils' },\n        gone: { path: '../gone' },\n    },\n}\n";
+    let app_manifest = db.set_manifest(url("app/fleet.json5"), manifest.to_string());
+    db.set_manifest(url("utils/fleet.json5"), "{ name: 'utils', sources: ['lib'] }".to_string());
+    db.set_manifest(url("other/fleet.json5"), "{ name: 'other' }".to_string());
+    let broken_text = "{ name: 'broken',\n  version: }";
+    let broken = db.set_manifest(url("broken/fleet.json5"), broken_text.to_string());
+    db.set_file_text(url("utils/lib/helper.vk"), "namespace utils;\nclass Helper {}\n".to_string(), None);
+    db.set_file_text(url("other/src/thing.vk"), "namespace other;\nclass Thing {}\n".to_string(), None);
+    let text = "namespace app;\nusing utils::Helper;\nusing other::Thing;\n";
+    let app = db.set_file_text(url("app/src/main.vk"), text.to_string(), None);
+    let loose = db.set_file_text(url("scratch.vk"), text.to_string(), None);
+    let snapshot = db.snapshot();
+    let graph = snapshot.package_graph();
+    let names: Vec<&str> = graph.packages().iter().map(|package| package.name.as_str()).collect();
+    assert_eq!(names, ["app", "utils", "other"]);
+    let package = graph.package(snapshot.package_of(app).unwrap());
+    assert_eq!(package.name, "app");
+    assert_eq!(package.dependencies.len(), 1);
+    assert_eq!(snapshot.package_of(loose), None);
+    // only dependencies are visible to a package, files outside of all packages see everything
+    let resolved = |file| snapshot.file_scope(file).imports.iter().map(|import| import.len()).collect::<Vec<_>>();
+    assert_eq!(resolved(app), [1, 0]);
+    assert_eq!(resolved(loose), [1, 1]);
+    let diagnostics = snapshot.diagnostics(app_manifest);
+    let codes: Vec<&str> = diagnostics.iter().map(|diagnostic| diagnostic.code.code).collect();
+    assert_eq!(codes, ["V0005", "V0006"]);
+    assert_eq!(diagnostics[0].message, "unknown key `licence` in manifest");
+    assert_eq!(diagnostics[1].span.start, manifest.find("gone").unwrap());
+    let diagnostics = snapshot.diagnostics(broken);
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].code.name, "invalid-manifest");
+    assert_eq!(diagnostics[0].span.start, broken_text.rfind('}').unwrap());
+}
+
+#[tokio::test]
+async fn pull_diagnostics() {
+    let (mut server, result) = TestServer::initialize(json!({ "textDocument": { "diagnostic": {} } })).await;
+    assert_eq!(result["capabilities"]["diagnosticProvider"]["interFileDependencies"], true);
+    server.open("file:///app.vk", "micro run( {}\n").await;
+    let document = json!({ "uri": "file:///app.vk" });
+    let full = server.request("textDocument/diagnostic", json!({ "textDocument": document })).await.unwrap();
+    assert_eq!(full["kind"], "full");
+    assert!(!full["items"].as_array().unwrap().is_empty());
+    let result_id = full["resultId"].clone();
+    let again =
+        server.request("textDocument/diagnostic", json!({ "textDocument": document, "previousResultId": result_id })).await;
+    assert_eq!(again.unwrap()["kind"], "unchanged");
+    let previous = json!([{ "uri": "file:///app.vk", "value": result_id }]);
+    let workspace = server.request("workspace/diagnostic", json!({ "previousResultIds": previous })).await.unwrap();
+    assert_eq!(
+        workspace["items"],
+        json!([{ "kind": "unchanged", "uri": "file:///app.vk", "version": 1, "resultId": result_id }])
+    );
+    // a client that pulls gets nothing pushed
+    assert!(server.sent("textDocument/publishDiagnostics").is_empty());
+}
+
+#[tokio::test]
+async fn push_diagnostics() {
+    let (mut server, _) = TestServer::initialize(json!({})).await;
+    server.open("file:///app.vk", "micro run( {}\n").await;
+    let mut published = vec![];
+    for _ in 0..100 {
+        published = server.sent("textDocument/publishDiagnostics");
+        if !published.is_empty() {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(published.len(), 1);
+    assert_eq!(published[0]["version"], 1);
+    let diagnostic = &published[0]["diagnostics"][0];
+    assert_eq!(diagnostic["code"], "V0001");
+    assert_eq!(diagnostic["codeDescription"]["href"], "valkyrie-explain:V0001");
+    let explanation = server.request("valkyrie/explainDiagnostic", json!({ "code": "V0001" })).await.unwrap();
+    assert_eq!(explanation["name"], "syntax-error");
+    assert!(explanation["explanation"]["value"].as_str().unwrap().starts_with("# V0001"));
+    let unknown = server.request("valkyrie/explainDiagnostic", json!({ "code": "V9999" })).await.unwrap();
+    assert_eq!(unknown, Value::Null);
+    // changes that can not be applied are logged, nothing is analyzed again
+    let logged = server.sent("window/logMessage").len();
+    let changes =
+        json!([{ "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } }, "text": "\n" }]);
+    for document in [json!({ "uri": "file:///app.vk", "version": 1 }), json!({ "uri": "file:///closed.vk", "version": 2 })] {
+        server.notify("textDocument/didChange", json!({ "textDocument": document, "contentChanges": changes })).await;
+    }
+    server.wait_sent("window/logMessage", logged + 2).await;
+    tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+    assert_eq!(server.sent("textDocument/publishDiagnostics").len(), 1);
+}
+
+#[tokio::test]
+async fn workspace_folders() {
+    let first = temp_tree("first", &[("fleet.json5", "{ name: 'first', version: 1 }"), ("src/main.vk", "class A {}\n")]);
+    let second = temp_tree("second", &[("fleet.json5", "{ name: 'second' }"), ("src/lib.vk", "class B {}\n")]);
+    let folder = |root: &std::path::Path| json!({ "uri": Url::from_file_path(root).unwrap(), "name": "folder" });
+    let params = json!({
+        "capabilities": { "textDocument": { "diagnostic": {} }, "window": { "workDoneProgress": true } },
+        "rootUri": Url::from_file_path(&second).unwrap(),
+        "workspaceFolders": [folder(&first)],
+    });
+    let (mut server, result) = TestServer::initialize_with(params).await;
+    assert_eq!(result["capabilities"]["workspace"]["workspaceFolders"]["changeNotifications"], true);
+    server.indexed(1).await;
+    let uris = |report: Value| {
+        let mut uris: Vec<String> = report["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .filter_map(|item| Url::parse(item["uri"].as_str()?).ok()?.to_file_path().ok())
+            .map(|path| path.strip_prefix(std::env::temp_dir()).unwrap().to_string_lossy().replace('\\', "/"))
+            .collect();
+        uris.sort();
+        uris
+    };
+    // the folders take precedence over the root uri
+    let report = server.request("workspace/diagnostic", json!({ "previousResultIds": [] })).await.unwrap();
+    let first_name = first.file_name().unwrap().to_string_lossy().to_string();
+    assert_eq!(uris(report.clone()), [format!("{first_name}/fleet.json5"), format!("{first_name}/src/main.vk")]);
+    assert_eq!(report["items"][0]["items"][0]["code"], "V0004");
+    let event = json!({ "event": { "added": [folder(&second)], "removed": [folder(&first)] } });
+    server.notify("workspace/didChangeWorkspaceFolders", event).await;
+    server.indexed(2).await;
+    let report = server.request("workspace/diagnostic", json!({ "previousResultIds": [] })).await.unwrap();
+    let second_name = second.file_name().unwrap().to_string_lossy().to_string();
+    assert_eq!(uris(report), [format!("{second_name}/fleet.json5"), format!("{second_name}/src/lib.vk")]);
+    let _ = std::fs::remove_dir_all(first);
+    let _ = std::fs::remove_dir_all(second);
+}
+
+#[tokio::test]
+async fn external_libraries() {
+    let manifest = "{ name: 'app', dependencies: { utils: { path: '../../shared/utils' }, json: '1.0', std: '1.0' } }";
+    let root = temp_tree(
+        "libraries",
+        &[
+            ("ws/app/fleet.json5", manifest),
+            ("ws/app/src/main.vk", "class App {}\n"),
+            ("ws/vendor/json/fleet.json5", "{ name: 'json', version: '1.0.0' }"),
+            ("ws/vendor/unused/fleet.json5", "{ name: 'unused' }"),
+            ("shared/utils/fleet.json5", "{ name: 'utils', version: '0.1.0', dependencies: { deep: { path: '../deep' } } }"),
+            ("shared/deep/fleet.json5", "{ name: 'deep' }"),
+        ],
+    );
+    let folder = json!({ "uri": Url::from_file_path(root.join("ws")).unwrap(), "name": "ws" });
+    let params = json!({ "capabilities": { "window": { "workDoneProgress": true } }, "workspaceFolders": [folder] });
+    let (mut server, _) = TestServer::initialize_with(params).await;
+    server.indexed(1).await;
+    let libraries = server.request("valkyrie/externalLibraries", json!({})).await.unwrap();
+    let names: Vec<&str> = libraries.as_array().unwrap().iter().map(|library| library["name"].as_str().unwrap()).collect();
+    // transitive path dependencies and vendored copies are found, registry packages are not known
+    assert_eq!(names, ["deep", "json", "utils"]);
+    assert_eq!(libraries[1]["version"], "1.0.0");
+    assert_eq!(libraries[1]["language"], "valkyrie");
+    assert_eq!(libraries[2]["root"], json!(Url::from_directory_path(root.join("shared/utils")).unwrap()));
+    let uri = Url::from_file_path(root.join("shared/utils/fleet.json5")).unwrap();
+    let libraries = server.request("valkyrie/externalLibraries", json!({ "uri": uri })).await.unwrap();
+    assert_eq!(libraries.as_array().unwrap().len(), 1);
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[tokio::test]
+async fn symbol_index() {
+    let text = "namespace demo;\nclass Point {}\n";
+    let root = temp_tree("index", &[("fleet.json5", "{ name: 'demo' }"), ("src/point.vk", text)]);
+    let folder = json!({ "uri": Url::from_file_path(&root).unwrap(), "name": "demo" });
+    // the index is written once the folder is indexed
+    let params = json!({ "capabilities": { "window": { "workDoneProgress": true } }, "workspaceFolders": [folder] });
+    let (server, _) = TestServer::initialize_with(params).await;
+    server.indexed(1).await;
+    let progress: Vec<Value> = server.sent("$/progress").iter().map(|progress| progress["value"]["kind"].clone()).collect();
+    assert_eq!(progress, ["begin", "report", "end"]);
+    assert_eq!(server.sent("window/workDoneProgress/create").len(), 1);
+    let index = SymbolIndex::load(&root);
+    assert_eq!(index.len(), 1);
+    let entry = index.get(text).unwrap();
+    assert_eq!(entry.tree.items[0].namepath, ["demo", "Point"]);
+    // the cached declarations are used instead of parsing the text
+    let mut db = Database::default();
+    let mut tree = entry.tree.clone();
+    tree.namespace = vec!["cached".to_string()];
+    let file = db.set_file_items(Url::parse("file:///point.vk").unwrap(), text.to_string(), tree, entry.sources.clone());
+    assert_eq!(db.snapshot().item_tree(file).namespace, ["cached"]);
+    // a damaged file is dropped without an error
+    let path = SymbolIndex::path(&root);
+    let mut bytes = std::fs::read(&path).unwrap();
+    let last = bytes.len() - 2;
+    bytes[last] ^= 1;
+    std::fs::write(&path, bytes).unwrap();
+    assert!(SymbolIndex::load(&root).is_empty());
+    std::fs::write(&path, "{\"magic\":\"valkyrie-symbol-index\",\"format\":0}\n[]").unwrap();
+    assert!(SymbolIndex::load(&root).is_empty());
+    let _ = std::fs::remove_dir_all(root);
+}
+
+#[tokio::test]
+async fn find_references() {
+    let app =
+        "namespace demo.app;\nusing demo::shapes::Point;\nmicro run() {\n    let p = new Point(1, 2);\n    p.length();\n}\n";
+    let (mut server, result) = TestServer::initialize(json!({})).await;
+    assert_eq!(result["capabilities"]["referencesProvider"]["workDoneProgress"], true);
+    server.open("file:///shapes.vk", SAMPLE).await;
+    server.open("file:///app.vk", app).await;
+    let mut references_at = async |line: u32, character: u32, include_declaration: bool| {
+        let params = json!({
+            "textDocument": { "uri": "file:///app.vk" },
+            "position": { "line": line, "character": character },
+            "context": { "includeDeclaration": include_declaration },
+        });
+        server.request("textDocument/references", params).await.unwrap()
+    };
+    // a class is referenced across files, through the import
+    let references = references_at(3, 17, true).await;
+    let uris: Vec<&str> = references.as_array().unwrap().iter().map(|location| location["uri"].as_str().unwrap()).collect();
+    assert!(uris.contains(&"file:///shapes.vk") && uris.contains(&"file:///app.vk"), "{:?}", uris);
+    assert!(uris.len() >= 4, "{:?}", uris);
+    let without = references_at(3, 17, false).await;
+    assert_eq!(without.as_array().unwrap().len(), uris.len() - 1);
+    // a local only in its own file
+    let local = references_at(4, 4, true).await;
+    let lines: Vec<&Value> = local.as_array().unwrap().iter().map(|location| &location["range"]["start"]["line"]).collect();
+    assert_eq!(lines, [3, 4]);
+}
+
+#[tokio::test]
+async fn rename() {
+    let app = "namespace demo.app;\nusing demo::shapes::Point;\nmicro run() {\n    let p = new Point(1, 2);\n}\n";
+    let (mut server, result) = TestServer::initialize(json!({})).await;
+    assert_eq!(result["capabilities"]["renameProvider"]["prepareProvider"], true);
+    server.open("file:///shapes.vk", SAMPLE).await;
+    server.open("file:///app.vk", app).await;
+    let at = json!({ "textDocument": { "uri": "file:///app.vk" }, "position": { "line": 3, "character": 17 } });
+    let range = server.request("textDocument/prepareRename", at.clone()).await.unwrap();
+    assert_eq!(range, json!({ "start": { "line": 3, "character": 16 }, "end": { "line": 3, "character": 21 } }));
+    let mut nothing = at.clone();
+    nothing["position"] = json!({ "line": 3, "character": 14 });
+    assert_eq!(server.request("textDocument/prepareRename", nothing).await.unwrap(), Value::Null);
+    let mut rename = at.clone();
+    rename["newName"] = json!("Vector");
+    let edit = server.request("textDocument/rename", rename.clone()).await.unwrap();
+    assert_eq!(edit["changes"]["file:///app.vk"].as_array().unwrap().len(), 2);
+    assert!(edit["changes"]["file:///shapes.vk"].as_array().unwrap().len() >= 3);
+    rename["newName"] = json!("1st");
+    assert_eq!(server.request("textDocument/rename", rename).await.unwrap_err().code, ErrorCode::InvalidParams);
+}
+
+#[tokio::test]
+async fn workspace_symbols() {
+    let (mut server, result) = TestServer::initialize(json!({})).await;
+    assert_eq!(result["capabilities"]["workspaceSymbolProvider"], true);
+    server.open("file:///shapes.vk", SAMPLE).await;
+    let symbols = server.request("workspace/symbol", json!({ "query": "pnt" })).await.unwrap();
+    assert_eq!(symbols[0]["name"], "Point");
+    assert_eq!(symbols[0]["containerName"], "demo::shapes");
+    assert_eq!(symbols[0]["kind"], 5);
+    let symbols = server.request("workspace/symbol", json!({ "query": "AREA" })).await.unwrap();
+    let names: Vec<&str> = symbols.as_array().unwrap().iter().map(|symbol| symbol["name"].as_str().unwrap()).collect();
+    assert_eq!(names, ["area", "area"]);
+}
+
+#[tokio::test]
+async fn request_cancellation() {
+    let app = "namespace demo.app;\nusing demo::shapes::Point;\nmicro run() {\n    let p = new Point(1, 2);\n}\n";
+    let (mut server, _) = TestServer::initialize(json!({})).await;
+    server.open("file:///shapes.vk", SAMPLE).await;
+    server.open("file:///app.vk", app).await;
+    let at = json!({ "textDocument": { "uri": "file:///app.vk" }, "position": { "line": 3, "character": 17 } });
+    let mut params = at.clone();
+    params["context"] = json!({ "includeDeclaration": true });
+    // the client cancels before the result is ready
+    let pending = server.start("textDocument/references", params.clone()).await;
+    server.notify("$/cancelRequest", json!({ "id": server.id })).await;
+    assert_eq!(pending.await.unwrap_err().code, ErrorCode::RequestCancelled);
+    // an edit arrives while the references are searched
+    let mut pending = Box::pin(server.start("textDocument/references", params.clone()).await);
+    assert!(futures::poll!(&mut pending).is_pending());
+    let document = json!({ "uri": "file:///app.vk", "version": 2 });
+    let changes =
+        json!([{ "range": { "start": { "line": 4, "character": 1 }, "end": { "line": 4, "character": 1 } }, "text": "\n" }]);
+    server.notify("textDocument/didChange", json!({ "textDocument": document, "contentChanges": changes })).await;
+    assert_eq!(pending.await.unwrap_err().code, ErrorCode::ContentModified);
+    // an edit of a document the request does not read
+    server.open("file:///notes.vk", "namespace demo.notes;\n").await;
+    params["textDocument"]["uri"] = json!("file:///shapes.vk");
+    params["position"] = json!({ "line": 5, "character": 14 });
+    let mut pending = Box::pin(server.start("textDocument/references", params).await);
+    assert!(futures::poll!(&mut pending).is_pending());
+    let document = json!({ "uri": "file:///notes.vk", "version": 2 });
+    let changes =
+        json!([{ "range": { "start": { "line": 0, "character": 0 }, "end": { "line": 0, "character": 0 } }, "text": "\n" }]);
+    server.notify("textDocument/didChange", json!({ "textDocument": document, "contentChanges": changes })).await;
+    assert!(!pending.await.unwrap().as_array().unwrap().is_empty());
+}
+
+#[tokio::test]
+async fn configuration() {
+    let lints = json!({ "duplicate-type": "allow", "V0002": "warn", "no-such-lint": "deny", "syntax-error": "allow" });
+    let params = json!({
+        "capabilities": {
+            "textDocument": { "diagnostic": {} },
+            "workspace": { "configuration": true, "didChangeConfiguration": { "dynamicRegistration": true } },
+        },
+        "initializationOptions": { "valkyrie": { "lints": lints, "diagnostics": { "debounce": 0 }, "colour": true } },
+    });
+    let (mut server, _) = TestServer::initialize_with(params).await;
+    let messages = server.wait_sent("window/showMessage", 1).await;
+    let message = messages[0]["message"].as_str().unwrap();
+    assert!(
+        message.contains("unknown setting `valkyrie.colour`")
+            && message.contains("unknown lint `no-such-lint`")
+            && message.contains("`V0001` is not a lint"),
+        "{}",
+        message
+    );
+    assert_eq!(
+        server.wait_sent("client/registerCapability", 1).await[0]["registrations"][0]["method"],
+        "workspace/didChangeConfiguration"
+    );
+    // the levels of the lints change the severity or drop the diagnostic
+    let text = "namespace demo.app;\nusing demo::app::Missing;\nclass Point {}\nclass Point {}\n";
+    server.open("file:///app.vk", text).await;
+    let document = json!({ "textDocument": { "uri": "file:///app.vk" } });
+    let report = server.request("textDocument/diagnostic", document.clone()).await.unwrap();
+    let items = report["items"].as_array().unwrap();
+    let codes: Vec<&Value> = items.iter().map(|item| &item["code"]).collect();
+    assert_eq!(codes, ["V0002"]);
+    assert_eq!(items[0]["severity"], 2);
+    // the settings of the client override the initialization options, invalid values keep the old one
+    server
+        .respond("workspace/configuration", json!([{ "lints": { "V0002": "allow" }, "formatter": { "indentWidth": "four" } }]));
+    server.notify("workspace/didChangeConfiguration", json!({ "settings": null })).await;
+    let message = server.wait_sent("window/showMessage", 2).await[1]["message"].as_str().unwrap().to_string();
+    assert!(message.contains("invalid value for `valkyrie.formatter.indentWidth`"), "{}", message);
+    let report = server.request("textDocument/diagnostic", document).await.unwrap();
+    assert!(report["items"].as_array().unwrap().is_empty(), "{}", report);
+    let requested = server.sent("workspace/configuration");
+    assert_eq!(requested.last().unwrap()["items"][0]["section"], "valkyrie");
+}
+
+#[tokio::test]
+async fn goto_definition() {
+    let app = "namespace demo.app;\nusing demo::shapes::Point;\nmicro run() {\n    let p = new Point(1, 2);\n    p.length();\n    \
+               demo::shapes::Point;\n    twice();\n}\nmicro twice() {}\nmicro twice(x: i32) {}\n";
+    let shapes = LineIndex::new(SAMPLE, PositionEncoding::Utf16);
+    let index = LineIndex::new(app, PositionEncoding::Utf16);
+    let capabilities = json!({ "textDocument": { "definition": { "linkSupport": true } } });
+    for link_support in [false, true] {
+        let (mut server, _) = TestServer::initialize(if link_support { capabilities.clone() } else { json!({}) }).await;
+        server.open("file:///shapes.vk", SAMPLE).await;
+        server.open("file:///app.vk", app).await;
+        let mut definition_at = async |offset: usize| {
+            let params = json!({ "textDocument": { "uri": "file:///app.vk" }, "position": index.position(offset) });
+            server.request("textDocument/definition", params).await.unwrap()
+        };
+        let point = SAMPLE.find("Point<T>").unwrap();
+        let locations = definition_at(app.find("Point(1").unwrap()).await;
+        if link_support {
+            let link = &locations[0];
+            assert_eq!(link["targetUri"], "file:///shapes.vk");
+            assert_eq!(link["targetSelectionRange"], json!(shapes.range(point..point + 5)));
+            let class = SAMPLE.find("public class").unwrap();
+            assert_eq!(link["targetRange"]["start"], json!(shapes.position(class)));
+            let origin = app.find("Point(1").unwrap();
+            assert_eq!(link["originSelectionRange"], json!(index.range(origin..origin + 5)));
+            continue;
+        }
+        assert_eq!(locations, json!([{ "uri": "file:///shapes.vk", "range": shapes.range(point..point + 5) }]));
+        // the import, a local, a member, a namespace segment and a function declared twice
+        assert_eq!(definition_at(app.find("Point;").unwrap()).await[0]["range"], json!(shapes.range(point..point + 5)));
+        let local = app.find("p =").unwrap();
+        assert_eq!(definition_at(app.find("p.length").unwrap()).await[0]["range"], json!(index.range(local..local + 1)));
+        let length = SAMPLE.find("length(self)").unwrap();
+        let member = definition_at(app.find("length()").unwrap()).await;
+        assert_eq!(member, json!([{ "uri": "file:///shapes.vk", "range": shapes.range(length..length + 6) }]));
+        let namespace = SAMPLE.find("shapes;").unwrap();
+        let segment = definition_at(app.find("shapes::Point;\n    twice").unwrap()).await;
+        assert_eq!(segment, json!([{ "uri": "file:///shapes.vk", "range": shapes.range(namespace..namespace + 6) }]));
+        let twice = definition_at(app.find("twice();").unwrap()).await;
+        assert_eq!(twice.as_array().unwrap().len(), 2, "{}", twice);
+        assert_eq!(definition_at(app.find("(1, 2)").unwrap() + 1).await, Value::Null);
+    }
+}
+
+#[tokio::test]
+async fn goto_declaration() {
+    let app =
+        "namespace demo.app;\nusing demo::shapes::{Point as P};\nmicro run() {\n    let p = new P(1, 2);\n    p.area();\n}\n";
+    let shapes = LineIndex::new(SAMPLE, PositionEncoding::Utf16);
+    let index = LineIndex::new(app, PositionEncoding::Utf16);
+    let (mut server, _) = TestServer::initialize(json!({})).await;
+    server.open("file:///shapes.vk", SAMPLE).await;
+    server.open("file:///app.vk", app).await;
+    let mut goto = async |method: &'static str, uri: &str, position: Position| {
+        let params = json!({ "textDocument": { "uri": uri }, "position": position });
+        server.request(method, params).await.unwrap()
+    };
+    // a name brought in by an import is declared by the import, defined by the class
+    let alias = app.find("P(1").unwrap();
+    let declaration = goto("textDocument/declaration", "file:///app.vk", index.position(alias)).await;
+    let import = app.find("as P").unwrap() + 3;
+    assert_eq!(declaration, json!([{ "uri": "file:///app.vk", "range": index.range(import..import + 1) }]));
+    let definition = goto("textDocument/definition", "file:///app.vk", index.position(alias)).await;
+    assert_eq!(definition[0]["uri"], "file:///shapes.vk");
+    // an implemented method is declared by the abstract method of the trait
+    let abstract_area = SAMPLE.find("area(self) -> f64;").unwrap();
+    let trait_area = json!([{ "uri": "file:///shapes.vk", "range": shapes.range(abstract_area..abstract_area + 4) }]);
+    let implemented = SAMPLE.find("area(self) -> f64 {").unwrap();
+    assert_eq!(goto("textDocument/declaration", "file:///shapes.vk", shapes.position(implemented)).await, trait_area);
+    let definition = goto("textDocument/definition", "file:///shapes.vk", shapes.position(implemented)).await;
+    assert_eq!(definition[0]["range"], json!(shapes.range(implemented..implemented + 4)));
+    // other symbols are declared where they are defined
+    let local = app.find("p =").unwrap();
+    let declaration = goto("textDocument/declaration", "file:///app.vk", index.position(app.find("p.area").unwrap())).await;
+    assert_eq!(declaration, json!([{ "uri": "file:///app.vk", "range": index.range(local..local + 1) }]));
+}
+
+#[tokio::test]
+async fn goto_type_definition() {
+    let app = "namespace demo.app;\nusing demo::shapes::{Point, Shape};\nclass Circle {\n    center: Point<f64>,\n}\nclass Circle {}\n\
+               micro run(shape: Point<Shape>) {\n    let p = new Point(1, 2);\n    p.length();\n    let c = new Circle();\n}\n";
+    let shapes = LineIndex::new(SAMPLE, PositionEncoding::Utf16);
+    let index = LineIndex::new(app, PositionEncoding::Utf16);
+    let (mut server, result) = TestServer::initialize(json!({})).await;
+    assert_eq!(result["capabilities"]["typeDefinitionProvider"], true);
+    server.open("file:///shapes.vk", SAMPLE).await;
+    server.open("file:///app.vk", app).await;
+    let mut type_at = async |offset: usize| {
+        let params = json!({ "textDocument": { "uri": "file:///app.vk" }, "position": index.position(offset) });
+        server.request("textDocument/typeDefinition", params).await.unwrap()
+    };
+    let point = SAMPLE.find("Point<T>").unwrap();
+    let point = json!({ "uri": "file:///shapes.vk", "range": shapes.range(point..point + 5) });
+    let shape = SAMPLE.find("trait Shape").unwrap() + 6;
+    let shape = json!({ "uri": "file:///shapes.vk", "range": shapes.range(shape..shape + 5) });
+    // a local and a field lead to the class of their value, primitives to nothing
+    assert_eq!(type_at(app.find("p.length").unwrap()).await, json!([point]));
+    assert_eq!(type_at(app.find("length()").unwrap()).await, Value::Null);
+    assert_eq!(type_at(app.find("center").unwrap()).await, json!([point]));
+    // a generic type leads to the container and to the element
+    assert_eq!(type_at(app.find("shape:").unwrap()).await, json!([point, shape]));
+    // a type declared twice leads to both declarations
+    let circles: Vec<_> = app.match_indices("Circle {").map(|(start, _)| index.range(start..start + 6)).collect();
+    let circles: Vec<_> = circles.into_iter().map(|range| json!({ "uri": "file:///app.vk", "range": range })).collect();
+    assert_eq!(type_at(app.find("c =").unwrap()).await, json!(circles));
+    assert_eq!(type_at(app.find("run").unwrap()).await, Value::Null);
+}
+
+#[tokio::test]
+async fn goto_implementation() {
+    let app = "namespace demo.app;\nusing demo::shapes::Shape;\ntrait Solid: Shape {}\nclass Cube: Solid {\n    \
+               area(self) -> f64 { 6.0 }\n}\n";
+    let shapes = LineIndex::new(SAMPLE, PositionEncoding::Utf16);
+    let index = LineIndex::new(app, PositionEncoding::Utf16);
+    let (mut server, result) = TestServer::initialize(json!({})).await;
+    assert_eq!(result["capabilities"]["implementationProvider"], true);
+    server.open("file:///shapes.vk", SAMPLE).await;
+    server.open("file:///app.vk", app).await;
+    let mut implementation_at = async |offset: usize| {
+        let params = json!({ "textDocument": { "uri": "file:///shapes.vk" }, "position": shapes.position(offset) });
+        server.request("textDocument/implementation", params).await.unwrap()
+    };
+    // the results are in no particular order
+    let sorted = |locations: Value| {
+        let mut locations = locations.as_array().unwrap().clone();
+        locations.sort_by_key(|location| location.to_string());
+        locations
+    };
+    // the class, the imply block and the class implementing a sub trait in another file
+    let point = SAMPLE.find("Point<T>").unwrap();
+    let imply = SAMPLE.find("imply").unwrap()..SAMPLE.find("}\n\nmicro").unwrap() + 1;
+    let cube = app.find("Cube").unwrap();
+    let implementors = json!([
+        { "uri": "file:///shapes.vk", "range": shapes.range(point..point + 5) },
+        { "uri": "file:///shapes.vk", "range": shapes.range(imply) },
+        { "uri": "file:///app.vk", "range": index.range(cube..cube + 4) },
+    ]);
+    assert_eq!(sorted(implementation_at(SAMPLE.find("trait Shape").unwrap() + 6).await), sorted(implementors));
+    // the overrides of a trait method
+    let implied = SAMPLE.find("area(self) -> f64 {").unwrap();
+    let area = app.find("area").unwrap();
+    let overrides = json!([
+        { "uri": "file:///shapes.vk", "range": shapes.range(implied..implied + 4) },
+        { "uri": "file:///app.vk", "range": index.range(area..area + 4) },
+    ]);
+    assert_eq!(sorted(implementation_at(SAMPLE.find("area(self) -> f64;").unwrap()).await), sorted(overrides));
+    assert_eq!(implementation_at(SAMPLE.find("Point<T>").unwrap()).await, Value::Null);
+}
+
+#[tokio::test]
+async fn hover() {
+    let (mut server, _) = TestServer::initialize(json!({})).await;
+    server.open("file:///shapes.vk", SAMPLE).await;
+    let index = LineIndex::new(SAMPLE, PositionEncoding::Utf16);
+    let mut hover_at = async |offset: usize| {
+        let position = index.position(offset);
+        let params = json!({ "textDocument": { "uri": "file:///shapes.vk" }, "position": position });
+        server.request("textDocument/hover", params).await.unwrap()
+    };
+    let point = SAMPLE.find("Point(1").unwrap() + 2;
+    let hover = hover_at(point).await;
+    let value = hover["contents"]["value"].as_str().unwrap();
+    assert_eq!(
+        value,
+        "*class* `demo::shapes::Point`\n\n```valkyrie\npublic class Point<T>: Shape\n```\n\n---\n\nA point in the plane."
+    );
+    assert_eq!(hover["range"], json!(index.range(point - 2..point + 3)));
+    let local = SAMPLE.find("p.length").unwrap();
+    let value = hover_at(local).await["contents"]["value"].as_str().unwrap().to_string();
+    assert_eq!(value, "*local* `p`\n\n```valkyrie\nlet p: Point\n```");
+    let keyword = hover_at(SAMPLE.find("micro").unwrap()).await;
+    assert!(keyword["contents"]["value"].as_str().unwrap().starts_with("*keyword* `micro`\n\nDeclares a function."));
+    let power = SAMPLE.find("**").unwrap();
+    let hover = hover_at(power + 1).await;
+    let value = hover["contents"]["value"].as_str().unwrap();
+    assert!(value.starts_with("*operator* `**` power"), "{}", value);
+    assert!(value.contains("- precedence: 15\n- associativity: right\n- desugars to: `std::ops::Pow::pow`"));
+    assert_eq!(hover["range"], json!(index.range(power..power + 2)));
+    let value = hover_at(SAMPLE.find("> pi").unwrap()).await["contents"]["value"].as_str().unwrap().to_string();
+    assert!(value.starts_with("*operator* `>` greater than"), "{}", value);
+    assert_eq!(hover_at(SAMPLE.find("{ print").unwrap() + 1).await, Value::Null);
+}
+
+#[tokio::test]
+async fn document_renderers() {
+    let write = |format: DocumentFormat| {
+        let mut out = format.renderer(ColorSchema::DARK);
+        out.write_modifiers(&["public", "class"]);
+        out.write_class("Point<T>");
+        out.write_newline();
+        out.write_bad("?");
+        out.finish()
+    };
+    assert_eq!(write(DocumentFormat::Markdown), "*public* *class* `Point<T>`\n~~?~~");
+    // text is escaped, names and code are fenced by longer runs of backticks than they contain
+    let mut markdown = DocumentFormat::Markdown.renderer(ColorSchema::DARK);
+    markdown.write_text("a_b *c* [d](e) <f>");
+    markdown.write_attribute("`raw`");
+    markdown.write_newline();
+    markdown.write_code("let s = ```;");
+    assert_eq!(markdown.finish(), "a\\_b \\*c\\* \\[d\\](e) \\<f\\>`` `raw` ``\n````valkyrie\nlet s = ```;\n````");
+    assert_eq!(write(DocumentFormat::Plain), "public class Point<T>\n?");
+    assert_eq!(
+        write(DocumentFormat::Html),
+        "<span style=\"color: #C679DD\">public</span> <span style=\"color: #C679DD\">class</span> \
+         <span style=\"color: #E5C17C\">Point&lt;T&gt;</span><br/><span style=\"color: #F44747\">?</span>"
+    );
+    assert_eq!(
+        write(DocumentFormat::Ansi),
+        "\x1b[35mpublic\x1b[0m \x1b[35mclass\x1b[0m \x1b[33mPoint<T>\x1b[0m\n\x1b[31m?\x1b[0m"
+    );
+    // clients preferring plain text get no markup
+    let capabilities = json!({ "textDocument": { "hover": { "contentFormat": ["plaintext", "markdown"] } } });
+    let (mut server, _) = TestServer::initialize(capabilities).await;
+    server.open("file:///shapes.vk", SAMPLE).await;
+    let index = LineIndex::new(SAMPLE, PositionEncoding::Utf16);
+    let position = index.position(SAMPLE.find("Point(1").unwrap());
+    let params = json!({ "textDocument": { "uri": "file:///shapes.vk" }, "position": position });
+    let hover = server.request("textDocument/hover", params).await.unwrap();
+    assert_eq!(hover["contents"]["kind"], "plaintext");
+    assert_eq!(
+        hover["contents"]["value"],
+        "class demo::shapes::Point\n\npublic class Point<T>: Shape\n\n---\n\nA point in the plane."
+    );
+}
+
+#[test]
+fn color_schema() {
+    let vscode = r##"{
+        // comments and trailing commas are allowed
+        "type": "light",
+        "tokenColors": [
+            { "scope": "keyword", "settings": { "foreground": "#0000FF" } },
+            { "scope": ["keyword.operator", "comment"], "settings": { "foreground": "#777777" } },
+            { "scope": "entity.name.function, entity.name", "settings": { "foreground": "#795E26" } },
+        ],
+        "semanticTokenColors": { "class": { "foreground": "#267F99" } },
+    }"##;
+    let jetbrains = r#"<?xml version="1.0" encoding="UTF-8"?>
+<scheme name="Custom" version="142" parent_scheme="Darcula">
+  <!-- <option name="DEFAULT_CLASS_NAME"> -->
+  <colors>
+    <option name="CARET_COLOR" value="bbbbbb" />
+  </colors>
+  <attributes>
+    <option name="DEFAULT_KEYWORD">
+      <value>
+        <option name="FOREGROUND" value="cc7832" />
+        <option name="FONT_TYPE" value="1" />
+      </value>
+    </option>
+    <option name="DEFAULT_NUMBER" baseAttributes="DEFAULT_CONSTANT" />
+    <option name="ERRORS_ATTRIBUTES">
+      <value>
+        <option name="EFFECT_COLOR" value="ff" />
+        <option name="EFFECT_TYPE" value="2" />
+      </value>
+    </option>
+  </attributes>
+</scheme>
+"#;
+    let files = [("light.json", vscode), ("Custom.icls", jetbrains), ("broken.json", "[]"), ("broken.icls", "<colors/>")];
+    let root = temp_tree("themes", &files);
+    let schema = ColorSchema::load_theme(&root.join("light.json")).unwrap();
+    assert_eq!(schema.keyword, Color::rgb(0, 0, 0xFF));
+    // the most specific scope wins
+    assert_eq!(schema.operator.to_string(), "#777777");
+    assert_eq!(schema.function.to_string(), "#795E26");
+    assert_eq!(schema.namespace.to_string(), "#795E26");
+    assert_eq!(schema.class.to_string(), "#267F99");
+    assert_eq!(schema.literal, ColorSchema::LIGHT.literal);
+    let schema = ColorSchema::load_theme(&root.join("Custom.icls")).unwrap();
+    assert_eq!(schema, ColorSchema { keyword: "#CC7832".parse().unwrap(), bad: Color::rgb(0, 0, 0xFF), ..ColorSchema::DARK });
+    for broken in ["broken.json", "broken.icls"] {
+        let error = ColorSchema::load_theme(&root.join(broken)).unwrap_err();
+        assert!(matches!(error.kind(), ValkyrieErrorKind::ThemeError { .. }), "{}", error);
+    }
+    // the settings pick a preset or a theme file, then override single categories
+    let section =
+        json!({ "theme": { "kind": "light", "colors": { "keyword": "#123456", "kyeword": "#000000", "class": "red" } } });
+    let (settings, problems) = Settings::default().merge(&section);
+    assert_eq!(problems.len(), 2, "{:?}", problems);
+    assert!(problems[0].contains("unknown color category `kyeword`"), "{:?}", problems);
+    assert!(problems[1].contains("invalid value for `valkyrie.theme.colors.class`"), "{:?}", problems);
+    let schema = settings.theme.schema(&[]).unwrap();
+    assert_eq!(schema, ColorSchema { keyword: Color::rgb(0x12, 0x34, 0x56), ..ColorSchema::LIGHT });
+    // theme files are relative to the workspace folders
+    assert_eq!(Settings::default().theme.schema(&[]).unwrap(), ColorSchema::DARK);
+    let (settings, _) = Settings::default().merge(&json!({ "theme": { "path": "Custom.icls" } }));
+    let roots = [root.join("missing"), root.clone()];
+    assert_eq!(settings.theme.schema(&roots).unwrap().keyword.to_string(), "#CC7832");
+    let mut html = DocumentFormat::Html.renderer(schema);
+    html.write_keyword("class");
+    assert_eq!(html.finish(), "<span style=\"color: #123456\">class</span>");
+}
+
+#[test]
+fn operator_catalog() {
+    for kind in SyntaxKind::KEYWORDS {
+        assert!(KeywordDoc::find(*kind).is_some(), "{:?} is not documented", kind);
+    }
+    // the catalog levels agree with the parser on how `a op b op c` groups
+    let infix: Vec<&OperatorDoc> =
+        OPERATOR_DOCS.iter().filter(|doc| doc.fixity == Fixity::Infix && doc.symbol != "is" && doc.symbol != "as").collect();
+    for first in &infix {
+        for second in &infix {
+            let text = format!("micro f() {{ a {} b {} c }}", first.symbol, second.symbol);
+            let parse = Parse::new(&text);
+            assert!(parse.errors().is_empty(), "{}: {:?}", text, parse.errors());
+            let outer = parse.syntax().descendants().find(|node| node.kind() == SyntaxKind::BinaryExpr).unwrap();
+            let grouped_left = outer.children().next().unwrap().kind() == SyntaxKind::BinaryExpr;
+            let expected = match first.precedence.cmp(&second.precedence) {
+                std::cmp::Ordering::Greater => true,
+                std::cmp::Ordering::Less => false,
+                std::cmp::Ordering::Equal => first.associativity == Associativity::Left,
+            };
+            assert_eq!(grouped_left, expected, "{}", text);
+        }
+    }
+}